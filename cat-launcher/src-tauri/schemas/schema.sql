@@ -82,6 +82,21 @@ CREATE TABLE IF NOT EXISTS play_time (
 -- and speeds up filtering play time by game_variant and version.
 CREATE UNIQUE INDEX IF NOT EXISTS idx_play_time_game_variant_version ON play_time (game_variant, version);
 
+-- This table stores every play session measured by the launcher.
+-- The duration of each session is also added to play_time.
+CREATE TABLE IF NOT EXISTS play_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_variant TEXT NOT NULL,
+    version TEXT NOT NULL,
+    world TEXT,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER NOT NULL CHECK (ended_at >= started_at),
+    exit_code INTEGER,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_play_sessions_game_variant_started_at ON play_sessions (game_variant, started_at);
+
 -- This table stores the order of the game variants in the launcher.
 CREATE TABLE IF NOT EXISTS game_variant_order (
     game_variant TEXT PRIMARY KEY,
@@ -181,4 +196,305 @@ CREATE TABLE IF NOT EXISTS settings (
 CREATE TABLE IF NOT EXISTS color_settings (
     _id INTEGER PRIMARY KEY DEFAULT 1 CHECK(_id = 1),
     theme_path TEXT
-);
\ No newline at end of file
+);
+
+-- This table stores every game release that is currently installed on disk,
+-- so that several versions of a variant can be kept side by side.
+CREATE TABLE IF NOT EXISTS installed_releases (
+    game_variant TEXT NOT NULL,
+    version TEXT NOT NULL,
+    size_in_bytes INTEGER NOT NULL CHECK (size_in_bytes >= 0),
+    installed_at INTEGER NOT NULL,
+    is_pinned INTEGER NOT NULL DEFAULT 0,
+    -- Whether the downloaded archive matched a published digest.
+    is_verified INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (game_variant, version),
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores how many installed releases are kept for each game variant.
+-- A NULL keep_count means that every installed release is kept.
+CREATE TABLE IF NOT EXISTS release_retention_policies (
+    game_variant TEXT PRIMARY KEY,
+    keep_count INTEGER CHECK (keep_count IS NULL OR keep_count >= 1),
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores named launch profiles for each game variant. The list and map
+-- columns hold JSON so that a profile can be stored and loaded as a single row.
+CREATE TABLE IF NOT EXISTS launch_profiles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_variant TEXT NOT NULL,
+    name TEXT NOT NULL,
+    extra_args TEXT NOT NULL DEFAULT '[]',
+    env_vars TEXT NOT NULL DEFAULT '{}',
+    wrapper_command TEXT NOT NULL DEFAULT '[]',
+    working_dir TEXT,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This unique index prevents two profiles of a variant from sharing a name.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_launch_profiles_game_variant_name ON launch_profiles (game_variant, name);
+
+-- This table stores the named user data profiles of each game variant. Every
+-- profile has its own user data directory. The unnamed default profile uses
+-- the original user data directory and is not stored here.
+CREATE TABLE IF NOT EXISTS data_profiles (
+    game_variant TEXT NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    created_at INTEGER NOT NULL,
+    PRIMARY KEY (game_variant, name),
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores the selected data profile for each game variant.
+-- Variants without a row use the default profile.
+CREATE TABLE IF NOT EXISTS active_data_profiles (
+    game_variant TEXT PRIMARY KEY,
+    profile_name TEXT NOT NULL,
+    FOREIGN KEY (game_variant, profile_name) REFERENCES data_profiles (game_variant, name) ON DELETE CASCADE
+);
+
+-- This table stores the data profile an automatic backup was taken from.
+-- Backups without a row were taken from the default profile.
+-- Rows are kept when the profile is deleted, so that the backup is not restored into another profile.
+CREATE TABLE IF NOT EXISTS backup_data_profiles (
+    backup_id INTEGER PRIMARY KEY,
+    profile_name TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES backups (id) ON DELETE CASCADE
+);
+
+-- This table stores the data profile a manual backup was taken from.
+-- Backups without a row were taken from the default profile.
+-- Rows are kept when the profile is deleted, so that the backup is not restored into another profile.
+CREATE TABLE IF NOT EXISTS manual_backup_data_profiles (
+    backup_id INTEGER PRIMARY KEY,
+    profile_name TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES manual_backups (id) ON DELETE CASCADE
+);
+
+-- These tables store the mods, tilesets and soundpacks installed into named data
+-- profiles. Installations into the default profile are stored in installed_mods,
+-- installed_tilesets and installed_soundpacks.
+CREATE TABLE IF NOT EXISTS data_profile_installed_mods (
+    mod_id TEXT NOT NULL,
+    game_variant TEXT NOT NULL,
+    profile_name TEXT NOT NULL,
+    PRIMARY KEY (mod_id, game_variant, profile_name),
+    FOREIGN KEY (game_variant, profile_name) REFERENCES data_profiles (game_variant, name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS data_profile_installed_tilesets (
+    tileset_id TEXT NOT NULL,
+    game_variant TEXT NOT NULL,
+    profile_name TEXT NOT NULL,
+    PRIMARY KEY (tileset_id, game_variant, profile_name),
+    FOREIGN KEY (game_variant, profile_name) REFERENCES data_profiles (game_variant, name) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS data_profile_installed_soundpacks (
+    soundpack_id TEXT NOT NULL,
+    game_variant TEXT NOT NULL,
+    profile_name TEXT NOT NULL,
+    PRIMARY KEY (soundpack_id, game_variant, profile_name),
+    FOREIGN KEY (game_variant, profile_name) REFERENCES data_profiles (game_variant, name) ON DELETE CASCADE
+);
+
+-- This table stores the crash reports collected after the game exited abnormally.
+-- The collected artifacts live in the CrashReports directory, keyed by id.
+CREATE TABLE IF NOT EXISTS crash_reports (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_variant TEXT NOT NULL,
+    release_version TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    exit_kind TEXT NOT NULL,
+    exit_code INTEGER,
+    exit_signal INTEGER,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_crash_reports_game_variant_timestamp ON crash_reports (game_variant, timestamp);
+
+-- This table stores the log files written for every game session.
+-- ended_at is NULL while the session is running or if the launcher quit before the game.
+CREATE TABLE IF NOT EXISTS session_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    game_variant TEXT NOT NULL,
+    release_version TEXT NOT NULL,
+    started_at INTEGER NOT NULL,
+    ended_at INTEGER,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_logs_game_variant_started_at ON session_logs (game_variant, started_at);
+
+-- This table stores how many session logs are kept per game variant, and for how long.
+-- A NULL limit means unlimited. Variants without a row use the default policy.
+CREATE TABLE IF NOT EXISTS session_log_retention_policies (
+    game_variant TEXT PRIMARY KEY,
+    max_count INTEGER,
+    max_age_days INTEGER,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- These tables store which directories of the user data directory a backup contains,
+-- as a JSON list of backup scopes. Backups without a row only contain the save directory.
+CREATE TABLE IF NOT EXISTS backup_scopes (
+    backup_id INTEGER PRIMARY KEY,
+    scopes TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES backups (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS manual_backup_scopes (
+    backup_id INTEGER PRIMARY KEY,
+    scopes TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES manual_backups (id) ON DELETE CASCADE
+);
+
+-- This table stores the release that was active when a manual backup was created.
+-- Backups without a row were created before this was recorded, or without an active release.
+CREATE TABLE IF NOT EXISTS manual_backup_releases (
+    backup_id INTEGER PRIMARY KEY,
+    release_version TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES manual_backups (id) ON DELETE CASCADE
+);
+
+-- This table stores which directories are backed up for each game variant and kind of
+-- backup, as a JSON list of backup scopes. Variants without a row only back up saves.
+CREATE TABLE IF NOT EXISTS backup_scope_settings (
+    game_variant TEXT NOT NULL,
+    backup_kind TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    PRIMARY KEY (game_variant, backup_kind),
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores the automatic backups that retention never removes.
+CREATE TABLE IF NOT EXISTS pinned_backups (
+    backup_id INTEGER PRIMARY KEY,
+    FOREIGN KEY (backup_id) REFERENCES backups (id) ON DELETE CASCADE
+);
+
+-- This table stores which automatic backups are kept for each game variant.
+-- A NULL limit means unlimited. The keep_* columns hold the grandfather-father-son
+-- rotation and are either all NULL or all set. Variants without a row use the default policy.
+CREATE TABLE IF NOT EXISTS backup_retention_policies (
+    game_variant TEXT PRIMARY KEY,
+    max_count INTEGER CHECK (max_count IS NULL OR max_count >= 1),
+    max_total_size_in_bytes INTEGER CHECK (max_total_size_in_bytes IS NULL OR max_total_size_in_bytes >= 0),
+    max_age_days INTEGER CHECK (max_age_days IS NULL OR max_age_days >= 0),
+    keep_last INTEGER,
+    keep_daily_days INTEGER,
+    keep_weekly_weeks INTEGER,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores what caused an automatic backup to be taken.
+-- Backups without a row were taken before the game was launched.
+CREATE TABLE IF NOT EXISTS backup_triggers (
+    backup_id INTEGER PRIMARY KEY,
+    backup_trigger TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES backups (id) ON DELETE CASCADE
+);
+
+-- This table stores when automatic backups are taken for each game variant.
+-- A NULL periodic_interval_minutes disables periodic backups while the game runs.
+-- Variants without a row only back up before the game is launched.
+CREATE TABLE IF NOT EXISTS backup_trigger_settings (
+    game_variant TEXT PRIMARY KEY,
+    before_launch INTEGER NOT NULL,
+    on_exit INTEGER NOT NULL,
+    periodic_interval_minutes INTEGER CHECK (periodic_interval_minutes IS NULL OR periodic_interval_minutes >= 1),
+    before_update INTEGER NOT NULL,
+    FOREIGN KEY (game_variant) REFERENCES variants (name) ON DELETE CASCADE
+);
+
+-- This table stores what an automatic backup contained when it was created,
+-- so that it can be verified later. The sha256 is that of the backup's manifest,
+-- or of its archive for backups taken before the chunk store was introduced.
+-- Backups taken before this was recorded have no row.
+CREATE TABLE IF NOT EXISTS backup_integrity (
+    backup_id INTEGER PRIMARY KEY,
+    size_in_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    file_count INTEGER NOT NULL,
+    worlds TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES backups (id) ON DELETE CASCADE
+);
+
+-- This table stores what a manual backup archive contained when it was created.
+CREATE TABLE IF NOT EXISTS manual_backup_integrity (
+    backup_id INTEGER PRIMARY KEY,
+    size_in_bytes INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    file_count INTEGER NOT NULL,
+    worlds TEXT NOT NULL,
+    FOREIGN KEY (backup_id) REFERENCES manual_backups (id) ON DELETE CASCADE
+);
+
+-- This table stores the target backups are mirrored to, such as a synced folder or a WebDAV server.
+-- It has at most one row. The config is a JSON-serialized BackupTargetConfig.
+CREATE TABLE IF NOT EXISTS backup_target (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    config TEXT NOT NULL
+);
+
+-- This table stores the state of copying each automatic or manual backup to the backup target.
+-- It is cleared when the target changes.
+CREATE TABLE IF NOT EXISTS backup_uploads (
+    backup_kind TEXT NOT NULL,
+    backup_id INTEGER NOT NULL,
+    remote_name TEXT NOT NULL,
+    status TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    last_error TEXT,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (backup_kind, backup_id)
+);
+
+-- This table stores the folder or WebDAV remote saves are synced with.
+-- It has at most one row. The config is a JSON-serialized BackupTargetConfig.
+CREATE TABLE IF NOT EXISTS save_sync_remote (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    config TEXT NOT NULL
+);
+
+-- This table stores the state of each world at its last sync, so that changes on both sides can be detected.
+-- data_profile is empty for the default user data directory. It is cleared when the remote changes.
+CREATE TABLE IF NOT EXISTS save_sync_worlds (
+    game_variant TEXT NOT NULL,
+    data_profile TEXT NOT NULL,
+    world TEXT NOT NULL,
+    version_vector TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    synced_at INTEGER NOT NULL,
+    PRIMARY KEY (game_variant, data_profile, world)
+);
+
+-- This table stores the release each world was last played with, so that launching it with an older release can be caught.
+-- data_profile is empty for the default user data directory. release_created_at orders releases, as version names cannot be compared.
+CREATE TABLE IF NOT EXISTS world_versions (
+    game_variant TEXT NOT NULL,
+    data_profile TEXT NOT NULL,
+    world TEXT NOT NULL,
+    release_version TEXT NOT NULL,
+    release_created_at INTEGER NOT NULL,
+    played_at INTEGER NOT NULL,
+    PRIMARY KEY (game_variant, data_profile, world)
+);
+
+-- This table stores where each installed third-party mod was installed from, so that updates can be detected.
+-- data_profile is empty for the default user data directory. Rows are only used while the mod is in
+-- installed_mods or data_profile_installed_mods. commit_sha is set for mods tracked through GitHub commits,
+-- etag for other archives whose server sent one.
+CREATE TABLE IF NOT EXISTS installed_mod_sources (
+    mod_id TEXT NOT NULL,
+    game_variant TEXT NOT NULL,
+    data_profile TEXT NOT NULL,
+    source_url TEXT NOT NULL,
+    commit_sha TEXT,
+    etag TEXT,
+    installed_at INTEGER NOT NULL,
+    modinfo_version TEXT,
+    PRIMARY KEY (mod_id, game_variant, data_profile)
+);
@@ -0,0 +1,89 @@
+use crate::backup_settings::repository::{
+  BackupKind, BackupRetentionPolicy, BackupSettingsRepository,
+  BackupSettingsRepositoryError, BackupTriggerSettings,
+};
+use crate::backups::scopes::BackupScope;
+use crate::variants::GameVariant;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupSettingsError {
+  #[error("failed to access backup settings: {0}")]
+  Repository(#[from] BackupSettingsRepositoryError),
+
+  #[error("at least one backup scope must be selected")]
+  NoScopes,
+
+  #[error("the maximum number of backups must be at least 1")]
+  InvalidMaxCount,
+
+  #[error("the periodic backup interval must be at least 1 minute")]
+  InvalidInterval,
+}
+
+pub async fn get_backup_scopes(
+  variant: &GameVariant,
+  backup_kind: &BackupKind,
+  repository: &dyn BackupSettingsRepository,
+) -> Result<Vec<BackupScope>, BackupSettingsError> {
+  Ok(repository.get_backup_scopes(variant, backup_kind).await?)
+}
+
+pub async fn set_backup_scopes(
+  variant: &GameVariant,
+  backup_kind: &BackupKind,
+  scopes: &[BackupScope],
+  repository: &dyn BackupSettingsRepository,
+) -> Result<(), BackupSettingsError> {
+  let mut scopes = scopes.to_vec();
+  scopes.sort();
+  scopes.dedup();
+
+  if scopes.is_empty() {
+    return Err(BackupSettingsError::NoScopes);
+  }
+
+  repository
+    .set_backup_scopes(variant, backup_kind, &scopes)
+    .await?;
+  Ok(())
+}
+
+pub async fn get_backup_retention_policy(
+  variant: &GameVariant,
+  repository: &dyn BackupSettingsRepository,
+) -> Result<BackupRetentionPolicy, BackupSettingsError> {
+  Ok(repository.get_retention_policy(variant).await?)
+}
+
+pub async fn set_backup_retention_policy(
+  variant: &GameVariant,
+  policy: &BackupRetentionPolicy,
+  repository: &dyn BackupSettingsRepository,
+) -> Result<(), BackupSettingsError> {
+  if policy.max_count == Some(0) {
+    return Err(BackupSettingsError::InvalidMaxCount);
+  }
+
+  repository.set_retention_policy(variant, policy).await?;
+  Ok(())
+}
+
+pub async fn get_backup_trigger_settings(
+  variant: &GameVariant,
+  repository: &dyn BackupSettingsRepository,
+) -> Result<BackupTriggerSettings, BackupSettingsError> {
+  Ok(repository.get_trigger_settings(variant).await?)
+}
+
+pub async fn set_backup_trigger_settings(
+  variant: &GameVariant,
+  settings: &BackupTriggerSettings,
+  repository: &dyn BackupSettingsRepository,
+) -> Result<(), BackupSettingsError> {
+  if settings.periodic_interval_minutes == Some(0) {
+    return Err(BackupSettingsError::InvalidInterval);
+  }
+
+  repository.set_trigger_settings(variant, settings).await?;
+  Ok(())
+}
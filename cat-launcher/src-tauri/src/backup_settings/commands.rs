@@ -0,0 +1,99 @@
+use strum::IntoStaticStr;
+use tauri::{command, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::backup_settings::backup_settings::{
+  get_backup_retention_policy as get_backup_retention_policy_impl,
+  get_backup_scopes as get_backup_scopes_impl,
+  get_backup_trigger_settings as get_backup_trigger_settings_impl,
+  set_backup_retention_policy as set_backup_retention_policy_impl,
+  set_backup_scopes as set_backup_scopes_impl,
+  set_backup_trigger_settings as set_backup_trigger_settings_impl,
+  BackupSettingsError,
+};
+use crate::backup_settings::repository::sqlite_backup_settings_repository::SqliteBackupSettingsRepository;
+use crate::backup_settings::repository::{
+  BackupKind, BackupRetentionPolicy, BackupTriggerSettings,
+};
+use crate::backups::scopes::BackupScope;
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum BackupSettingsCommandError {
+  #[error("failed to access backup settings: {0}")]
+  BackupSettings(#[from] BackupSettingsError),
+}
+
+#[command]
+pub async fn get_backup_scopes(
+  variant: GameVariant,
+  backup_kind: BackupKind,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<Vec<BackupScope>, BackupSettingsCommandError> {
+  let scopes =
+    get_backup_scopes_impl(&variant, &backup_kind, &*repository)
+      .await?;
+  Ok(scopes)
+}
+
+#[command]
+pub async fn set_backup_scopes(
+  variant: GameVariant,
+  backup_kind: BackupKind,
+  scopes: Vec<BackupScope>,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<(), BackupSettingsCommandError> {
+  set_backup_scopes_impl(
+    &variant,
+    &backup_kind,
+    &scopes,
+    &*repository,
+  )
+  .await?;
+  Ok(())
+}
+
+#[command]
+pub async fn get_backup_retention_policy(
+  variant: GameVariant,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<BackupRetentionPolicy, BackupSettingsCommandError> {
+  let policy =
+    get_backup_retention_policy_impl(&variant, &*repository).await?;
+  Ok(policy)
+}
+
+#[command]
+pub async fn set_backup_retention_policy(
+  variant: GameVariant,
+  policy: BackupRetentionPolicy,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<(), BackupSettingsCommandError> {
+  set_backup_retention_policy_impl(&variant, &policy, &*repository)
+    .await?;
+  Ok(())
+}
+
+#[command]
+pub async fn get_backup_trigger_settings(
+  variant: GameVariant,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<BackupTriggerSettings, BackupSettingsCommandError> {
+  let settings =
+    get_backup_trigger_settings_impl(&variant, &*repository).await?;
+  Ok(settings)
+}
+
+#[command]
+pub async fn set_backup_trigger_settings(
+  variant: GameVariant,
+  settings: BackupTriggerSettings,
+  repository: State<'_, SqliteBackupSettingsRepository>,
+) -> Result<(), BackupSettingsCommandError> {
+  set_backup_trigger_settings_impl(&variant, &settings, &*repository)
+    .await?;
+  Ok(())
+}
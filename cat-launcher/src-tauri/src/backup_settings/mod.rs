@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod backup_settings;
+
+pub mod commands;
+pub mod repository;
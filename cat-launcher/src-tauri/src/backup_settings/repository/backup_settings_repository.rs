@@ -0,0 +1,159 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use ts_rs::TS;
+
+use crate::backups::scopes::BackupScope;
+use crate::constants::MAX_BACKUPS;
+use crate::variants::GameVariant;
+
+#[derive(
+  Debug,
+  Display,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Hash,
+  EnumString,
+  Serialize,
+  Deserialize,
+  TS,
+)]
+#[ts(export)]
+pub enum BackupKind {
+  /// The backups taken before the game is launched.
+  Automatic,
+  Manual,
+}
+
+/// Which automatic backups are kept. Pinned backups are never removed and
+/// do not count towards any of the limits.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct BackupRetentionPolicy {
+  pub max_count: Option<u32>,
+  /// The total size of the files in the kept backups. Backups share
+  /// unchanged files, so they take up less space on disk than this.
+  pub max_total_size_in_bytes: Option<u64>,
+  pub max_age_days: Option<u32>,
+  pub rotation: Option<BackupRotation>,
+}
+
+/// Grandfather-father-son rotation. A backup is kept if it is one of the
+/// last `keep_last` backups, the newest backup of one of the last
+/// `keep_daily_days` days or the newest backup of one of the last
+/// `keep_weekly_weeks` weeks.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct BackupRotation {
+  pub keep_last: u32,
+  pub keep_daily_days: u32,
+  pub keep_weekly_weeks: u32,
+}
+
+impl Default for BackupRetentionPolicy {
+  fn default() -> Self {
+    BackupRetentionPolicy {
+      max_count: Some(MAX_BACKUPS.get() as u32),
+      max_total_size_in_bytes: None,
+      max_age_days: None,
+      rotation: None,
+    }
+  }
+}
+
+/// When automatic backups are taken.
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct BackupTriggerSettings {
+  pub before_launch: bool,
+  pub on_exit: bool,
+  /// How often to back up while the game is running, or None to not back
+  /// up while it runs.
+  pub periodic_interval_minutes: Option<u32>,
+  /// Whether to back up before installing a release switches the active
+  /// release.
+  pub before_update: bool,
+}
+
+impl Default for BackupTriggerSettings {
+  // Matches the behaviour from before triggers could be configured.
+  fn default() -> Self {
+    BackupTriggerSettings {
+      before_launch: true,
+      on_exit: false,
+      periodic_interval_minutes: None,
+      before_update: false,
+    }
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupSettingsRepositoryError {
+  #[error("failed to get backup scopes: {0}")]
+  GetScopes(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set backup scopes: {0}")]
+  SetScopes(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get backup retention policy: {0}")]
+  GetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set backup retention policy: {0}")]
+  SetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get backup trigger settings: {0}")]
+  GetTriggerSettings(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set backup trigger settings: {0}")]
+  SetTriggerSettings(#[source] Box<dyn Error + Send + Sync>),
+}
+
+#[async_trait]
+pub trait BackupSettingsRepository: Send + Sync {
+  /// Returns the scopes backed up for a variant, or only the save directory
+  /// if none were set.
+  async fn get_backup_scopes(
+    &self,
+    game_variant: &GameVariant,
+    backup_kind: &BackupKind,
+  ) -> Result<Vec<BackupScope>, BackupSettingsRepositoryError>;
+
+  async fn set_backup_scopes(
+    &self,
+    game_variant: &GameVariant,
+    backup_kind: &BackupKind,
+    scopes: &[BackupScope],
+  ) -> Result<(), BackupSettingsRepositoryError>;
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<BackupRetentionPolicy, BackupSettingsRepositoryError>;
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &BackupRetentionPolicy,
+  ) -> Result<(), BackupSettingsRepositoryError>;
+
+  async fn get_trigger_settings(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<BackupTriggerSettings, BackupSettingsRepositoryError>;
+
+  async fn set_trigger_settings(
+    &self,
+    game_variant: &GameVariant,
+    settings: &BackupTriggerSettings,
+  ) -> Result<(), BackupSettingsRepositoryError>;
+}
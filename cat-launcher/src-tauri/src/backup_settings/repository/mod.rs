@@ -0,0 +1,8 @@
+pub mod backup_settings_repository;
+pub mod sqlite_backup_settings_repository;
+
+pub use backup_settings_repository::{
+  BackupKind, BackupRetentionPolicy, BackupRotation,
+  BackupSettingsRepository, BackupSettingsRepositoryError,
+  BackupTriggerSettings,
+};
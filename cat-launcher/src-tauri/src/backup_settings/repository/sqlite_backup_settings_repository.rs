@@ -0,0 +1,214 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::OptionalExtension;
+
+use crate::backup_settings::repository::{
+  BackupKind, BackupRetentionPolicy, BackupRotation,
+  BackupSettingsRepository, BackupSettingsRepositoryError,
+  BackupTriggerSettings,
+};
+use crate::backups::scopes::{default_backup_scopes, BackupScope};
+use crate::infra::repository::db_helper::run_db;
+use crate::variants::GameVariant;
+
+#[derive(Clone)]
+pub struct SqliteBackupSettingsRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteBackupSettingsRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl BackupSettingsRepository for SqliteBackupSettingsRepository {
+  async fn get_backup_scopes(
+    &self,
+    game_variant: &GameVariant,
+    backup_kind: &BackupKind,
+  ) -> Result<Vec<BackupScope>, BackupSettingsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let backup_kind = backup_kind.to_string();
+
+    run_db(pool, move |conn| {
+      let scopes: Option<String> = conn
+        .query_row(
+          "SELECT scopes FROM backup_scope_settings WHERE game_variant = ?1 AND backup_kind = ?2",
+          [&variant_name, &backup_kind],
+          |row| row.get(0),
+        )
+        .optional()?;
+
+      match scopes {
+        Some(scopes) => serde_json::from_str(&scopes).map_err(|e| {
+          rusqlite::Error::FromSqlConversionFailure(
+            0,
+            Type::Text,
+            Box::new(e),
+          )
+        }),
+        None => Ok(default_backup_scopes()),
+      }
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::GetScopes)
+  }
+
+  async fn set_backup_scopes(
+    &self,
+    game_variant: &GameVariant,
+    backup_kind: &BackupKind,
+    scopes: &[BackupScope],
+  ) -> Result<(), BackupSettingsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let backup_kind = backup_kind.to_string();
+    let scopes = serde_json::to_string(scopes).map_err(|e| {
+      BackupSettingsRepositoryError::SetScopes(Box::new(e))
+    })?;
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO backup_scope_settings (game_variant, backup_kind, scopes) VALUES (?1, ?2, ?3)",
+        rusqlite::params![variant_name, backup_kind, scopes],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::SetScopes)
+  }
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<BackupRetentionPolicy, BackupSettingsRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let policy = conn
+        .query_row(
+          "SELECT max_count, max_total_size_in_bytes, max_age_days, keep_last, keep_daily_days, keep_weekly_weeks FROM backup_retention_policies WHERE game_variant = ?1",
+          [&variant_name],
+          |row| {
+            let keep_last: Option<u32> = row.get(3)?;
+            let keep_daily_days: Option<u32> = row.get(4)?;
+            let keep_weekly_weeks: Option<u32> = row.get(5)?;
+            let rotation = match (keep_last, keep_daily_days, keep_weekly_weeks) {
+              (Some(keep_last), Some(keep_daily_days), Some(keep_weekly_weeks)) => {
+                Some(BackupRotation {
+                  keep_last,
+                  keep_daily_days,
+                  keep_weekly_weeks,
+                })
+              }
+              _ => None,
+            };
+
+            Ok(BackupRetentionPolicy {
+              max_count: row.get(0)?,
+              max_total_size_in_bytes: row.get(1)?,
+              max_age_days: row.get(2)?,
+              rotation,
+            })
+          },
+        )
+        .optional()?;
+      Ok::<BackupRetentionPolicy, rusqlite::Error>(
+        policy.unwrap_or_default(),
+      )
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::GetRetentionPolicy)
+  }
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &BackupRetentionPolicy,
+  ) -> Result<(), BackupSettingsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let policy = *policy;
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO backup_retention_policies (game_variant, max_count, max_total_size_in_bytes, max_age_days, keep_last, keep_daily_days, keep_weekly_weeks) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+          variant_name,
+          policy.max_count,
+          policy.max_total_size_in_bytes,
+          policy.max_age_days,
+          policy.rotation.map(|rotation| rotation.keep_last),
+          policy.rotation.map(|rotation| rotation.keep_daily_days),
+          policy.rotation.map(|rotation| rotation.keep_weekly_weeks),
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::SetRetentionPolicy)
+  }
+
+  async fn get_trigger_settings(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<BackupTriggerSettings, BackupSettingsRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let settings = conn
+        .query_row(
+          "SELECT before_launch, on_exit, periodic_interval_minutes, before_update FROM backup_trigger_settings WHERE game_variant = ?1",
+          [&variant_name],
+          |row| {
+            Ok(BackupTriggerSettings {
+              before_launch: row.get(0)?,
+              on_exit: row.get(1)?,
+              periodic_interval_minutes: row.get(2)?,
+              before_update: row.get(3)?,
+            })
+          },
+        )
+        .optional()?;
+      Ok::<BackupTriggerSettings, rusqlite::Error>(
+        settings.unwrap_or_default(),
+      )
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::GetTriggerSettings)
+  }
+
+  async fn set_trigger_settings(
+    &self,
+    game_variant: &GameVariant,
+    settings: &BackupTriggerSettings,
+  ) -> Result<(), BackupSettingsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let settings = *settings;
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO backup_trigger_settings (game_variant, before_launch, on_exit, periodic_interval_minutes, before_update) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+          variant_name,
+          settings.before_launch,
+          settings.on_exit,
+          settings.periodic_interval_minutes,
+          settings.before_update
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(BackupSettingsRepositoryError::SetTriggerSettings)
+  }
+}
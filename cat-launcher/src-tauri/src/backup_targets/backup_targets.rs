@@ -0,0 +1,754 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use serde::Serialize;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::backup_settings::repository::BackupKind;
+use crate::backup_targets::repository::{
+  BackupTargetConfig, BackupTargetsRepository,
+  BackupTargetsRepositoryError, BackupUpload, BackupUploadStatus,
+};
+use crate::backup_targets::targets::{
+  create_backup_target, BackupTarget, BackupTargetError,
+};
+use crate::backups::backups::{
+  write_backup_archive, WriteBackupArchiveError,
+};
+use crate::constants::{
+  BACKUP_MIRROR_INTERVAL, BACKUP_UPLOAD_INITIAL_BACKOFF,
+  BACKUP_UPLOAD_MAX_ATTEMPTS, BACKUP_UPLOAD_MAX_BACKOFF,
+  BACKUP_UPLOAD_MAX_FAILED_MIRRORS, BACKUP_UPLOAD_MAX_RETRY_DELAY,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
+use crate::filesystem::paths::{
+  get_backup_upload_staging_dir, GetManualBackupArchivePathError,
+};
+use crate::infra::checksum::{
+  compute_file_sha256, ComputeSha256Error,
+};
+use crate::infra::download::RetryPolicy;
+use crate::infra::utils::OS;
+use crate::launch_game::repository::{
+  BackupEntry, BackupRepository, BackupRepositoryError,
+};
+use crate::manual_backups::manual_backups::{
+  get_manual_backup_archive_path, restore_manual_backup,
+  RestoreManualBackupError,
+};
+use crate::manual_backups::portable::{
+  import_backup, write_portable_backup, ImportBackupError,
+  PortableBackupManifest, WritePortableBackupError,
+  PORTABLE_BACKUP_FORMAT_VERSION,
+};
+use crate::manual_backups::repository::manual_backup_repository::{
+  ManualBackupEntry, ManualBackupRepository,
+  ManualBackupRepositoryError,
+};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+
+// Each backup is stored as a portable backup, next to a copy of its
+// manifest so that remote backups can be listed without downloading them.
+const MANIFEST_SUFFIX: &str = ".json";
+
+fn get_remote_name(
+  kind: &BackupKind,
+  variant: &GameVariant,
+  id: i64,
+  timestamp: u64,
+) -> String {
+  format!("{}_{}_{}_{}.zip", variant.id(), kind, id, timestamp)
+}
+
+fn get_manifest_name(remote_name: &str) -> String {
+  format!("{}{}", remote_name, MANIFEST_SUFFIX)
+}
+
+fn now() -> Result<u64, SystemTimeError> {
+  Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum InvalidBackupTargetError {
+  #[error("directory must be an absolute path")]
+  InvalidDirectory,
+
+  #[error("{0}")]
+  Target(#[from] BackupTargetError),
+}
+
+/// Returns the config without its password, so that the password is not
+/// sent to the frontend.
+pub fn redact_backup_target_config(
+  config: BackupTargetConfig,
+) -> BackupTargetConfig {
+  match config {
+    BackupTargetConfig::WebDav { url, username, .. } => {
+      BackupTargetConfig::WebDav {
+        url,
+        username,
+        password: None,
+      }
+    }
+    config => config,
+  }
+}
+
+/// Fills in the stored password of a normalized config without one if it
+/// points to the same WebDAV remote and user, since the frontend never
+/// receives the stored password. An empty password removes it.
+pub fn keep_stored_password(
+  config: BackupTargetConfig,
+  stored: Option<&BackupTargetConfig>,
+) -> BackupTargetConfig {
+  let BackupTargetConfig::WebDav {
+    url,
+    username,
+    password,
+  } = config
+  else {
+    return config;
+  };
+
+  let password = match (password, stored) {
+    (
+      None,
+      Some(BackupTargetConfig::WebDav {
+        url: stored_url,
+        username: stored_username,
+        password: stored_password,
+      }),
+    ) if url == *stored_url && username == *stored_username => {
+      stored_password.clone()
+    }
+    (password, _) => password,
+  };
+
+  BackupTargetConfig::WebDav {
+    url,
+    username,
+    password: password.filter(|password| !password.is_empty()),
+  }
+}
+
+/// Trims the fields of a target config and checks that a target can be
+/// created for it.
+pub fn normalize_backup_target_config(
+  config: BackupTargetConfig,
+) -> Result<BackupTargetConfig, InvalidBackupTargetError> {
+  let config = match config {
+    BackupTargetConfig::Directory { path } => {
+      let path = path.trim().to_string();
+      if path.is_empty() || !Path::new(&path).is_absolute() {
+        return Err(InvalidBackupTargetError::InvalidDirectory);
+      }
+      BackupTargetConfig::Directory { path }
+    }
+    BackupTargetConfig::WebDav {
+      url,
+      username,
+      password,
+    } => BackupTargetConfig::WebDav {
+      url: url.trim().to_string(),
+      username: username
+        .map(|username| username.trim().to_string())
+        .filter(|username| !username.is_empty()),
+      password,
+    },
+  };
+
+  // Fails for URLs a target cannot be created for.
+  create_backup_target(&config)?;
+
+  Ok(config)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SetBackupTargetError {
+  #[error("invalid backup target: {0}")]
+  Invalid(#[from] InvalidBackupTargetError),
+
+  #[error("failed to save backup target: {0}")]
+  Repository(#[from] BackupTargetsRepositoryError),
+}
+
+/// Sets where backups are mirrored to, or stops mirroring them if `config`
+/// is None. Every backup is uploaded again after the target changes.
+pub async fn set_backup_target(
+  config: Option<BackupTargetConfig>,
+  repository: &dyn BackupTargetsRepository,
+) -> Result<(), SetBackupTargetError> {
+  let stored = repository.get_target().await?;
+  let config = config
+    .map(normalize_backup_target_config)
+    .transpose()?
+    .map(|config| keep_stored_password(config, stored.as_ref()));
+
+  if stored == config {
+    return Ok(());
+  }
+
+  repository.set_target(config.as_ref()).await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum UploadBackupError {
+  #[error("failed to write backup: {0}")]
+  Write(#[from] WriteBackupArchiveError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetManualBackupArchivePathError),
+
+  #[error("failed to compute checksum: {0}")]
+  Checksum(#[from] ComputeSha256Error),
+
+  #[error("failed to write portable backup: {0}")]
+  Portable(#[from] WritePortableBackupError),
+
+  #[error("failed to write manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  #[error("failed to upload backup: {0}")]
+  Target(#[from] BackupTargetError),
+
+  #[error("failed to write staging file: {0}")]
+  Io(#[from] io::Error),
+}
+
+async fn upload_with_retry(
+  target: &dyn BackupTarget,
+  name: &str,
+  source: &Path,
+) -> Result<(), BackupTargetError> {
+  let retry_policy = RetryPolicy {
+    max_attempts: BACKUP_UPLOAD_MAX_ATTEMPTS,
+    initial_backoff: BACKUP_UPLOAD_INITIAL_BACKOFF,
+    max_backoff: BACKUP_UPLOAD_MAX_BACKOFF,
+  };
+
+  let max_attempts = retry_policy.max_attempts.get();
+  let mut attempt = 1;
+  loop {
+    match target.upload(name, source).await {
+      Ok(()) => return Ok(()),
+      Err(e) if e.is_retryable() && attempt < max_attempts => {
+        tokio::time::sleep(retry_policy.backoff_for_attempt(attempt))
+          .await;
+        attempt += 1;
+      }
+      Err(e) => return Err(e),
+    }
+  }
+}
+
+// Writes the portable backup and its manifest into `staging_dir` and
+// uploads them. The manifest is uploaded last, so that a backup is only
+// listed once it was uploaded in full.
+async fn upload_portable_backup(
+  target: &dyn BackupTarget,
+  remote_name: &str,
+  mut manifest: PortableBackupManifest,
+  archive_path: &Path,
+  staging_dir: &Path,
+) -> Result<(), UploadBackupError> {
+  manifest.sha256 = compute_file_sha256(archive_path).await?;
+
+  let portable_path = staging_dir.join(remote_name);
+  write_portable_backup(&manifest, archive_path, &portable_path)
+    .await?;
+
+  let manifest_name = get_manifest_name(remote_name);
+  let manifest_path = staging_dir.join(&manifest_name);
+  tokio::fs::write(
+    &manifest_path,
+    serde_json::to_vec_pretty(&manifest)?,
+  )
+  .await?;
+
+  upload_with_retry(target, remote_name, &portable_path).await?;
+  upload_with_retry(target, &manifest_name, &manifest_path).await?;
+
+  Ok(())
+}
+
+async fn upload_automatic_backup(
+  target: &dyn BackupTarget,
+  backup: &BackupEntry,
+  remote_name: &str,
+  data_dir: &Path,
+  staging_dir: &Path,
+) -> Result<(), UploadBackupError> {
+  let archive_path = staging_dir.join("backup.zip");
+  write_backup_archive(backup, &archive_path, data_dir).await?;
+
+  let manifest = PortableBackupManifest {
+    format_version: PORTABLE_BACKUP_FORMAT_VERSION,
+    game_variant: backup.game_variant,
+    name: format!("Automatic backup ({})", backup.trigger),
+    notes: None,
+    release_version: Some(backup.release_version.clone()),
+    timestamp: backup.timestamp,
+    scopes: backup.scopes.clone(),
+    sha256: String::new(),
+  };
+
+  upload_portable_backup(
+    target,
+    remote_name,
+    manifest,
+    &archive_path,
+    staging_dir,
+  )
+  .await
+}
+
+async fn upload_manual_backup(
+  target: &dyn BackupTarget,
+  backup: &ManualBackupEntry,
+  remote_name: &str,
+  data_dir: &Path,
+  staging_dir: &Path,
+) -> Result<(), UploadBackupError> {
+  let archive_path =
+    get_manual_backup_archive_path(backup, data_dir).await?;
+
+  let manifest = PortableBackupManifest {
+    format_version: PORTABLE_BACKUP_FORMAT_VERSION,
+    game_variant: backup.game_variant,
+    name: backup.name.clone(),
+    notes: backup.notes.clone(),
+    release_version: backup.release_version.clone(),
+    timestamp: backup.timestamp,
+    scopes: backup.scopes.clone(),
+    sha256: String::new(),
+  };
+
+  upload_portable_backup(
+    target,
+    remote_name,
+    manifest,
+    &archive_path,
+    staging_dir,
+  )
+  .await
+}
+
+async fn create_staging_dir(data_dir: &Path) -> io::Result<PathBuf> {
+  let dir = get_backup_upload_staging_dir(data_dir)
+    .join(Uuid::new_v4().to_string());
+  tokio::fs::create_dir_all(&dir).await?;
+  Ok(dir)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum MirrorBackupsError {
+  #[error("failed to get backup target: {0}")]
+  Repository(#[from] BackupTargetsRepositoryError),
+
+  #[error("failed to create backup target: {0}")]
+  Target(#[from] BackupTargetError),
+
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get manual backup entries: {0}")]
+  GetManual(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to create staging directory: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to get current time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+// Failed uploads are retried with increasing delays, starting at the
+// mirror interval, and only on request once they failed too often.
+fn is_upload_retry_due(upload: &BackupUpload, now: u64) -> bool {
+  let retry_policy = RetryPolicy {
+    max_attempts: BACKUP_UPLOAD_MAX_FAILED_MIRRORS,
+    initial_backoff: BACKUP_MIRROR_INTERVAL,
+    max_backoff: BACKUP_UPLOAD_MAX_RETRY_DELAY,
+  };
+
+  let attempts = u16::try_from(upload.attempts).unwrap_or(u16::MAX);
+  if attempts >= retry_policy.max_attempts.get() {
+    return false;
+  }
+
+  let delay = retry_policy.backoff_for_attempt(attempts.max(1));
+  upload.updated_at.saturating_add(delay.as_secs()) <= now
+}
+
+fn get_upload_record(
+  backup_kind: BackupKind,
+  backup_id: i64,
+  remote_name: String,
+  result: Result<(), UploadBackupError>,
+  previous: Option<&BackupUpload>,
+) -> Result<BackupUpload, SystemTimeError> {
+  let (status, last_error) = match result {
+    Ok(()) => (BackupUploadStatus::Uploaded, None),
+    Err(e) => (BackupUploadStatus::Failed, Some(e.to_string())),
+  };
+
+  Ok(BackupUpload {
+    backup_kind,
+    backup_id,
+    remote_name,
+    status,
+    attempts: previous.map_or(0, |upload| upload.attempts) + 1,
+    last_error,
+    updated_at: now()?,
+  })
+}
+
+/// Uploads every backup that is not on the backup target yet. A failed
+/// upload is recorded and does not stop the others. It is retried once its
+/// retry is due, or right away if `retry_failed` is set. Returns the number
+/// of uploaded backups.
+pub async fn mirror_pending_backups(
+  data_dir: &Path,
+  retry_failed: bool,
+  targets_repository: &dyn BackupTargetsRepository,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+) -> Result<usize, MirrorBackupsError> {
+  let Some(config) = targets_repository.get_target().await? else {
+    return Ok(0);
+  };
+  let target = create_backup_target(&config)?;
+
+  let uploads: HashMap<(BackupKind, i64), BackupUpload> =
+    targets_repository
+      .get_uploads()
+      .await?
+      .into_iter()
+      .map(|upload| ((upload.backup_kind, upload.backup_id), upload))
+      .collect();
+  let automatic_backups = backup_repository.get_all_backups().await?;
+  let manual_backups =
+    manual_backup_repository.get_all_manual_backups().await?;
+
+  // The uploads of deleted backups are forgotten. Their copies stay on
+  // the target.
+  let local_backups: HashSet<(BackupKind, i64)> = automatic_backups
+    .iter()
+    .map(|backup| (BackupKind::Automatic, backup.id))
+    .chain(
+      manual_backups
+        .iter()
+        .map(|backup| (BackupKind::Manual, backup.id)),
+    )
+    .collect();
+  for key in uploads.keys() {
+    if !local_backups.contains(key) {
+      targets_repository.delete_upload(key.0, key.1).await?;
+    }
+  }
+
+  let now = now()?;
+  let is_pending =
+    |kind: BackupKind, id: i64| match uploads.get(&(kind, id)) {
+      None => true,
+      Some(upload) => match upload.status {
+        BackupUploadStatus::Uploaded => false,
+        BackupUploadStatus::Failed => {
+          retry_failed || is_upload_retry_due(upload, now)
+        }
+      },
+    };
+
+  let automatic_backups: Vec<_> = automatic_backups
+    .into_iter()
+    .filter(|backup| is_pending(BackupKind::Automatic, backup.id))
+    .collect();
+  let manual_backups: Vec<_> = manual_backups
+    .into_iter()
+    .filter(|backup| is_pending(BackupKind::Manual, backup.id))
+    .collect();
+
+  let mut uploaded = 0;
+
+  for backup in &automatic_backups {
+    let remote_name = get_remote_name(
+      &BackupKind::Automatic,
+      &backup.game_variant,
+      backup.id,
+      backup.timestamp,
+    );
+    let staging_dir = create_staging_dir(data_dir).await?;
+    let result = upload_automatic_backup(
+      target.as_ref(),
+      backup,
+      &remote_name,
+      data_dir,
+      &staging_dir,
+    )
+    .await;
+    let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+
+    uploaded += usize::from(result.is_ok());
+    let upload = get_upload_record(
+      BackupKind::Automatic,
+      backup.id,
+      remote_name,
+      result,
+      uploads.get(&(BackupKind::Automatic, backup.id)),
+    )?;
+    targets_repository.set_upload(&upload).await?;
+  }
+
+  for backup in &manual_backups {
+    let remote_name = get_remote_name(
+      &BackupKind::Manual,
+      &backup.game_variant,
+      backup.id,
+      backup.timestamp,
+    );
+    let staging_dir = create_staging_dir(data_dir).await?;
+    let result = upload_manual_backup(
+      target.as_ref(),
+      backup,
+      &remote_name,
+      data_dir,
+      &staging_dir,
+    )
+    .await;
+    let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+
+    uploaded += usize::from(result.is_ok());
+    let upload = get_upload_record(
+      BackupKind::Manual,
+      backup.id,
+      remote_name,
+      result,
+      uploads.get(&(BackupKind::Manual, backup.id)),
+    )?;
+    targets_repository.set_upload(&upload).await?;
+  }
+
+  Ok(uploaded)
+}
+
+/// A backup on the backup target.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RemoteBackup {
+  pub remote_name: String,
+  pub game_variant: GameVariant,
+  pub name: String,
+  pub notes: Option<String>,
+  pub release_version: Option<String>,
+  pub timestamp: u64,
+  /// Whether the backup no longer exists locally, such as when it was
+  /// removed by the retention policy or uploaded from another machine.
+  pub is_remote_only: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListRemoteBackupsError {
+  #[error("no backup target is set")]
+  NoTarget,
+
+  #[error("failed to get backup target: {0}")]
+  Repository(#[from] BackupTargetsRepositoryError),
+
+  #[error("failed to list backup target: {0}")]
+  Target(#[from] BackupTargetError),
+
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get manual backup entries: {0}")]
+  GetManual(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to read backup manifest: {0}")]
+  Io(#[from] io::Error),
+}
+
+async fn get_target(
+  targets_repository: &dyn BackupTargetsRepository,
+) -> Result<Box<dyn BackupTarget>, ListRemoteBackupsError> {
+  let config = targets_repository
+    .get_target()
+    .await?
+    .ok_or(ListRemoteBackupsError::NoTarget)?;
+  Ok(create_backup_target(&config)?)
+}
+
+async fn read_remote_manifests(
+  target: &dyn BackupTarget,
+  staging_dir: &Path,
+) -> Result<
+  Vec<(String, PortableBackupManifest)>,
+  ListRemoteBackupsError,
+> {
+  let names: HashSet<String> =
+    target.list().await?.into_iter().collect();
+
+  let mut manifests = Vec::new();
+  for name in &names {
+    let Some(remote_name) = name.strip_suffix(MANIFEST_SUFFIX) else {
+      continue;
+    };
+    if !names.contains(remote_name) {
+      continue;
+    }
+
+    let manifest_path = staging_dir.join(name);
+    target.download(name, &manifest_path).await?;
+    let manifest = tokio::fs::read(&manifest_path).await?;
+    match serde_json::from_slice::<PortableBackupManifest>(&manifest)
+    {
+      Ok(manifest) => {
+        manifests.push((remote_name.to_string(), manifest))
+      }
+      // Files that are not backups may be stored next to them.
+      Err(e) => {
+        eprintln!("Skipping invalid backup manifest {}: {}", name, e)
+      }
+    }
+  }
+
+  Ok(manifests)
+}
+
+/// Lists the backups on the backup target, newest first.
+pub async fn list_remote_backups(
+  data_dir: &Path,
+  targets_repository: &dyn BackupTargetsRepository,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+) -> Result<Vec<RemoteBackup>, ListRemoteBackupsError> {
+  let target = get_target(targets_repository).await?;
+
+  let local_backups: HashSet<(BackupKind, i64)> = backup_repository
+    .get_all_backups()
+    .await?
+    .into_iter()
+    .map(|backup| (BackupKind::Automatic, backup.id))
+    .chain(
+      manual_backup_repository
+        .get_all_manual_backups()
+        .await?
+        .into_iter()
+        .map(|backup| (BackupKind::Manual, backup.id)),
+    )
+    .collect();
+  let local_names: HashSet<String> = targets_repository
+    .get_uploads()
+    .await?
+    .into_iter()
+    .filter(|upload| {
+      local_backups.contains(&(upload.backup_kind, upload.backup_id))
+    })
+    .map(|upload| upload.remote_name)
+    .collect();
+
+  let staging_dir = create_staging_dir(data_dir).await?;
+  let manifests =
+    read_remote_manifests(target.as_ref(), &staging_dir).await;
+  let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+
+  let mut backups: Vec<RemoteBackup> = manifests?
+    .into_iter()
+    .map(|(remote_name, manifest)| RemoteBackup {
+      is_remote_only: !local_names.contains(&remote_name),
+      remote_name,
+      game_variant: manifest.game_variant,
+      name: manifest.name,
+      notes: manifest.notes,
+      release_version: manifest.release_version,
+      timestamp: manifest.timestamp,
+    })
+    .collect();
+  backups.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+  Ok(backups)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RestoreRemoteBackupError {
+  #[error("failed to get backup target: {0}")]
+  Target(#[from] ListRemoteBackupsError),
+
+  #[error("failed to download backup: {0}")]
+  Download(#[from] BackupTargetError),
+
+  #[error("failed to import backup: {0}")]
+  Import(#[from] ImportBackupError),
+
+  #[error("failed to restore backup: {0}")]
+  Restore(#[from] RestoreManualBackupError),
+
+  #[error("failed to record backup upload: {0}")]
+  Repository(#[from] BackupTargetsRepositoryError),
+
+  #[error("failed to create staging directory: {0}")]
+  Io(#[from] io::Error),
+}
+
+/// Downloads a backup from the backup target, adds it as a manual backup
+/// and restores it. Returns the id of the manual backup.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_remote_backup(
+  remote_name: &str,
+  timestamp: u64,
+  data_dir: &Path,
+  targets_repository: &dyn BackupTargetsRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  running_games: &RunningGamesRegistry,
+  os: &OS,
+) -> Result<i64, RestoreRemoteBackupError> {
+  let target = get_target(targets_repository).await?;
+
+  let staging_dir = create_staging_dir(data_dir).await?;
+  let source = staging_dir.join("backup.zip");
+  let id = async {
+    target.download(remote_name, &source).await?;
+    Ok::<i64, RestoreRemoteBackupError>(
+      import_backup(
+        &source,
+        data_dir,
+        manual_backup_repository,
+        data_profiles_repository,
+      )
+      .await?,
+    )
+  }
+  .await;
+  let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+  let id = id?;
+
+  // The backup is already on the target, so it is not uploaded again.
+  targets_repository
+    .set_upload(&BackupUpload {
+      backup_kind: BackupKind::Manual,
+      backup_id: id,
+      remote_name: remote_name.to_string(),
+      status: BackupUploadStatus::Uploaded,
+      attempts: 0,
+      last_error: None,
+      updated_at: timestamp,
+    })
+    .await?;
+
+  restore_manual_backup(
+    id,
+    timestamp,
+    data_dir,
+    manual_backup_repository,
+    data_profiles_repository,
+    running_games,
+    os,
+  )
+  .await?;
+
+  Ok(id)
+}
@@ -0,0 +1,134 @@
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::backup_targets::backup_targets::{
+  list_remote_backups as list_remote_backups_impl,
+  restore_remote_backup as restore_remote_backup_impl,
+  redact_backup_target_config,
+  set_backup_target as set_backup_target_impl, ListRemoteBackupsError,
+  RemoteBackup, RestoreRemoteBackupError, SetBackupTargetError,
+};
+use crate::backup_targets::mirror::BackupMirror;
+use crate::backup_targets::repository::sqlite_backup_targets_repository::SqliteBackupTargetsRepository;
+use crate::backup_targets::repository::{
+  BackupTargetConfig, BackupTargetsRepository,
+  BackupTargetsRepositoryError, BackupUpload,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum BackupTargetCommandError {
+  #[error("failed to access backup target: {0}")]
+  Repository(#[from] BackupTargetsRepositoryError),
+
+  #[error("failed to set backup target: {0}")]
+  Set(#[from] SetBackupTargetError),
+}
+
+#[command]
+pub async fn get_backup_target(
+  repository: State<'_, SqliteBackupTargetsRepository>,
+) -> Result<Option<BackupTargetConfig>, BackupTargetCommandError> {
+  let config = repository.get_target().await?;
+  Ok(config.map(redact_backup_target_config))
+}
+
+#[command]
+pub async fn set_backup_target(
+  config: Option<BackupTargetConfig>,
+  repository: State<'_, SqliteBackupTargetsRepository>,
+  mirror: State<'_, BackupMirror>,
+) -> Result<(), BackupTargetCommandError> {
+  set_backup_target_impl(config, &*repository).await?;
+  mirror.request_sync();
+  Ok(())
+}
+
+#[command]
+pub async fn list_backup_uploads(
+  repository: State<'_, SqliteBackupTargetsRepository>,
+) -> Result<Vec<BackupUpload>, BackupTargetCommandError> {
+  Ok(repository.get_uploads().await?)
+}
+
+#[command]
+pub fn retry_backup_uploads(mirror: State<'_, BackupMirror>) {
+  mirror.request_retry();
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum RemoteBackupsCommandError {
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+
+  #[error("failed to list remote backups: {0}")]
+  List(#[from] ListRemoteBackupsError),
+
+  #[error("failed to restore remote backup: {0}")]
+  Restore(#[from] RestoreRemoteBackupError),
+
+  #[error("unsupported OS: {0}")]
+  UnsupportedOS(#[from] OSNotSupportedError),
+
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+#[command]
+pub async fn list_remote_backups(
+  app_handle: AppHandle,
+  repository: State<'_, SqliteBackupTargetsRepository>,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+) -> Result<Vec<RemoteBackup>, RemoteBackupsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  let backups = list_remote_backups_impl(
+    &data_dir,
+    &*repository,
+    backup_repository.inner(),
+    manual_backup_repository.inner(),
+  )
+  .await?;
+  Ok(backups)
+}
+
+#[command]
+pub async fn restore_remote_backup(
+  remote_name: String,
+  app_handle: AppHandle,
+  repository: State<'_, SqliteBackupTargetsRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<i64, RemoteBackupsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+  let id = restore_remote_backup_impl(
+    &remote_name,
+    timestamp,
+    &data_dir,
+    &*repository,
+    manual_backup_repository.inner(),
+    &*data_profiles_repository,
+    &running_games,
+    &os,
+  )
+  .await?;
+  Ok(id)
+}
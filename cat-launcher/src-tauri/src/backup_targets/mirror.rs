@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+use tokio::time::MissedTickBehavior;
+
+use crate::backup_targets::backup_targets::{
+  mirror_pending_backups, MirrorBackupsError,
+};
+use crate::backup_targets::repository::sqlite_backup_targets_repository::SqliteBackupTargetsRepository;
+use crate::constants::BACKUP_MIRROR_INTERVAL;
+use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+
+/// Copies new backups to the backup target in the background.
+#[derive(Default)]
+pub struct BackupMirror {
+  sync_requested: Notify,
+  retry_requested: AtomicBool,
+}
+
+impl BackupMirror {
+  /// Uploads pending backups now instead of at the next interval. Requests
+  /// made while uploading are handled once it finishes.
+  pub fn request_sync(&self) {
+    self.sync_requested.notify_one();
+  }
+
+  /// Like `request_sync`, but also retries failed uploads whose retry is
+  /// not due yet.
+  pub fn request_retry(&self) {
+    self.retry_requested.store(true, Ordering::SeqCst);
+    self.request_sync();
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum MirrorError {
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+
+  #[error("failed to mirror backups: {0}")]
+  Mirror(#[from] MirrorBackupsError),
+}
+
+async fn mirror_backups(
+  handle: &AppHandle,
+  retry_failed: bool,
+) -> Result<(), MirrorError> {
+  let data_dir = handle.path().app_local_data_dir()?;
+
+  mirror_pending_backups(
+    &data_dir,
+    retry_failed,
+    handle.state::<SqliteBackupTargetsRepository>().inner(),
+    handle.state::<SqliteBackupRepository>().inner(),
+    handle.state::<SqliteManualBackupRepository>().inner(),
+  )
+  .await?;
+
+  Ok(())
+}
+
+/// Uploads pending backups when the app starts, whenever a sync is
+/// requested and periodically, which also retries failed uploads that are
+/// due.
+pub fn spawn_backup_mirror(handle: AppHandle) {
+  tauri::async_runtime::spawn(async move {
+    let mut interval = tokio::time::interval(BACKUP_MIRROR_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+      let mirror = handle.state::<BackupMirror>();
+      tokio::select! {
+        _ = interval.tick() => {}
+        _ = mirror.sync_requested.notified() => {}
+      }
+
+      let retry_failed =
+        mirror.retry_requested.swap(false, Ordering::SeqCst);
+      if let Err(e) = mirror_backups(&handle, retry_failed).await {
+        eprintln!("Failed to mirror backups: {}", e);
+      }
+    }
+  });
+}
@@ -0,0 +1,7 @@
+#[allow(clippy::module_inception)]
+pub mod backup_targets;
+
+pub mod commands;
+pub mod mirror;
+pub mod repository;
+pub mod targets;
@@ -0,0 +1,106 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use ts_rs::TS;
+
+use crate::backup_settings::repository::BackupKind;
+
+/// Where backups are mirrored to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", content = "payload")]
+pub enum BackupTargetConfig {
+  /// A local or mounted directory, such as a synced folder or a NAS.
+  Directory { path: String },
+  /// A WebDAV collection. Backups are stored directly inside `url`. The
+  /// password is never sent to the frontend, and a config without one
+  /// keeps the stored password.
+  WebDav {
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+  },
+}
+
+#[derive(
+  Debug,
+  Display,
+  EnumString,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  TS,
+)]
+#[ts(export)]
+pub enum BackupUploadStatus {
+  Uploaded,
+  /// The upload failed. It is retried later with increasing delays, and
+  /// only on request once it failed too often.
+  Failed,
+}
+
+/// The state of copying a backup to the backup target.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupUpload {
+  pub backup_kind: BackupKind,
+  pub backup_id: i64,
+  /// The name of the backup's archive on the target.
+  pub remote_name: String,
+  pub status: BackupUploadStatus,
+  pub attempts: u32,
+  pub last_error: Option<String>,
+  pub updated_at: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupTargetsRepositoryError {
+  #[error("failed to get backup target: {0}")]
+  GetTarget(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set backup target: {0}")]
+  SetTarget(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get backup uploads: {0}")]
+  GetUploads(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to record backup upload: {0}")]
+  SetUpload(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete backup upload: {0}")]
+  DeleteUpload(#[source] Box<dyn Error + Send + Sync>),
+}
+
+#[async_trait]
+pub trait BackupTargetsRepository: Send + Sync {
+  async fn get_target(
+    &self,
+  ) -> Result<Option<BackupTargetConfig>, BackupTargetsRepositoryError>;
+
+  /// Replaces the backup target, or removes it if `config` is None. The
+  /// uploads to the previous target are forgotten.
+  async fn set_target(
+    &self,
+    config: Option<&BackupTargetConfig>,
+  ) -> Result<(), BackupTargetsRepositoryError>;
+
+  async fn get_uploads(
+    &self,
+  ) -> Result<Vec<BackupUpload>, BackupTargetsRepositoryError>;
+
+  async fn set_upload(
+    &self,
+    upload: &BackupUpload,
+  ) -> Result<(), BackupTargetsRepositoryError>;
+
+  async fn delete_upload(
+    &self,
+    backup_kind: BackupKind,
+    backup_id: i64,
+  ) -> Result<(), BackupTargetsRepositoryError>;
+}
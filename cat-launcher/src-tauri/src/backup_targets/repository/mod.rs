@@ -0,0 +1,7 @@
+pub mod backup_targets_repository;
+pub mod sqlite_backup_targets_repository;
+
+pub use backup_targets_repository::{
+  BackupTargetConfig, BackupTargetsRepository,
+  BackupTargetsRepositoryError, BackupUpload, BackupUploadStatus,
+};
@@ -0,0 +1,174 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::OptionalExtension;
+
+use crate::backup_settings::repository::BackupKind;
+use crate::backup_targets::repository::{
+  BackupTargetConfig, BackupTargetsRepository,
+  BackupTargetsRepositoryError, BackupUpload, BackupUploadStatus,
+};
+use crate::infra::repository::db_helper::run_db;
+
+#[derive(Clone)]
+pub struct SqliteBackupTargetsRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteBackupTargetsRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+fn parse_column<T>(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<T>
+where
+  T: FromStr,
+  T::Err: std::error::Error + Send + Sync + 'static,
+{
+  let value: String = row.get(index)?;
+  T::from_str(&value).map_err(|e| {
+    rusqlite::Error::FromSqlConversionFailure(
+      index,
+      Type::Text,
+      Box::new(e),
+    )
+  })
+}
+
+#[async_trait]
+impl BackupTargetsRepository for SqliteBackupTargetsRepository {
+  async fn get_target(
+    &self,
+  ) -> Result<Option<BackupTargetConfig>, BackupTargetsRepositoryError>
+  {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      let config: Option<String> = conn
+        .query_row(
+          "SELECT config FROM backup_target WHERE id = 1",
+          [],
+          |row| row.get(0),
+        )
+        .optional()?;
+
+      config
+        .map(|config| {
+          serde_json::from_str(&config).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+              0,
+              Type::Text,
+              Box::new(e),
+            )
+          })
+        })
+        .transpose()
+    })
+    .await
+    .map_err(BackupTargetsRepositoryError::GetTarget)
+  }
+
+  async fn set_target(
+    &self,
+    config: Option<&BackupTargetConfig>,
+  ) -> Result<(), BackupTargetsRepositoryError> {
+    let pool = self.pool.clone();
+    let config =
+      config.map(serde_json::to_string).transpose().map_err(|e| {
+        BackupTargetsRepositoryError::SetTarget(Box::new(e))
+      })?;
+
+    run_db(pool, move |mut conn| {
+      let tx = conn.transaction()?;
+      match config {
+        Some(config) => tx.execute(
+          "INSERT OR REPLACE INTO backup_target (id, config) VALUES (1, ?1)",
+          [config],
+        )?,
+        None => tx.execute("DELETE FROM backup_target", [])?,
+      };
+      tx.execute("DELETE FROM backup_uploads", [])?;
+      tx.commit()
+    })
+    .await
+    .map_err(BackupTargetsRepositoryError::SetTarget)
+  }
+
+  async fn get_uploads(
+    &self,
+  ) -> Result<Vec<BackupUpload>, BackupTargetsRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT backup_kind, backup_id, remote_name, status, attempts, last_error, updated_at FROM backup_uploads ORDER BY updated_at ASC",
+      )?;
+      let uploads = stmt
+        .query_map([], |row| {
+          Ok(BackupUpload {
+            backup_kind: parse_column::<BackupKind>(row, 0)?,
+            backup_id: row.get(1)?,
+            remote_name: row.get(2)?,
+            status: parse_column::<BackupUploadStatus>(row, 3)?,
+            attempts: row.get(4)?,
+            last_error: row.get(5)?,
+            updated_at: row.get(6)?,
+          })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<BackupUpload>, rusqlite::Error>(uploads)
+    })
+    .await
+    .map_err(BackupTargetsRepositoryError::GetUploads)
+  }
+
+  async fn set_upload(
+    &self,
+    upload: &BackupUpload,
+  ) -> Result<(), BackupTargetsRepositoryError> {
+    let pool = self.pool.clone();
+    let upload = upload.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO backup_uploads (backup_kind, backup_id, remote_name, status, attempts, last_error, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+          upload.backup_kind.to_string(),
+          upload.backup_id,
+          upload.remote_name,
+          upload.status.to_string(),
+          upload.attempts,
+          upload.last_error,
+          upload.updated_at
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(BackupTargetsRepositoryError::SetUpload)
+  }
+
+  async fn delete_upload(
+    &self,
+    backup_kind: BackupKind,
+    backup_id: i64,
+  ) -> Result<(), BackupTargetsRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "DELETE FROM backup_uploads WHERE backup_kind = ?1 AND backup_id = ?2",
+        rusqlite::params![backup_kind.to_string(), backup_id],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(BackupTargetsRepositoryError::DeleteUpload)
+  }
+}
@@ -0,0 +1,580 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+use crate::backup_targets::repository::BackupTargetConfig;
+
+static HREF_RE: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(
+    r"(?i)<(?:[a-z0-9]+:)?href>([^<]*)</(?:[a-z0-9]+:)?href>",
+  )
+  .unwrap()
+});
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?><propfind xmlns="DAV:"><prop><resourcetype/></prop></propfind>"#;
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupTargetError {
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("request failed: {0}")]
+  Request(#[from] reqwest::Error),
+
+  #[error("server responded with {0}")]
+  Status(StatusCode),
+
+  #[error("invalid WebDAV URL: {0}")]
+  InvalidUrl(String),
+
+  #[error("invalid backup name: {0}")]
+  InvalidName(String),
+}
+
+impl BackupTargetError {
+  /// Whether the operation may succeed if it is tried again, such as when
+  /// the server or a mounted directory is briefly unavailable.
+  pub fn is_retryable(&self) -> bool {
+    match self {
+      BackupTargetError::Io(_) | BackupTargetError::Request(_) => {
+        true
+      }
+      BackupTargetError::Status(status) => {
+        status.is_server_error()
+          || *status == StatusCode::REQUEST_TIMEOUT
+          || *status == StatusCode::TOO_MANY_REQUESTS
+      }
+      BackupTargetError::InvalidUrl(_)
+      | BackupTargetError::InvalidName(_) => false,
+    }
+  }
+}
+
+/// A place backups are copied to, identified by their file names.
+#[async_trait]
+pub trait BackupTarget: Send + Sync {
+  async fn upload(
+    &self,
+    name: &str,
+    source: &Path,
+  ) -> Result<(), BackupTargetError>;
+
+  async fn download(
+    &self,
+    name: &str,
+    destination: &Path,
+  ) -> Result<(), BackupTargetError>;
+
+  /// Returns the names of the files on the target.
+  async fn list(&self) -> Result<Vec<String>, BackupTargetError>;
+
+  /// Removes a file from the target. Removing a missing file succeeds.
+  async fn delete(&self, name: &str)
+    -> Result<(), BackupTargetError>;
+}
+
+// Names come from remote listings, so they are checked to not escape the
+// target.
+fn validate_name(name: &str) -> Result<(), BackupTargetError> {
+  if name.is_empty()
+    || name.starts_with('.')
+    || name.contains(['/', '\\'])
+  {
+    return Err(BackupTargetError::InvalidName(name.to_string()));
+  }
+
+  Ok(())
+}
+
+pub struct DirectoryTarget {
+  dir: PathBuf,
+}
+
+impl DirectoryTarget {
+  pub fn new(dir: PathBuf) -> Self {
+    Self { dir }
+  }
+}
+
+#[async_trait]
+impl BackupTarget for DirectoryTarget {
+  async fn upload(
+    &self,
+    name: &str,
+    source: &Path,
+  ) -> Result<(), BackupTargetError> {
+    validate_name(name)?;
+    fs::create_dir_all(&self.dir).await?;
+
+    // The file is renamed into place so that a synced folder never picks
+    // up a partially copied backup.
+    let temp_path = self.dir.join(format!(".{}.tmp", name));
+    fs::copy(source, &temp_path).await?;
+    fs::rename(&temp_path, self.dir.join(name)).await?;
+
+    Ok(())
+  }
+
+  async fn download(
+    &self,
+    name: &str,
+    destination: &Path,
+  ) -> Result<(), BackupTargetError> {
+    validate_name(name)?;
+    fs::copy(self.dir.join(name), destination).await?;
+    Ok(())
+  }
+
+  async fn list(&self) -> Result<Vec<String>, BackupTargetError> {
+    let mut names = Vec::new();
+    let mut entries = match fs::read_dir(&self.dir).await {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {
+        return Ok(names);
+      }
+      Err(e) => return Err(e.into()),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+      let name = entry.file_name().to_string_lossy().into_owned();
+      if entry.file_type().await?.is_file()
+        && validate_name(&name).is_ok()
+      {
+        names.push(name);
+      }
+    }
+
+    names.sort();
+    Ok(names)
+  }
+
+  async fn delete(
+    &self,
+    name: &str,
+  ) -> Result<(), BackupTargetError> {
+    validate_name(name)?;
+    match fs::remove_file(self.dir.join(name)).await {
+      Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+}
+
+pub struct WebDavTarget {
+  // Not the shared client, which sends the GitHub token to every host.
+  client: Client,
+  base_url: Url,
+  username: Option<String>,
+  password: Option<String>,
+}
+
+impl WebDavTarget {
+  pub fn new(
+    url: &str,
+    username: Option<String>,
+    password: Option<String>,
+  ) -> Result<Self, BackupTargetError> {
+    // Without a trailing slash, joining a name would replace the last
+    // segment of the collection.
+    let url = if url.ends_with('/') {
+      url.to_string()
+    } else {
+      format!("{}/", url)
+    };
+    let base_url = Url::parse(&url)
+      .ok()
+      .filter(|url| matches!(url.scheme(), "http" | "https"))
+      .ok_or_else(|| BackupTargetError::InvalidUrl(url.clone()))?;
+
+    let client = Client::builder()
+      .user_agent("cat-launcher")
+      .build()
+      .map_err(BackupTargetError::Request)?;
+
+    Ok(Self {
+      client,
+      base_url,
+      username,
+      password,
+    })
+  }
+
+  fn file_url(&self, name: &str) -> Result<Url, BackupTargetError> {
+    validate_name(name)?;
+    self
+      .base_url
+      .join(&urlencoding::encode(name))
+      .map_err(|_| BackupTargetError::InvalidName(name.to_string()))
+  }
+
+  fn request(&self, method: Method, url: Url) -> RequestBuilder {
+    let request = self.client.request(method, url);
+    match &self.username {
+      Some(username) => {
+        request.basic_auth(username, self.password.as_ref())
+      }
+      None => request,
+    }
+  }
+
+  async fn create_collection(&self) -> Result<(), BackupTargetError> {
+    let method = Method::from_bytes(b"MKCOL").expect("valid method");
+    let response =
+      self.request(method, self.base_url.clone()).send().await?;
+
+    // Servers respond with 405 if the collection already exists.
+    let status = response.status();
+    if status.is_success() || status == StatusCode::METHOD_NOT_ALLOWED
+    {
+      Ok(())
+    } else {
+      Err(BackupTargetError::Status(status))
+    }
+  }
+}
+
+#[async_trait]
+impl BackupTarget for WebDavTarget {
+  async fn upload(
+    &self,
+    name: &str,
+    source: &Path,
+  ) -> Result<(), BackupTargetError> {
+    let url = self.file_url(name)?;
+    self.create_collection().await?;
+
+    // The file is streamed, with its length so that servers that do not
+    // accept chunked uploads can store it.
+    let file = fs::File::open(source).await?;
+    let len = file.metadata().await?.len();
+    let response = self
+      .request(Method::PUT, url)
+      .header(CONTENT_LENGTH, len)
+      .body(file)
+      .send()
+      .await?;
+    if !response.status().is_success() {
+      return Err(BackupTargetError::Status(response.status()));
+    }
+
+    Ok(())
+  }
+
+  async fn download(
+    &self,
+    name: &str,
+    destination: &Path,
+  ) -> Result<(), BackupTargetError> {
+    let url = self.file_url(name)?;
+    let mut response = self.request(Method::GET, url).send().await?;
+    if !response.status().is_success() {
+      return Err(BackupTargetError::Status(response.status()));
+    }
+
+    let mut file = fs::File::create(destination).await?;
+    while let Some(chunk) = response.chunk().await? {
+      file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+
+    Ok(())
+  }
+
+  async fn list(&self) -> Result<Vec<String>, BackupTargetError> {
+    let method =
+      Method::from_bytes(b"PROPFIND").expect("valid method");
+    let response = self
+      .request(method, self.base_url.clone())
+      .header("Depth", "1")
+      .header(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/xml"),
+      )
+      .body(PROPFIND_BODY)
+      .send()
+      .await?;
+
+    // The collection does not exist until the first upload.
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(Vec::new());
+    }
+    if !response.status().is_success() {
+      return Err(BackupTargetError::Status(response.status()));
+    }
+
+    let body = response.text().await?;
+    let mut names: Vec<String> = HREF_RE
+      .captures_iter(&body)
+      .filter_map(|captures| {
+        let href = captures[1].trim();
+        // Collections, including the listed collection itself, end with
+        // a slash.
+        if href.ends_with('/') {
+          return None;
+        }
+        let name = href.rsplit('/').next()?;
+        let name = urlencoding::decode(name).ok()?.into_owned();
+        validate_name(&name).is_ok().then_some(name)
+      })
+      .collect();
+
+    names.sort();
+    names.dedup();
+    Ok(names)
+  }
+
+  async fn delete(
+    &self,
+    name: &str,
+  ) -> Result<(), BackupTargetError> {
+    let url = self.file_url(name)?;
+    let response = self.request(Method::DELETE, url).send().await?;
+
+    let status = response.status();
+    if status.is_success() || status == StatusCode::NOT_FOUND {
+      Ok(())
+    } else {
+      Err(BackupTargetError::Status(status))
+    }
+  }
+}
+
+pub fn create_backup_target(
+  config: &BackupTargetConfig,
+) -> Result<Box<dyn BackupTarget>, BackupTargetError> {
+  Ok(match config {
+    BackupTargetConfig::Directory { path } => {
+      Box::new(DirectoryTarget::new(PathBuf::from(path)))
+    }
+    BackupTargetConfig::WebDav {
+      url,
+      username,
+      password,
+    } => Box::new(WebDavTarget::new(
+      url,
+      username.clone(),
+      password.clone(),
+    )?),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+  use std::sync::{Arc, Mutex};
+
+  use super::*;
+  use crate::infra::test_utils::{self, Response};
+
+  const COLLECTION: &str = "/dav/backups/";
+
+  #[derive(Default)]
+  struct Server {
+    collection_exists: bool,
+    files: HashMap<String, Vec<u8>>,
+    requests: Vec<String>,
+    fail_uploads: bool,
+  }
+
+  fn handle(
+    server: &mut Server,
+    head: &str,
+    body: Vec<u8>,
+  ) -> (&'static str, Vec<u8>) {
+    let mut request_line = head.lines().next().unwrap().split(' ');
+    let method = request_line.next().unwrap().to_uppercase();
+    let path = request_line.next().unwrap();
+    let name = path.strip_prefix(COLLECTION).unwrap().to_string();
+
+    match (method.as_str(), name.is_empty()) {
+      ("MKCOL", true) if server.collection_exists => {
+        ("405 Method Not Allowed", Vec::new())
+      }
+      ("MKCOL", true) => {
+        server.collection_exists = true;
+        ("201 Created", Vec::new())
+      }
+      ("PROPFIND", true) if !server.collection_exists => {
+        ("404 Not Found", Vec::new())
+      }
+      ("PROPFIND", true) => {
+        let mut xml = format!(
+          r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"><d:response><d:href>{}</d:href></d:response>"#,
+          COLLECTION
+        );
+        for name in server.files.keys() {
+          xml.push_str(&format!(
+            "<d:response><d:href>{}{}</d:href></d:response>",
+            COLLECTION, name
+          ));
+        }
+        xml.push_str("</d:multistatus>");
+        ("207 Multi-Status", xml.into_bytes())
+      }
+      ("PUT", false) if server.fail_uploads => {
+        ("503 Service Unavailable", Vec::new())
+      }
+      ("PUT", false) if server.collection_exists => {
+        server.files.insert(name, body);
+        ("201 Created", Vec::new())
+      }
+      ("GET", false) => match server.files.get(&name) {
+        Some(file) => ("200 OK", file.clone()),
+        None => ("404 Not Found", Vec::new()),
+      },
+      ("DELETE", false) => match server.files.remove(&name) {
+        Some(_) => ("204 No Content", Vec::new()),
+        None => ("404 Not Found", Vec::new()),
+      },
+      _ => ("409 Conflict", Vec::new()),
+    }
+  }
+
+  // Stands in for a WebDAV server that stores files of a single
+  // collection in memory.
+  async fn serve(server: Server) -> (String, Arc<Mutex<Server>>) {
+    let server = Arc::new(Mutex::new(server));
+
+    let state = server.clone();
+    let address = test_utils::serve(move |head, body| {
+      let mut server = state.lock().unwrap();
+      server.requests.push(head.to_string());
+      let (status, body) = handle(&mut server, head, body);
+      Response::new(status, body)
+    })
+    .await;
+
+    let url = format!(
+      "http://{}{}",
+      address,
+      COLLECTION.trim_end_matches('/')
+    );
+    (url, server)
+  }
+
+  fn target(url: &str) -> WebDavTarget {
+    WebDavTarget::new(
+      url,
+      Some("user".to_string()),
+      Some("secret".to_string()),
+    )
+    .unwrap()
+  }
+
+  #[tokio::test]
+  async fn uploads_lists_downloads_and_deletes() {
+    let (url, server) = serve(Server::default()).await;
+    let dir = test_utils::temp_dir("webdav");
+    fs::create_dir_all(&dir).await.unwrap();
+    let source = dir.join("source.zip");
+    let contents = vec![7u8; 100_000];
+    fs::write(&source, &contents).await.unwrap();
+    let target = target(&url);
+
+    assert!(target.list().await.unwrap().is_empty());
+
+    target.upload("backup 1.zip", &source).await.unwrap();
+    target.upload("backup 1.zip.json", &source).await.unwrap();
+    assert_eq!(
+      target.list().await.unwrap(),
+      vec![
+        "backup 1.zip".to_string(),
+        "backup 1.zip.json".to_string()
+      ]
+    );
+
+    let destination = dir.join("destination.zip");
+    target.download("backup 1.zip", &destination).await.unwrap();
+    assert_eq!(fs::read(&destination).await.unwrap(), contents);
+
+    target.delete("backup 1.zip.json").await.unwrap();
+    target.delete("backup 1.zip.json").await.unwrap();
+    assert_eq!(
+      target.list().await.unwrap(),
+      vec!["backup 1.zip".to_string()]
+    );
+
+    let server = server.lock().unwrap();
+    let uploads: Vec<&String> = server
+      .requests
+      .iter()
+      .filter(|request| request.starts_with("put "))
+      .collect();
+    assert_eq!(uploads.len(), 2);
+    for upload in uploads {
+      assert!(upload.starts_with("put /dav/backups/backup%201.zip"));
+      assert!(upload.contains("content-length: 100000"));
+      assert!(upload.contains("authorization: basic "));
+    }
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn missing_file_fails_to_download() {
+    let (url, _) = serve(Server {
+      collection_exists: true,
+      ..Server::default()
+    })
+    .await;
+    let dir = test_utils::temp_dir("webdav");
+    fs::create_dir_all(&dir).await.unwrap();
+
+    let result = target(&url)
+      .download("missing.zip", &dir.join("missing.zip"))
+      .await;
+
+    assert!(matches!(
+      result,
+      Err(BackupTargetError::Status(StatusCode::NOT_FOUND))
+    ));
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn unavailable_server_is_retryable() {
+    let (url, _) = serve(Server {
+      fail_uploads: true,
+      ..Server::default()
+    })
+    .await;
+    let dir = test_utils::temp_dir("webdav");
+    fs::create_dir_all(&dir).await.unwrap();
+    let source = dir.join("source.zip");
+    fs::write(&source, b"backup").await.unwrap();
+
+    let error = target(&url)
+      .upload("backup.zip", &source)
+      .await
+      .unwrap_err();
+
+    assert!(matches!(
+      error,
+      BackupTargetError::Status(StatusCode::SERVICE_UNAVAILABLE)
+    ));
+    assert!(error.is_retryable());
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn names_cannot_escape_the_collection() {
+    let target = target("http://127.0.0.1:9/dav/backups");
+
+    for name in ["../backup.zip", ".hidden", "a/b.zip", ""] {
+      assert!(matches!(
+        target.delete(name).await,
+        Err(BackupTargetError::InvalidName(_))
+      ));
+    }
+  }
+}
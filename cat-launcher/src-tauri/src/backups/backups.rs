@@ -1,15 +1,42 @@
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 
+use crate::backups::contents::{
+  list_worlds, prepare_selection_staging, BackupWorld,
+  RestoreSelection, RestoreSelectionError,
+};
+use crate::backups::integrity::{
+  verify_archive_files, verify_snapshot_files, BackupIntegrityError,
+  BackupVerification,
+};
+use crate::backups::restore::{
+  commit_restore, create_restore_staging_dir,
+  discard_restore_staging_dir, RestoreSaveError,
+};
+use crate::backups::store::{
+  collect_garbage, get_snapshot_size, list_snapshot_files,
+  lock_store, restore_snapshot, restore_snapshot_files,
+  write_snapshot_archive, BackupStoreError,
+};
+use crate::data_profiles::data_profiles::{
+  data_profile_exists, DataProfileError,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::filesystem::paths::{
+  get_automatic_backup_manifest_filepath, get_backup_chunks_dir,
   get_or_create_automatic_backup_archive_filepath,
   get_or_create_user_game_data_dir,
   GetAutomaticBackupArchivePathError, GetUserGameDataDirError,
 };
-use crate::infra::archive::{extract_archive, ExtractionError};
+use crate::infra::archive::{
+  extract_archive, extract_zip_entries, list_zip_entries,
+  ExtractionError,
+};
 use crate::infra::utils::OS;
 use crate::launch_game::repository::{
-  BackupRepository, BackupRepositoryError,
+  BackupEntry, BackupRepository, BackupRepositoryError,
 };
+use crate::running_games::running_games::RunningGamesRegistry;
 use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug)]
@@ -31,36 +58,134 @@ pub async fn list_backups(
   Ok(backups)
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum DeleteBackupError {
-  #[error("failed to get backup entry: {0}")]
-  Get(#[from] BackupRepositoryError),
+async fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+  match tokio::fs::remove_file(path).await {
+    Ok(()) => Ok(()),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(e),
+  }
+}
 
+#[derive(thiserror::Error, Debug)]
+pub enum RemoveBackupFilesError {
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
   #[error("failed to remove backup file: {0}")]
-  RemoveBackupFile(#[from] std::io::Error),
+  Io(#[from] io::Error),
 }
 
-pub async fn delete_backup(
-  id: i64,
+/// Returns the paths a backup's manifest or, if it was taken before backups
+/// were stored as manifests, its archive can have.
+pub async fn get_backup_files(
+  backup: &BackupEntry,
   data_dir: &Path,
-  backup_repository: &impl BackupRepository,
-) -> Result<(), DeleteBackupError> {
-  let backup = backup_repository.get_backup_entry(id).await?;
-  let path = get_or_create_automatic_backup_archive_filepath(
+) -> Result<[PathBuf; 2], GetAutomaticBackupArchivePathError> {
+  let manifest_path = get_automatic_backup_manifest_filepath(
     &backup.game_variant,
     backup.id,
     &backup.release_version,
     backup.timestamp,
     data_dir,
+  );
+  let archive_path = get_archive_path(backup, data_dir).await?;
+
+  Ok([manifest_path, archive_path])
+}
+
+/// Removes the manifest of a backup, or its archive if it was taken before
+/// backups were stored as manifests. Its chunks are left to
+/// `collect_backup_garbage`.
+pub async fn remove_backup_files(
+  backup: &BackupEntry,
+  data_dir: &Path,
+) -> Result<(), RemoveBackupFilesError> {
+  for path in get_backup_files(backup, data_dir).await? {
+    remove_file_if_exists(&path).await?;
+  }
+
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CollectBackupGarbageError {
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to collect unused backup chunks: {0}")]
+  Store(#[from] BackupStoreError),
+}
+
+/// Removes the chunks that no backup in the `backups` table refers to.
+pub async fn collect_backup_garbage(
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+) -> Result<usize, CollectBackupGarbageError> {
+  // Taken before the entries are read, so that a backup added meanwhile
+  // cannot have its new chunks removed.
+  let guard = lock_store().await;
+  let manifest_paths: Vec<_> = backup_repository
+    .get_all_backups()
+    .await?
+    .iter()
+    .map(|backup| {
+      get_automatic_backup_manifest_filepath(
+        &backup.game_variant,
+        backup.id,
+        &backup.release_version,
+        backup.timestamp,
+        data_dir,
+      )
+    })
+    .collect();
+
+  let removed = collect_garbage(
+    &manifest_paths,
+    &get_backup_chunks_dir(data_dir),
+    &guard,
   )
   .await?;
 
+  Ok(removed)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SetBackupPinnedError {
+  #[error("failed to update backup entry: {0}")]
+  Update(#[from] BackupRepositoryError),
+}
+
+pub async fn set_backup_pinned(
+  id: i64,
+  is_pinned: bool,
+  backup_repository: &impl BackupRepository,
+) -> Result<(), SetBackupPinnedError> {
+  backup_repository.set_backup_pinned(id, is_pinned).await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeleteBackupError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to remove backup files: {0}")]
+  RemoveBackupFiles(#[from] RemoveBackupFilesError),
+
+  #[error("failed to collect unused backup chunks: {0}")]
+  CollectGarbage(#[from] CollectBackupGarbageError),
+}
+
+pub async fn delete_backup(
+  id: i64,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+) -> Result<(), DeleteBackupError> {
+  let backup = backup_repository.get_backup_entry(id).await?;
+
   backup_repository.delete_backup_entry(id).await?;
 
-  if let Err(e) = tokio::fs::remove_file(path).await {
+  if let Err(e) = remove_backup_files(&backup, data_dir).await {
     // If we fail to delete the file, we should re-insert the backup entry
     // to avoid having an orphaned file.
     let _ = backup_repository
@@ -68,14 +193,56 @@ pub async fn delete_backup(
         &backup.game_variant,
         &backup.release_version,
         backup.timestamp,
+        backup.data_profile.as_deref(),
+        &backup.scopes,
+        &backup.trigger,
       )
       .await;
-    return Err(DeleteBackupError::RemoveBackupFile(e));
+    return Err(DeleteBackupError::RemoveBackupFiles(e));
   }
 
+  collect_backup_garbage(data_dir, backup_repository).await?;
+
   Ok(())
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum GetBackupSizeError {
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
+
+  #[error("failed to read backup manifest: {0}")]
+  Store(#[from] BackupStoreError),
+
+  #[error("failed to read backup archive: {0}")]
+  Io(#[from] io::Error),
+}
+
+/// Returns the total size of the files in a backup. For backups taken
+/// before the chunk store was introduced, this is the size of the archive.
+pub async fn get_backup_size(
+  backup: &BackupEntry,
+  data_dir: &Path,
+) -> Result<u64, GetBackupSizeError> {
+  if let Some(integrity) = &backup.integrity {
+    return Ok(integrity.size_in_bytes);
+  }
+
+  match get_existing_manifest_path(backup, data_dir).await {
+    Some(manifest_path) => {
+      Ok(get_snapshot_size(&manifest_path).await?)
+    }
+    None => {
+      let archive_path = get_archive_path(backup, data_dir).await?;
+      match tokio::fs::metadata(&archive_path).await {
+        Ok(metadata) => Ok(metadata.len()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e.into()),
+      }
+    }
+  }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum RestoreBackupError {
   #[error("failed to get backup entry: {0}")]
@@ -84,34 +251,463 @@ pub enum RestoreBackupError {
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
+  #[error("failed to extract archive: {0}")]
+  Extract(#[from] ExtractionError),
+
+  #[error("failed to restore backup from store: {0}")]
+  Store(#[from] BackupStoreError),
+
+  #[error("failed to replace save directory: {0}")]
+  Swap(#[from] RestoreSaveError),
+
+  #[error("failed to restore selection: {0}")]
+  Selection(#[from] RestoreSelectionError),
+
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
-  #[error("failed to extract archive: {0}")]
-  Extract(#[from] ExtractionError),
+  #[error("failed to get data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error(
+    "backup was taken from data profile {0}, which was deleted"
+  )]
+  DeletedDataProfile(String),
+
+  #[error("backups cannot be restored while {0} is running")]
+  GameRunning(GameVariant),
+}
+
+// Backups keep the data profile they were taken from after it is deleted,
+// so that they are never restored into another profile.
+async fn ensure_data_profile_exists(
+  backup: &BackupEntry,
+  data_profiles_repository: &dyn DataProfilesRepository,
+) -> Result<(), RestoreBackupError> {
+  let data_profile = backup.data_profile.as_deref();
+  if !data_profile_exists(
+    &backup.game_variant,
+    data_profile,
+    data_profiles_repository,
+  )
+  .await?
+  {
+    return Err(RestoreBackupError::DeletedDataProfile(
+      data_profile.unwrap_or_default().to_string(),
+    ));
+  }
+
+  Ok(())
+}
+
+// Returns the manifest of a backup, or None if the backup was taken before
+// the chunk store was introduced and is a zip archive.
+async fn get_existing_manifest_path(
+  backup: &BackupEntry,
+  data_dir: &Path,
+) -> Option<PathBuf> {
+  let manifest_path = get_automatic_backup_manifest_filepath(
+    &backup.game_variant,
+    backup.id,
+    &backup.release_version,
+    backup.timestamp,
+    data_dir,
+  );
+
+  tokio::fs::try_exists(&manifest_path)
+    .await
+    .unwrap_or(false)
+    .then_some(manifest_path)
+}
+
+async fn get_archive_path(
+  backup: &BackupEntry,
+  data_dir: &Path,
+) -> Result<PathBuf, GetAutomaticBackupArchivePathError> {
+  get_or_create_automatic_backup_archive_filepath(
+    &backup.game_variant,
+    backup.id,
+    &backup.release_version,
+    backup.timestamp,
+    data_dir,
+  )
+  .await
 }
 
+async fn extract_backup(
+  backup: &BackupEntry,
+  target_dir: &Path,
+  data_dir: &Path,
+  os: &OS,
+) -> Result<(), RestoreBackupError> {
+  match get_existing_manifest_path(backup, data_dir).await {
+    Some(manifest_path) => {
+      restore_snapshot(
+        &manifest_path,
+        &get_backup_chunks_dir(data_dir),
+        target_dir,
+      )
+      .await?;
+    }
+    None => {
+      let archive_path = get_archive_path(backup, data_dir).await?;
+      extract_archive(&archive_path, target_dir, os).await?;
+    }
+  }
+
+  Ok(())
+}
+
+async fn extract_backup_selection(
+  backup: &BackupEntry,
+  selection: &RestoreSelection,
+  target_dir: &Path,
+  data_dir: &Path,
+) -> Result<usize, RestoreBackupError> {
+  let filter_selection = selection.clone();
+  let filter = move |path: &str| filter_selection.matches(path);
+
+  let extracted =
+    match get_existing_manifest_path(backup, data_dir).await {
+      Some(manifest_path) => {
+        restore_snapshot_files(
+          &manifest_path,
+          &get_backup_chunks_dir(data_dir),
+          target_dir,
+          filter,
+        )
+        .await?
+      }
+      None => {
+        let archive_path = get_archive_path(backup, data_dir).await?;
+        extract_zip_entries(&archive_path, target_dir, filter).await?
+      }
+    };
+
+  Ok(extracted)
+}
+
+/// Replaces the directories of the backup's scopes with the backup. The
+/// backup is extracted into a staging directory first, so a failed restore
+/// leaves them untouched, and the replaced directories are kept until the
+/// next restore so that the restore can be undone.
 pub async fn restore_backup(
   id: i64,
+  timestamp: u64,
   data_dir: &Path,
   backup_repository: &impl BackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  running_games: &RunningGamesRegistry,
   os: &OS,
 ) -> Result<(), RestoreBackupError> {
   let backup = backup_repository.get_backup_entry(id).await?;
-  let archive_path = get_or_create_automatic_backup_archive_filepath(
+  ensure_data_profile_exists(&backup, data_profiles_repository)
+    .await?;
+  if running_games
+    .is_running(&backup.game_variant, backup.data_profile.as_deref())
+  {
+    return Err(RestoreBackupError::GameRunning(backup.game_variant));
+  }
+
+  let staging_dir = create_restore_staging_dir(data_dir).await?;
+  let result = async {
+    extract_backup(&backup, &staging_dir, data_dir, os).await?;
+    commit_restore(
+      &backup.game_variant,
+      backup.data_profile.as_deref(),
+      &staging_dir,
+      None,
+      &backup.scopes,
+      timestamp,
+      data_dir,
+    )
+    .await?;
+    Ok(())
+  }
+  .await;
+
+  if result.is_err() {
+    discard_restore_staging_dir(&staging_dir).await;
+  }
+
+  result
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListBackupWorldsError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
+
+  #[error("failed to read archive: {0}")]
+  Archive(#[from] ExtractionError),
+
+  #[error("failed to read backup manifest: {0}")]
+  Store(#[from] BackupStoreError),
+}
+
+/// Lists the worlds and characters in a backup without extracting it.
+pub async fn list_backup_worlds(
+  id: i64,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+) -> Result<Vec<BackupWorld>, ListBackupWorldsError> {
+  let backup = backup_repository.get_backup_entry(id).await?;
+
+  let paths = match get_existing_manifest_path(&backup, data_dir)
+    .await
+  {
+    Some(manifest_path) => {
+      list_snapshot_files(&manifest_path).await?
+    }
+    None => {
+      let archive_path = get_archive_path(&backup, data_dir).await?;
+      list_zip_entries(&archive_path).await?
+    }
+  };
+
+  Ok(list_worlds(&paths))
+}
+
+/// Restores a single world, or a single character within a world, from a
+/// backup. The other worlds are left untouched.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_backup_selection(
+  id: i64,
+  selection: &RestoreSelection,
+  timestamp: u64,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  running_games: &RunningGamesRegistry,
+  os: &OS,
+) -> Result<(), RestoreBackupError> {
+  let backup = backup_repository.get_backup_entry(id).await?;
+  ensure_data_profile_exists(&backup, data_profiles_repository)
+    .await?;
+  if running_games
+    .is_running(&backup.game_variant, backup.data_profile.as_deref())
+  {
+    return Err(RestoreBackupError::GameRunning(backup.game_variant));
+  }
+  let user_data_dir = get_or_create_user_game_data_dir(
     &backup.game_variant,
-    backup.id,
-    &backup.release_version,
-    backup.timestamp,
+    backup.data_profile.as_deref(),
     data_dir,
   )
   .await?;
 
-  let user_data_dir =
-    get_or_create_user_game_data_dir(&backup.game_variant, data_dir)
-      .await?;
+  let staging_dir = create_restore_staging_dir(data_dir).await?;
+  let result = async {
+    prepare_selection_staging(
+      selection,
+      &user_data_dir,
+      &staging_dir,
+      os,
+    )
+    .await?;
+
+    let extracted = extract_backup_selection(
+      &backup,
+      selection,
+      &staging_dir,
+      data_dir,
+    )
+    .await?;
+    if extracted == 0 {
+      return Err(RestoreSelectionError::NotInBackup.into());
+    }
+
+    commit_restore(
+      &backup.game_variant,
+      backup.data_profile.as_deref(),
+      &staging_dir,
+      Some(selection.world()),
+      &[],
+      timestamp,
+      data_dir,
+    )
+    .await?;
+    Ok(())
+  }
+  .await;
+
+  if result.is_err() {
+    discard_restore_staging_dir(&staging_dir).await;
+  }
+
+  result
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VerifyBackupError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
+
+  #[error("failed to verify backup: {0}")]
+  Integrity(#[from] BackupIntegrityError),
+}
+
+/// Checks a backup against the checksum recorded when it was created and
+/// reads it in full.
+pub async fn verify_backup(
+  id: i64,
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+) -> Result<BackupVerification, VerifyBackupError> {
+  let backup = backup_repository.get_backup_entry(id).await?;
+  verify_backup_entry(&backup, data_dir).await
+}
+
+pub async fn verify_backup_entry(
+  backup: &BackupEntry,
+  data_dir: &Path,
+) -> Result<BackupVerification, VerifyBackupError> {
+  let expected = backup.integrity.as_ref();
+
+  let verification =
+    match get_existing_manifest_path(backup, data_dir).await {
+      Some(manifest_path) => {
+        verify_snapshot_files(
+          &manifest_path,
+          &get_backup_chunks_dir(data_dir),
+          expected,
+        )
+        .await?
+      }
+      None => {
+        let archive_path = get_archive_path(backup, data_dir).await?;
+        verify_archive_files(&archive_path, expected).await?
+      }
+    };
 
-  extract_archive(&archive_path, &user_data_dir, os).await?;
+  Ok(verification)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WriteBackupArchiveError {
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
+
+  #[error("failed to write backup from store: {0}")]
+  Store(#[from] BackupStoreError),
+
+  #[error("failed to copy backup archive: {0}")]
+  Io(#[from] io::Error),
+}
+
+/// Writes a backup to `destination` as a zip archive, whether it is stored
+/// in the chunk store or as an archive.
+pub async fn write_backup_archive(
+  backup: &BackupEntry,
+  destination: &Path,
+  data_dir: &Path,
+) -> Result<(), WriteBackupArchiveError> {
+  match get_existing_manifest_path(backup, data_dir).await {
+    Some(manifest_path) => {
+      write_snapshot_archive(
+        &manifest_path,
+        &get_backup_chunks_dir(data_dir),
+        destination,
+      )
+      .await?;
+    }
+    None => {
+      let archive_path = get_archive_path(backup, data_dir).await?;
+      tokio::fs::copy(&archive_path, destination).await?;
+    }
+  }
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use r2d2_sqlite::SqliteConnectionManager;
+
+  use super::*;
+  use crate::backups::scopes::default_backup_scopes;
+  use crate::backups::triggers::BackupTrigger;
+  use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+  use crate::filesystem::paths::get_user_game_data_dir;
+  use crate::infra::repository::db_schema::initialize_schema;
+  use crate::infra::test_utils;
+  use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+
+  #[tokio::test]
+  async fn backup_of_deleted_profile_is_not_restored() {
+    let data_dir = test_utils::temp_dir("backups");
+    std::fs::create_dir_all(&data_dir).unwrap();
+
+    let manager =
+      SqliteConnectionManager::file(data_dir.join("db.sqlite"))
+        .with_init(|conn| {
+          conn.pragma_update(None, "foreign_keys", "ON")
+        });
+    let pool = r2d2::Pool::new(manager).unwrap();
+    let schema_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+      .join("schemas")
+      .join("schema.sql");
+    initialize_schema(&pool.get().unwrap(), &[schema_path]).unwrap();
+
+    let variant = GameVariant::DarkDaysAhead;
+    let backup_repository = SqliteBackupRepository::new(pool.clone());
+    let data_profiles_repository =
+      SqliteDataProfilesRepository::new(pool);
+
+    data_profiles_repository
+      .add_data_profile(&variant, "Alt", 1)
+      .await
+      .unwrap();
+    let id = backup_repository
+      .add_backup_entry(
+        &variant,
+        "0.H",
+        2,
+        Some("Alt"),
+        &default_backup_scopes(),
+        &BackupTrigger::BeforeLaunch,
+      )
+      .await
+      .unwrap();
+    data_profiles_repository
+      .delete_data_profile(&variant, "Alt")
+      .await
+      .unwrap();
+
+    let default_save_dir =
+      get_user_game_data_dir(&variant, None, &data_dir).join("save");
+    std::fs::create_dir_all(&default_save_dir).unwrap();
+    let marker = default_save_dir.join("marker");
+    std::fs::write(&marker, "default profile").unwrap();
+
+    let result = restore_backup(
+      id,
+      3,
+      &data_dir,
+      &backup_repository,
+      &data_profiles_repository,
+      &RunningGamesRegistry::default(),
+      &OS::Linux,
+    )
+    .await;
+
+    assert!(matches!(
+      result,
+      Err(RestoreBackupError::DeletedDataProfile(name)) if name == "Alt"
+    ));
+    assert_eq!(
+      std::fs::read_to_string(&marker).unwrap(),
+      "default profile"
+    );
+
+    std::fs::remove_dir_all(&data_dir).unwrap();
+  }
+}
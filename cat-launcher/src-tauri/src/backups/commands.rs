@@ -1,15 +1,40 @@
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
 use strum::IntoStaticStr;
 use tauri::{Manager, State};
 
 use cat_macros::CommandErrorSerialize;
 
+use crate::backup_settings::repository::BackupKind;
 use crate::backups::backups::{
-  delete_backup, list_backups, restore_backup, DeleteBackupError,
-  ListBackupsError, RestoreBackupError,
+  delete_backup, list_backup_worlds, list_backups, restore_backup,
+  restore_backup_selection, set_backup_pinned as set_backup_pinned_impl,
+  verify_backup as verify_backup_impl, DeleteBackupError,
+  ListBackupWorldsError, ListBackupsError, RestoreBackupError,
+  SetBackupPinnedError, VerifyBackupError,
+};
+use crate::backups::contents::{BackupWorld, RestoreSelection};
+use crate::backups::integrity::{
+  verify_all_backups as verify_all_backups_impl, BackupVerification,
+  BackupVerificationReport, VerifyAllBackupsError,
+};
+use crate::backups::restore::{
+  get_last_restore as get_last_restore_impl,
+  undo_last_restore as undo_last_restore_impl, LastRestore,
+  RestoreSaveError,
 };
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
 use crate::launch_game::repository::BackupEntry;
+use crate::manual_backups::manual_backups::{
+  verify_manual_backup, VerifyManualBackupError,
+};
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
 use crate::variants::GameVariant;
 
 #[derive(
@@ -30,6 +55,25 @@ pub async fn list_backups_for_variant(
   Ok(backups)
 }
 
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum SetBackupPinnedCommandError {
+  #[error("failed to pin backup: {0}")]
+  SetPinned(#[from] SetBackupPinnedError),
+}
+
+#[tauri::command]
+pub async fn set_backup_pinned(
+  id: i64,
+  is_pinned: bool,
+  backup_repository: State<'_, SqliteBackupRepository>,
+) -> Result<(), SetBackupPinnedCommandError> {
+  set_backup_pinned_impl(id, is_pinned, backup_repository.inner())
+    .await?;
+  Ok(())
+}
+
 #[derive(
   thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
 )]
@@ -61,6 +105,8 @@ pub enum RestoreBackupCommandError {
   DataDir(#[from] tauri::Error),
   #[error("unsupported OS: {0}")]
   UnsupportedOS(#[from] OSNotSupportedError),
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
 }
 
 #[tauri::command]
@@ -68,10 +114,183 @@ pub async fn restore_backup_by_id(
   id: i64,
   app_handle: tauri::AppHandle,
   backup_repository: State<'_, SqliteBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
 ) -> Result<(), RestoreBackupCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let os = get_os_enum(std::env::consts::OS)?;
-  restore_backup(id, &data_dir, backup_repository.inner(), &os)
-    .await?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  restore_backup(
+    id,
+    timestamp,
+    &data_dir,
+    backup_repository.inner(),
+    &*data_profiles_repository,
+    &running_games,
+    &os,
+  )
+  .await?;
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn restore_backup_selection_by_id(
+  id: i64,
+  selection: RestoreSelection,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), RestoreBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  restore_backup_selection(
+    id,
+    &selection,
+    timestamp,
+    &data_dir,
+    backup_repository.inner(),
+    &*data_profiles_repository,
+    &running_games,
+    &os,
+  )
+  .await?;
+  Ok(())
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum ListBackupWorldsCommandError {
+  #[error("failed to list backup worlds: {0}")]
+  List(#[from] ListBackupWorldsError),
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+}
+
+#[tauri::command]
+pub async fn list_backup_worlds_by_id(
+  id: i64,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+) -> Result<Vec<BackupWorld>, ListBackupWorldsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let worlds =
+    list_backup_worlds(id, &data_dir, backup_repository.inner())
+      .await?;
+  Ok(worlds)
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum UndoRestoreCommandError {
+  #[error("failed to undo restore: {0}")]
+  Restore(#[from] RestoreSaveError),
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+}
+
+/// Returns the last restore into the active data profile that can still be
+/// undone, if any.
+#[tauri::command]
+pub async fn get_last_restore(
+  variant: GameVariant,
+  app_handle: tauri::AppHandle,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Option<LastRestore>, UndoRestoreCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+  let last_restore = get_last_restore_impl(
+    &variant,
+    data_profile.as_deref(),
+    &data_dir,
+  )
+  .await?;
+  Ok(last_restore)
+}
+
+#[tauri::command]
+pub async fn undo_last_restore(
+  variant: GameVariant,
+  app_handle: tauri::AppHandle,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), UndoRestoreCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+  undo_last_restore_impl(
+    &variant,
+    data_profile.as_deref(),
+    &data_dir,
+    &running_games,
+  )
+  .await?;
   Ok(())
 }
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum VerifyBackupCommandError {
+  #[error("failed to verify backup: {0}")]
+  Verify(#[from] VerifyBackupError),
+  #[error("failed to verify manual backup: {0}")]
+  VerifyManual(#[from] VerifyManualBackupError),
+  #[error("failed to verify backups: {0}")]
+  VerifyAll(#[from] VerifyAllBackupsError),
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+}
+
+/// Recomputes the checksum of a backup and reads it in full.
+#[tauri::command]
+pub async fn verify_backup(
+  id: i64,
+  kind: BackupKind,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+) -> Result<BackupVerification, VerifyBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let verification = match kind {
+    BackupKind::Automatic => {
+      verify_backup_impl(id, &data_dir, backup_repository.inner())
+        .await?
+    }
+    BackupKind::Manual => {
+      verify_manual_backup(
+        id,
+        &data_dir,
+        manual_backup_repository.inner(),
+      )
+      .await?
+    }
+  };
+  Ok(verification)
+}
+
+#[tauri::command]
+pub async fn verify_all_backups(
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  manual_backup_repository: State<'_, SqliteManualBackupRepository>,
+) -> Result<BackupVerificationReport, VerifyBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let report = verify_all_backups_impl(
+    &data_dir,
+    backup_repository.inner(),
+    manual_backup_repository.inner(),
+  )
+  .await?;
+  Ok(report)
+}
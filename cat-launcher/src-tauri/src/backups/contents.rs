@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use ts_rs::TS;
+
+use crate::filesystem::utils::{
+  copy_dir_all, join_relative_path, CopyDirError,
+};
+use crate::infra::utils::OS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupWorld {
+  pub name: String,
+  pub characters: Vec<BackupCharacter>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupCharacter {
+  pub name: String,
+  /// The name all of the character's save files start with.
+  pub save_prefix: String,
+}
+
+/// The part of a backup to restore.
+#[derive(Debug, Clone, Deserialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", content = "payload")]
+pub enum RestoreSelection {
+  World { world: String },
+  Character { world: String, save_prefix: String },
+}
+
+impl RestoreSelection {
+  pub fn world(&self) -> &str {
+    match self {
+      RestoreSelection::World { world } => world,
+      RestoreSelection::Character { world, .. } => world,
+    }
+  }
+
+  /// Whether a `/` separated path in a backup belongs to the selection.
+  pub fn matches(&self, path: &str) -> bool {
+    let mut parts = path.split('/');
+    if parts.next() != Some("save")
+      || parts.next() != Some(self.world())
+    {
+      return false;
+    }
+
+    match (self, parts.next()) {
+      (_, None) | (_, Some("")) => false,
+      (RestoreSelection::World { .. }, Some(_)) => true,
+      (
+        RestoreSelection::Character { save_prefix, .. },
+        Some(name),
+      ) => is_character_file(name, save_prefix),
+    }
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RestoreSelectionError {
+  #[error("invalid world name: {0}")]
+  InvalidWorld(String),
+
+  #[error("world {0} does not exist")]
+  WorldNotFound(String),
+
+  #[error(
+    "the backup does not contain the selected world or character"
+  )]
+  NotInBackup,
+
+  #[error("failed to prepare restore: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to copy world: {0}")]
+  Copy(#[from] CopyDirError),
+}
+
+// A character's files are named after its save prefix, e.g. `#Qm9i.sav`,
+// `#Qm9i.seen` and the `#Qm9i.mm1` directory.
+fn is_character_file(name: &str, save_prefix: &str) -> bool {
+  name
+    .strip_prefix(save_prefix)
+    .is_some_and(|rest| rest.starts_with('.'))
+}
+
+fn decode_base64(input: &str) -> Option<Vec<u8>> {
+  let mut bytes = Vec::new();
+  let mut buffer = 0u32;
+  let mut bits = 0;
+
+  for byte in input.bytes().take_while(|&byte| byte != b'=') {
+    let value = match byte {
+      b'A'..=b'Z' => byte - b'A',
+      b'a'..=b'z' => byte - b'a' + 26,
+      b'0'..=b'9' => byte - b'0' + 52,
+      b'+' => 62,
+      b'/' => 63,
+      _ => return None,
+    };
+
+    buffer = (buffer << 6) | u32::from(value);
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      bytes.push((buffer >> bits) as u8);
+      buffer &= (1 << bits) - 1;
+    }
+  }
+
+  Some(bytes)
+}
+
+// Newer versions of the game name save files after the base64 encoded
+// character name, prefixed with `#`.
+pub fn decode_character_name(save_prefix: &str) -> String {
+  save_prefix
+    .strip_prefix('#')
+    .and_then(decode_base64)
+    .and_then(|bytes| String::from_utf8(bytes).ok())
+    .unwrap_or_else(|| save_prefix.to_string())
+}
+
+/// Finds the worlds and characters among the `/` separated paths of a
+/// backup.
+pub fn list_worlds(paths: &[String]) -> Vec<BackupWorld> {
+  let mut worlds: BTreeMap<&str, Vec<BackupCharacter>> =
+    BTreeMap::new();
+
+  for path in paths {
+    let parts: Vec<&str> =
+      path.split('/').filter(|part| !part.is_empty()).collect();
+    let ["save", world, rest @ ..] = parts.as_slice() else {
+      continue;
+    };
+
+    let characters = worlds.entry(*world).or_default();
+    if let [file] = rest {
+      if let Some(save_prefix) = file.strip_suffix(".sav") {
+        characters.push(BackupCharacter {
+          name: decode_character_name(save_prefix),
+          save_prefix: save_prefix.to_string(),
+        });
+      }
+    }
+  }
+
+  worlds
+    .into_iter()
+    .map(|(name, characters)| BackupWorld {
+      name: name.to_string(),
+      characters,
+    })
+    .collect()
+}
+
+fn get_world_dir(
+  root: &Path,
+  world: &str,
+) -> Result<PathBuf, RestoreSelectionError> {
+  let save_dir = root.join("save");
+  join_relative_path(&save_dir, world)
+    .filter(|path| path.parent() == Some(save_dir.as_path()))
+    .ok_or_else(|| {
+      RestoreSelectionError::InvalidWorld(world.to_string())
+    })
+}
+
+/// Prepares `staging_dir` for the selected files to be extracted into it.
+/// A character is restored into a copy of the current world without the
+/// character's current files, so that the rest of the world is kept.
+pub async fn prepare_selection_staging(
+  selection: &RestoreSelection,
+  user_data_dir: &Path,
+  staging_dir: &Path,
+  os: &OS,
+) -> Result<(), RestoreSelectionError> {
+  let world = selection.world();
+  let staged_world_dir = get_world_dir(staging_dir, world)?;
+
+  let RestoreSelection::Character { save_prefix, .. } = selection
+  else {
+    return Ok(());
+  };
+
+  let current_world_dir = get_world_dir(user_data_dir, world)?;
+  if !fs::try_exists(&current_world_dir).await? {
+    return Err(RestoreSelectionError::WorldNotFound(
+      world.to_string(),
+    ));
+  }
+
+  copy_dir_all(&current_world_dir, &staged_world_dir, os).await?;
+
+  let mut entries = fs::read_dir(&staged_world_dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    if !is_character_file(
+      &entry.file_name().to_string_lossy(),
+      save_prefix,
+    ) {
+      continue;
+    }
+
+    if entry.file_type().await?.is_dir() {
+      fs::remove_dir_all(entry.path()).await?;
+    } else {
+      fs::remove_file(entry.path()).await?;
+    }
+  }
+
+  Ok(())
+}
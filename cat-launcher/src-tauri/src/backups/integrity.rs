@@ -0,0 +1,333 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::backup_settings::repository::BackupKind;
+use crate::backups::backups::{
+  get_backup_files, verify_backup_entry, VerifyBackupError,
+};
+use crate::backups::contents::list_worlds;
+use crate::backups::store::{
+  read_snapshot_manifest, verify_snapshot, BackupStoreError,
+};
+use crate::filesystem::paths::{
+  get_or_create_automatic_backups_dir,
+  get_or_create_manual_backups_dir, GetAutomaticBackupsDirError,
+  GetManualBackupsDirError,
+};
+use crate::infra::archive::{
+  list_zip_entries, test_zip_archive, ExtractionError,
+};
+use crate::infra::checksum::{
+  compute_file_sha256, ComputeSha256Error,
+};
+use crate::launch_game::repository::{
+  BackupRepository, BackupRepositoryError,
+};
+use crate::manual_backups::manual_backups::{
+  get_manual_backup_archive_path, verify_manual_backup_entry,
+  VerifyManualBackupError,
+};
+use crate::manual_backups::repository::manual_backup_repository::{
+  ManualBackupRepository, ManualBackupRepositoryError,
+};
+
+/// What a backup contained when it was created.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BackupIntegrity {
+  /// The total size of the backed up files, or the size of the archive
+  /// for backups stored as zip archives.
+  pub size_in_bytes: u64,
+  /// The SHA-256 of the backup's manifest or archive.
+  pub sha256: String,
+  pub file_count: u64,
+  /// The names of the worlds in the backup.
+  pub worlds: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupIntegrityError {
+  #[error("failed to compute checksum: {0}")]
+  Checksum(#[from] ComputeSha256Error),
+
+  #[error("failed to read backup manifest: {0}")]
+  Store(#[from] BackupStoreError),
+
+  #[error("failed to read archive: {0}")]
+  Archive(#[from] ExtractionError),
+
+  #[error("failed to read backup file: {0}")]
+  Io(#[from] io::Error),
+}
+
+/// The outcome of verifying a backup.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+#[ts(export)]
+#[serde(tag = "type", content = "payload")]
+pub enum BackupVerification {
+  /// The backup can be read in full. Its checksum is not verified if the
+  /// backup was created before checksums were recorded.
+  Valid {
+    checksum_verified: bool,
+  },
+  /// The files of the backup do not exist.
+  Missing,
+  ChecksumMismatch {
+    expected: String,
+    actual: String,
+  },
+  Unreadable {
+    message: String,
+  },
+}
+
+fn get_world_names(paths: &[String]) -> Vec<String> {
+  list_worlds(paths)
+    .into_iter()
+    .map(|world| world.name)
+    .collect()
+}
+
+/// Describes a backup stored in the chunk store by its manifest.
+pub async fn compute_snapshot_integrity(
+  manifest_path: &Path,
+) -> Result<BackupIntegrity, BackupIntegrityError> {
+  let manifest = read_snapshot_manifest(manifest_path).await?;
+  let paths: Vec<String> = manifest
+    .files
+    .iter()
+    .map(|file| file.path.clone())
+    .collect();
+
+  Ok(BackupIntegrity {
+    size_in_bytes: manifest.files.iter().map(|file| file.size).sum(),
+    sha256: compute_file_sha256(manifest_path).await?,
+    file_count: paths.len() as u64,
+    worlds: get_world_names(&paths),
+  })
+}
+
+/// Describes a backup stored as a zip archive.
+pub async fn compute_archive_integrity(
+  archive_path: &Path,
+) -> Result<BackupIntegrity, BackupIntegrityError> {
+  let entries = list_zip_entries(archive_path).await?;
+  let metadata = tokio::fs::metadata(archive_path).await?;
+
+  Ok(BackupIntegrity {
+    size_in_bytes: metadata.len(),
+    sha256: compute_file_sha256(archive_path).await?,
+    file_count: entries
+      .iter()
+      .filter(|entry| !entry.ends_with('/'))
+      .count() as u64,
+    worlds: get_world_names(&entries),
+  })
+}
+
+// Returns the verification to report if the file is missing or does not
+// match the recorded checksum, or None if reading it should be tried.
+async fn check_checksum(
+  path: &Path,
+  expected: Option<&BackupIntegrity>,
+) -> Result<Option<BackupVerification>, BackupIntegrityError> {
+  if !tokio::fs::try_exists(path).await? {
+    return Ok(Some(BackupVerification::Missing));
+  }
+
+  let Some(expected) = expected else {
+    return Ok(None);
+  };
+
+  let actual = compute_file_sha256(path).await?;
+  if actual != expected.sha256 {
+    return Ok(Some(BackupVerification::ChecksumMismatch {
+      expected: expected.sha256.clone(),
+      actual,
+    }));
+  }
+
+  Ok(None)
+}
+
+/// Checks the manifest of a backup against its recorded checksum and
+/// reads every chunk it refers to.
+pub async fn verify_snapshot_files(
+  manifest_path: &Path,
+  chunks_dir: &Path,
+  expected: Option<&BackupIntegrity>,
+) -> Result<BackupVerification, BackupIntegrityError> {
+  if let Some(verification) =
+    check_checksum(manifest_path, expected).await?
+  {
+    return Ok(verification);
+  }
+
+  Ok(match verify_snapshot(manifest_path, chunks_dir).await {
+    Ok(()) => BackupVerification::Valid {
+      checksum_verified: expected.is_some(),
+    },
+    Err(e) => BackupVerification::Unreadable {
+      message: e.to_string(),
+    },
+  })
+}
+
+/// Checks the archive of a backup against its recorded checksum and reads
+/// every entry in it.
+pub async fn verify_archive_files(
+  archive_path: &Path,
+  expected: Option<&BackupIntegrity>,
+) -> Result<BackupVerification, BackupIntegrityError> {
+  if let Some(verification) =
+    check_checksum(archive_path, expected).await?
+  {
+    return Ok(verification);
+  }
+
+  Ok(match test_zip_archive(archive_path).await {
+    Ok(()) => BackupVerification::Valid {
+      checksum_verified: expected.is_some(),
+    },
+    Err(e) => BackupVerification::Unreadable {
+      message: e.to_string(),
+    },
+  })
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct VerifiedBackup {
+  pub kind: BackupKind,
+  pub id: i64,
+  pub verification: BackupVerification,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct BackupVerificationReport {
+  pub backups: Vec<VerifiedBackup>,
+  /// Backup entries whose files no longer exist.
+  pub orphaned_entries: Vec<VerifiedBackup>,
+  /// Files in the backup directories that no backup entry refers to.
+  pub orphaned_files: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VerifyAllBackupsError {
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get manual backup entries: {0}")]
+  GetManual(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to verify backup: {0}")]
+  Verify(#[from] VerifyBackupError),
+
+  #[error("failed to verify manual backup: {0}")]
+  VerifyManual(#[from] VerifyManualBackupError),
+
+  #[error("failed to get automatic backups directory: {0}")]
+  AutomaticBackupsDir(#[from] GetAutomaticBackupsDirError),
+
+  #[error("failed to get manual backups directory: {0}")]
+  ManualBackupsDir(#[from] GetManualBackupsDirError),
+
+  #[error("failed to read backups directory: {0}")]
+  Io(#[from] io::Error),
+}
+
+// A backup that cannot be verified does not stop the others from being
+// verified.
+fn into_unreadable(e: impl std::error::Error) -> BackupVerification {
+  BackupVerification::Unreadable {
+    message: e.to_string(),
+  }
+}
+
+async fn list_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  let mut entries = tokio::fs::read_dir(dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_file() {
+      files.push(entry.path());
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+/// Verifies every automatic and manual backup, and looks for entries whose
+/// files are gone and for files that no entry refers to.
+pub async fn verify_all_backups(
+  data_dir: &Path,
+  backup_repository: &impl BackupRepository,
+  manual_backup_repository: &impl ManualBackupRepository,
+) -> Result<BackupVerificationReport, VerifyAllBackupsError> {
+  let mut backups = Vec::new();
+  let mut known_files = HashSet::new();
+
+  for backup in backup_repository.get_all_backups().await? {
+    known_files.extend(
+      get_backup_files(&backup, data_dir)
+        .await
+        .map_err(VerifyBackupError::from)?,
+    );
+    backups.push(VerifiedBackup {
+      kind: BackupKind::Automatic,
+      id: backup.id,
+      verification: verify_backup_entry(&backup, data_dir)
+        .await
+        .unwrap_or_else(into_unreadable),
+    });
+  }
+
+  for backup in
+    manual_backup_repository.get_all_manual_backups().await?
+  {
+    known_files.insert(
+      get_manual_backup_archive_path(&backup, data_dir)
+        .await
+        .map_err(VerifyManualBackupError::from)?,
+    );
+    backups.push(VerifiedBackup {
+      kind: BackupKind::Manual,
+      id: backup.id,
+      verification: verify_manual_backup_entry(&backup, data_dir)
+        .await
+        .unwrap_or_else(into_unreadable),
+    });
+  }
+
+  let orphaned_entries = backups
+    .iter()
+    .filter(|backup| {
+      backup.verification == BackupVerification::Missing
+    })
+    .cloned()
+    .collect();
+
+  let mut orphaned_files = Vec::new();
+  for dir in [
+    get_or_create_automatic_backups_dir(data_dir).await?,
+    get_or_create_manual_backups_dir(data_dir).await?,
+  ] {
+    orphaned_files.extend(
+      list_files(&dir)
+        .await?
+        .into_iter()
+        .filter(|file| !known_files.contains(file))
+        .map(|file| file.display().to_string()),
+    );
+  }
+
+  Ok(BackupVerificationReport {
+    backups,
+    orphaned_entries,
+    orphaned_files,
+  })
+}
@@ -1,2 +1,9 @@
 pub mod backups;
 pub mod commands;
+pub mod contents;
+pub mod integrity;
+pub mod restore;
+pub mod retention;
+pub mod scopes;
+pub mod store;
+pub mod triggers;
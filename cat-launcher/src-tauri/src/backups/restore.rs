@@ -0,0 +1,335 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::backups::scopes::{default_backup_scopes, BackupScope};
+use crate::filesystem::paths::{
+  get_or_create_user_game_data_dir, get_pre_restore_dir,
+  get_restore_staging_dir, GetUserGameDataDirError,
+};
+use crate::filesystem::utils::join_relative_path;
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+
+const LAST_RESTORE_FILENAME: &str = "restore.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LastRestore {
+  pub game_variant: GameVariant,
+  pub data_profile: Option<String>,
+  /// The world that was restored, or None if whole scopes were.
+  pub world: Option<String>,
+  /// The scopes that were restored if no single world was.
+  #[serde(default = "default_backup_scopes")]
+  pub scopes: Vec<BackupScope>,
+  /// When the save directory was replaced by the restored backup.
+  pub restored_at: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RestoreSaveError {
+  #[error("failed to swap save directory: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to read or write restore metadata: {0}")]
+  Metadata(#[from] serde_json::Error),
+
+  #[error("failed to get user game data directory: {0}")]
+  UserGameDataDir(#[from] GetUserGameDataDirError),
+
+  #[error("invalid world name: {0}")]
+  InvalidWorld(String),
+
+  #[error("there is no restore to undo")]
+  NothingToUndo,
+
+  #[error("restores cannot be undone while {0} is running")]
+  GameRunning(GameVariant),
+}
+
+struct RestoredDir {
+  /// The path relative to the user data directory.
+  path: PathBuf,
+  /// The name the replaced directory is kept under in the pre-restore
+  /// directory, so that the restore can be undone.
+  snapshot_name: &'static str,
+}
+
+// The directories below the user data directory that a restore replaces.
+fn get_restored_dirs(
+  world: Option<&str>,
+  scopes: &[BackupScope],
+) -> Result<Vec<RestoredDir>, RestoreSaveError> {
+  match world {
+    None => Ok(
+      scopes
+        .iter()
+        .map(|scope| RestoredDir {
+          path: PathBuf::from(scope.dir_name()),
+          snapshot_name: scope.dir_name(),
+        })
+        .collect(),
+    ),
+    Some(world) => {
+      let save_dir = Path::new(BackupScope::Save.dir_name());
+      let path = join_relative_path(save_dir, world)
+        .filter(|path| path.parent() == Some(save_dir))
+        .ok_or_else(|| {
+          RestoreSaveError::InvalidWorld(world.to_string())
+        })?;
+      Ok(vec![RestoredDir {
+        path,
+        snapshot_name: "world",
+      }])
+    }
+  }
+}
+
+async fn remove_dir_if_exists(path: &Path) -> io::Result<()> {
+  match fs::remove_dir_all(path).await {
+    Ok(()) => Ok(()),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+    Err(e) => Err(e),
+  }
+}
+
+/// Creates an empty directory for a backup to be extracted into. It stands
+/// in for the user data directory, so backups put their `save` directory
+/// directly below it.
+pub async fn create_restore_staging_dir(
+  data_dir: &Path,
+) -> Result<PathBuf, RestoreSaveError> {
+  let dir = get_restore_staging_dir(data_dir)
+    .join(Uuid::new_v4().to_string());
+  fs::create_dir_all(&dir).await?;
+
+  Ok(dir)
+}
+
+pub async fn discard_restore_staging_dir(staging_dir: &Path) {
+  // A leftover staging directory is harmless.
+  let _ = remove_dir_if_exists(staging_dir).await;
+}
+
+// Moves `target` to `displaced` and `replacement` into its place. The
+// staging and pre-restore directories are on the same file system as the
+// save directory, so both moves are renames. If the second one fails, the
+// first is reverted.
+async fn swap_dir(
+  target: &Path,
+  replacement: &Path,
+  displaced: &Path,
+) -> io::Result<()> {
+  if fs::try_exists(target).await? {
+    fs::rename(target, displaced).await?;
+  } else {
+    fs::create_dir_all(displaced).await?;
+  }
+
+  if let Some(parent) = target.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  if let Err(e) = fs::rename(replacement, target).await {
+    let _ = fs::rename(displaced, target).await;
+    return Err(e);
+  }
+
+  Ok(())
+}
+
+// Swaps each (target, replacement, displaced) triple with `swap_dir`. If
+// one of the swaps fails, the ones before it are reverted.
+async fn swap_dirs(
+  swaps: &[(PathBuf, PathBuf, PathBuf)],
+) -> io::Result<()> {
+  for (index, (target, replacement, displaced)) in
+    swaps.iter().enumerate()
+  {
+    if let Err(e) = swap_dir(target, replacement, displaced).await {
+      for (target, replacement, displaced) in
+        swaps[..index].iter().rev()
+      {
+        let _ = fs::rename(target, replacement).await;
+        let _ = fs::rename(displaced, target).await;
+      }
+      return Err(e);
+    }
+  }
+
+  Ok(())
+}
+
+// Puts `snapshot_dir` in place of `pre_restore_dir`. The snapshot of an
+// earlier restore is moved to `displaced` first and only removed once
+// the new one is in place.
+async fn replace_pre_restore_dir(
+  pre_restore_dir: &Path,
+  snapshot_dir: &Path,
+  displaced: &Path,
+) -> io::Result<()> {
+  let existed = fs::try_exists(pre_restore_dir).await?;
+  if existed {
+    fs::rename(pre_restore_dir, displaced).await?;
+  } else if let Some(parent) = pre_restore_dir.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  if let Err(e) = fs::rename(snapshot_dir, pre_restore_dir).await {
+    if existed {
+      let _ = fs::rename(displaced, pre_restore_dir).await;
+    }
+    return Err(e);
+  }
+
+  // A leftover snapshot in the staging directory is harmless.
+  let _ = remove_dir_if_exists(displaced).await;
+
+  Ok(())
+}
+
+/// Swaps the directories of `scopes` extracted into `staging_dir` in for
+/// the current ones, or only the given world if `world` is set. The
+/// replaced directories become the pre-restore snapshot, which replaces the
+/// snapshot of any earlier restore once the swap has succeeded.
+pub async fn commit_restore(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  staging_dir: &Path,
+  world: Option<&str>,
+  scopes: &[BackupScope],
+  restored_at: u64,
+  data_dir: &Path,
+) -> Result<(), RestoreSaveError> {
+  let user_data_dir =
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
+
+  // The snapshot is built next to the staging directory, so that the
+  // snapshot of an earlier restore is kept if the swap fails.
+  let snapshot_dir = get_restore_staging_dir(data_dir)
+    .join(Uuid::new_v4().to_string());
+  fs::create_dir_all(&snapshot_dir).await?;
+
+  let mut swaps = Vec::new();
+  for restored_dir in get_restored_dirs(world, scopes)? {
+    // A backup taken before the first save has no save directory, and a
+    // scope that did not exist yet is restored as empty.
+    let replacement = staging_dir.join(&restored_dir.path);
+    fs::create_dir_all(&replacement).await?;
+
+    swaps.push((
+      user_data_dir.join(&restored_dir.path),
+      replacement,
+      snapshot_dir.join(restored_dir.snapshot_name),
+    ));
+  }
+  swap_dirs(&swaps).await?;
+
+  let last_restore = LastRestore {
+    game_variant: *variant,
+    data_profile: data_profile.map(str::to_string),
+    world: world.map(str::to_string),
+    scopes: scopes.to_vec(),
+    restored_at,
+  };
+  fs::write(
+    snapshot_dir.join(LAST_RESTORE_FILENAME),
+    serde_json::to_vec(&last_restore)?,
+  )
+  .await?;
+
+  replace_pre_restore_dir(
+    &get_pre_restore_dir(variant, data_profile, data_dir),
+    &snapshot_dir,
+    &get_restore_staging_dir(data_dir)
+      .join(Uuid::new_v4().to_string()),
+  )
+  .await?;
+
+  discard_restore_staging_dir(staging_dir).await;
+
+  Ok(())
+}
+
+pub async fn get_last_restore(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> Result<Option<LastRestore>, RestoreSaveError> {
+  let path = get_pre_restore_dir(variant, data_profile, data_dir)
+    .join(LAST_RESTORE_FILENAME);
+
+  match fs::read(&path).await {
+    Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+    Err(e) => Err(e.into()),
+  }
+}
+
+/// Puts the pre-restore snapshot back in place of the restored directories.
+/// The restored directories are discarded, as they can be restored again
+/// from their backup.
+pub async fn undo_last_restore(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), RestoreSaveError> {
+  if running_games.is_running(variant, data_profile) {
+    return Err(RestoreSaveError::GameRunning(*variant));
+  }
+
+  let Some(last_restore) =
+    get_last_restore(variant, data_profile, data_dir).await?
+  else {
+    return Err(RestoreSaveError::NothingToUndo);
+  };
+
+  let pre_restore_dir =
+    get_pre_restore_dir(variant, data_profile, data_dir);
+  let user_data_dir =
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
+  let staging_dir = create_restore_staging_dir(data_dir).await?;
+  let discarded_dir = staging_dir.join("discarded");
+  fs::create_dir_all(&discarded_dir).await?;
+
+  let mut swaps = Vec::new();
+  for restored_dir in get_restored_dirs(
+    last_restore.world.as_deref(),
+    &last_restore.scopes,
+  )? {
+    let snapshot_dir =
+      pre_restore_dir.join(restored_dir.snapshot_name);
+    if !fs::try_exists(&snapshot_dir).await? {
+      continue;
+    }
+
+    swaps.push((
+      user_data_dir.join(&restored_dir.path),
+      snapshot_dir,
+      discarded_dir.join(restored_dir.snapshot_name),
+    ));
+  }
+
+  if swaps.is_empty() {
+    discard_restore_staging_dir(&staging_dir).await;
+    return Err(RestoreSaveError::NothingToUndo);
+  }
+
+  if let Err(e) = swap_dirs(&swaps).await {
+    discard_restore_staging_dir(&staging_dir).await;
+    return Err(e.into());
+  }
+
+  remove_dir_if_exists(&pre_restore_dir).await?;
+  discard_restore_staging_dir(&staging_dir).await;
+
+  Ok(())
+}
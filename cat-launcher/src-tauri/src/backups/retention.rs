@@ -0,0 +1,155 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use tokio::task::JoinSet;
+
+use crate::backup_settings::repository::BackupRetentionPolicy;
+use crate::backups::backups::{
+  collect_backup_garbage, get_backup_size, remove_backup_files,
+  CollectBackupGarbageError, GetBackupSizeError,
+};
+use crate::launch_game::repository::{
+  BackupEntry, BackupRepository, BackupRepositoryError,
+};
+use crate::variants::GameVariant;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApplyBackupRetentionError {
+  #[error("failed to get backup entries: {0}")]
+  Get(#[from] BackupRepositoryError),
+
+  #[error("failed to get backup size: {0}")]
+  Size(#[from] GetBackupSizeError),
+
+  #[error("failed to collect unused backup chunks: {0}")]
+  CollectGarbage(#[from] CollectBackupGarbageError),
+}
+
+// Returns the backups that exceed the policy. `backups` are the unpinned
+// backups, newest first, and `sizes` holds their sizes in the same order.
+// The newest backup is always kept, as it was usually just taken.
+fn select_backups_to_remove<'a>(
+  backups: &'a [BackupEntry],
+  sizes: &[u64],
+  policy: &BackupRetentionPolicy,
+  now: u64,
+) -> Vec<&'a BackupEntry> {
+  let mut candidates = Vec::new();
+  let mut to_remove = Vec::new();
+
+  // Rotation only keeps the newest backup of each day and week, so the
+  // first backup seen for a day or week is the one that is kept.
+  let mut days = HashSet::new();
+  let mut weeks = HashSet::new();
+  for (index, backup) in backups.iter().enumerate() {
+    let Some(rotation) = policy.rotation else {
+      candidates.push(index);
+      continue;
+    };
+
+    let age = now.saturating_sub(backup.timestamp);
+    let is_last = index < rotation.keep_last as usize;
+    let is_daily = age
+      < u64::from(rotation.keep_daily_days) * SECONDS_PER_DAY
+      && days.insert(backup.timestamp / SECONDS_PER_DAY);
+    let is_weekly = age
+      < u64::from(rotation.keep_weekly_weeks) * SECONDS_PER_WEEK
+      && weeks.insert(backup.timestamp / SECONDS_PER_WEEK);
+
+    if index == 0 || is_last || is_daily || is_weekly {
+      candidates.push(index);
+    } else {
+      to_remove.push(backup);
+    }
+  }
+
+  let oldest_kept = policy.max_age_days.map(|days| {
+    now.saturating_sub(u64::from(days) * SECONDS_PER_DAY)
+  });
+  let mut count = 0;
+  let mut total_size = 0;
+  let mut size_exceeded = false;
+  for index in candidates {
+    let backup = &backups[index];
+
+    let exceeds_age = oldest_kept
+      .is_some_and(|oldest_kept| backup.timestamp < oldest_kept);
+    let exceeds_count = policy
+      .max_count
+      .is_some_and(|max_count| count >= max_count as usize);
+    // Once a backup does not fit, older backups are not kept either, so
+    // that the kept backups are always the newest ones.
+    size_exceeded = size_exceeded
+      || policy
+        .max_total_size_in_bytes
+        .is_some_and(|max_size| total_size + sizes[index] > max_size);
+
+    if index != 0 && (exceeds_age || exceeds_count || size_exceeded) {
+      to_remove.push(backup);
+      continue;
+    }
+
+    count += 1;
+    total_size += sizes[index];
+  }
+
+  to_remove
+}
+
+/// Removes the automatic backups of a variant that exceed its retention
+/// policy, along with the chunks only they used. Returns the number of
+/// removed backups.
+pub async fn apply_backup_retention_policy(
+  variant: &GameVariant,
+  policy: &BackupRetentionPolicy,
+  now: u64,
+  data_dir: &Path,
+  backup_repository: impl BackupRepository + Clone + 'static,
+) -> Result<usize, ApplyBackupRetentionError> {
+  let mut backups = backup_repository
+    .get_backups_sorted_by_timestamp(variant)
+    .await?;
+  backups.retain(|backup| !backup.is_pinned);
+  backups.reverse();
+
+  let mut sizes = vec![0; backups.len()];
+  if policy.max_total_size_in_bytes.is_some() {
+    for (size, backup) in sizes.iter_mut().zip(&backups) {
+      *size = get_backup_size(backup, data_dir).await?;
+    }
+  }
+
+  let backups_to_delete =
+    select_backups_to_remove(&backups, &sizes, policy, now);
+  if backups_to_delete.is_empty() {
+    return Ok(0);
+  }
+
+  let removed = backups_to_delete.len();
+  let mut set = JoinSet::new();
+
+  for backup in backups_to_delete.into_iter().cloned() {
+    let data_dir_clone = data_dir.to_owned();
+    let backup_repo_clone = backup_repository.clone();
+    set.spawn(async move {
+      if backup_repo_clone
+        .delete_backup_entry(backup.id)
+        .await
+        .is_ok()
+      {
+        // file deletion fails is ignored.
+        let _ = remove_backup_files(&backup, &data_dir_clone).await;
+      }
+    });
+  }
+
+  while set.join_next().await.is_some() {}
+
+  // Chunks that are still used by the remaining backups are kept.
+  collect_backup_garbage(data_dir, &backup_repository).await?;
+
+  Ok(removed)
+}
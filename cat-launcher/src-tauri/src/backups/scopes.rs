@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use ts_rs::TS;
+
+/// A directory of the user data directory that backups can contain.
+#[derive(
+  Debug,
+  Display,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Hash,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize,
+  TS,
+)]
+#[ts(export)]
+pub enum BackupScope {
+  Achievements,
+  Config,
+  Graveyard,
+  Memorial,
+  Save,
+  Templates,
+}
+
+impl BackupScope {
+  pub fn dir_name(&self) -> &'static str {
+    match self {
+      BackupScope::Achievements => "achievements",
+      BackupScope::Config => "config",
+      BackupScope::Graveyard => "graveyard",
+      BackupScope::Memorial => "memorial",
+      BackupScope::Save => "save",
+      BackupScope::Templates => "templates",
+    }
+  }
+}
+
+/// The scopes of backups taken before scopes could be configured.
+pub fn default_backup_scopes() -> Vec<BackupScope> {
+  vec![BackupScope::Save]
+}
+
+pub fn get_scope_dirs(
+  user_data_dir: &Path,
+  scopes: &[BackupScope],
+) -> Vec<PathBuf> {
+  scopes
+    .iter()
+    .map(|scope| user_data_dir.join(scope.dir_name()))
+    .collect()
+}
@@ -0,0 +1,480 @@
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::task::JoinError;
+use walkdir::WalkDir;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::CompressionMethod::Deflated;
+use zip::ZipWriter;
+
+use crate::constants::BACKUP_CHUNK_SIZE;
+use crate::filesystem::utils::join_relative_path;
+
+// Chunks are shared between all snapshots, so a snapshot must not be
+// written while garbage collection decides which chunks are unused.
+static STORE_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Exclusive access to the chunk store. It is held from the moment the
+/// backup entries are read or added until the snapshot is written or the
+/// unused chunks are removed, so that neither sees the other half done.
+pub struct StoreGuard {
+  _guard: MutexGuard<'static, ()>,
+}
+
+pub async fn lock_store() -> StoreGuard {
+  StoreGuard {
+    _guard: STORE_LOCK.lock().await,
+  }
+}
+
+/// Describes a snapshot as the list of chunks each file consists of.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+  pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+  /// The path relative to the snapshot root, separated by `/`.
+  pub path: String,
+  pub size: u64,
+  /// The sha256 hashes of the file's chunks, in order.
+  pub chunks: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BackupStoreError {
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to walk directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  #[error("failed to read or write backup manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  #[error("invalid path in backup manifest: {0}")]
+  InvalidPath(String),
+
+  #[error("backup chunk {0} is missing")]
+  MissingChunk(String),
+
+  #[error("backup chunk {0} is corrupted")]
+  CorruptChunk(String),
+
+  #[error("failed to write archive: {0}")]
+  Zip(#[from] ZipError),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+fn is_chunk_hash(hash: &str) -> bool {
+  hash.len() == 64
+    && hash.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+fn get_chunk_path(chunks_dir: &Path, hash: &str) -> PathBuf {
+  chunks_dir.join(&hash[..2]).join(hash)
+}
+
+fn write_chunk(
+  chunks_dir: &Path,
+  hash: &str,
+  data: &[u8],
+) -> io::Result<()> {
+  let path = get_chunk_path(chunks_dir, hash);
+  if path.exists() {
+    return Ok(());
+  }
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  // The chunk is renamed into place so that an interrupted write never
+  // leaves a truncated chunk behind under its hash.
+  let temp_path = path.with_extension("tmp");
+  let mut encoder = ZlibEncoder::new(
+    File::create(&temp_path)?,
+    Compression::default(),
+  );
+  encoder.write_all(data)?;
+  encoder.finish()?.sync_all()?;
+
+  fs::rename(&temp_path, &path)
+}
+
+fn read_chunk(
+  chunks_dir: &Path,
+  hash: &str,
+) -> Result<Vec<u8>, BackupStoreError> {
+  let file = match File::open(get_chunk_path(chunks_dir, hash)) {
+    Ok(file) => file,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Err(BackupStoreError::MissingChunk(hash.to_string()));
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  let mut data = Vec::new();
+  ZlibDecoder::new(file).read_to_end(&mut data)?;
+
+  if format!("{:x}", Sha256::digest(&data)) != hash {
+    return Err(BackupStoreError::CorruptChunk(hash.to_string()));
+  }
+
+  Ok(data)
+}
+
+fn store_file(
+  path: &Path,
+  chunks_dir: &Path,
+) -> Result<(u64, Vec<String>), BackupStoreError> {
+  let mut file = File::open(path)?;
+  let mut size = 0;
+  let mut chunks = Vec::new();
+
+  loop {
+    let mut data = Vec::with_capacity(BACKUP_CHUNK_SIZE.get());
+    (&mut file)
+      .take(BACKUP_CHUNK_SIZE.get() as u64)
+      .read_to_end(&mut data)?;
+    if data.is_empty() {
+      break;
+    }
+
+    let hash = format!("{:x}", Sha256::digest(&data));
+    write_chunk(chunks_dir, &hash, &data)?;
+
+    size += data.len() as u64;
+    chunks.push(hash);
+  }
+
+  Ok((size, chunks))
+}
+
+fn to_manifest_path(
+  relative_path: &Path,
+) -> Result<String, BackupStoreError> {
+  let components = relative_path
+    .components()
+    .map(|component| match component {
+      Component::Normal(part) => {
+        Ok(part.to_string_lossy().into_owned())
+      }
+      _ => Err(BackupStoreError::InvalidPath(
+        relative_path.display().to_string(),
+      )),
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  Ok(components.join("/"))
+}
+
+fn write_manifest(
+  manifest: &BackupManifest,
+  manifest_path: &Path,
+) -> Result<(), BackupStoreError> {
+  if let Some(parent) = manifest_path.parent() {
+    fs::create_dir_all(parent)?;
+  }
+
+  let temp_path = manifest_path.with_extension("tmp");
+  let mut file = File::create(&temp_path)?;
+  serde_json::to_writer(&mut file, manifest)?;
+  file.sync_all()?;
+
+  fs::rename(&temp_path, manifest_path)?;
+  Ok(())
+}
+
+fn read_manifest(
+  manifest_path: &Path,
+) -> Result<BackupManifest, BackupStoreError> {
+  let file = File::open(manifest_path)?;
+  Ok(serde_json::from_reader(io::BufReader::new(file))?)
+}
+
+/// Stores the files under `paths` and writes a manifest describing them.
+/// Only chunks that are not in the store yet are written.
+pub async fn store_snapshot(
+  source_root: &Path,
+  paths: &[PathBuf],
+  chunks_dir: &Path,
+  manifest_path: &Path,
+  _guard: &StoreGuard,
+) -> Result<BackupManifest, BackupStoreError> {
+  let source_root = source_root.to_owned();
+  let paths = paths.to_vec();
+  let chunks_dir = chunks_dir.to_owned();
+  let manifest_path = manifest_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut files = Vec::new();
+
+    for path in &paths {
+      if !path.exists() {
+        continue;
+      }
+
+      for entry in WalkDir::new(path).sort_by_file_name() {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+          continue;
+        }
+
+        let relative_path =
+          entry.path().strip_prefix(&source_root).map_err(|_| {
+            BackupStoreError::InvalidPath(
+              entry.path().display().to_string(),
+            )
+          })?;
+
+        let (size, chunks) = store_file(entry.path(), &chunks_dir)?;
+        files.push(ManifestFile {
+          path: to_manifest_path(relative_path)?,
+          size,
+          chunks,
+        });
+      }
+    }
+
+    let manifest = BackupManifest { files };
+    write_manifest(&manifest, &manifest_path)?;
+
+    Ok(manifest)
+  })
+  .await?
+}
+
+/// Writes the files of a snapshot below `target_root`. Nothing is written
+/// unless every chunk the snapshot needs is present.
+pub async fn restore_snapshot(
+  manifest_path: &Path,
+  chunks_dir: &Path,
+  target_root: &Path,
+) -> Result<(), BackupStoreError> {
+  restore_snapshot_files(
+    manifest_path,
+    chunks_dir,
+    target_root,
+    |_| true,
+  )
+  .await?;
+  Ok(())
+}
+
+/// Like `restore_snapshot`, but only writes the files whose manifest path
+/// matches `filter`. Returns the number of files written.
+pub async fn restore_snapshot_files<F>(
+  manifest_path: &Path,
+  chunks_dir: &Path,
+  target_root: &Path,
+  filter: F,
+) -> Result<usize, BackupStoreError>
+where
+  F: Fn(&str) -> bool + Send + 'static,
+{
+  let manifest_path = manifest_path.to_owned();
+  let chunks_dir = chunks_dir.to_owned();
+  let target_root = target_root.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut manifest = read_manifest(&manifest_path)?;
+    manifest.files.retain(|file| filter(&file.path));
+
+    let mut targets = Vec::with_capacity(manifest.files.len());
+    for file in &manifest.files {
+      // Manifest paths come from files on disk, so they are checked to
+      // stay inside the directory they are restored into.
+      let target = join_relative_path(&target_root, &file.path)
+        .ok_or_else(|| {
+          BackupStoreError::InvalidPath(file.path.clone())
+        })?;
+      targets.push(target);
+
+      for hash in &file.chunks {
+        if !is_chunk_hash(hash) {
+          return Err(BackupStoreError::CorruptChunk(hash.clone()));
+        }
+        if !get_chunk_path(&chunks_dir, hash).is_file() {
+          return Err(BackupStoreError::MissingChunk(hash.clone()));
+        }
+      }
+    }
+
+    for (file, target) in manifest.files.iter().zip(targets) {
+      if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+      }
+
+      let mut output = File::create(&target)?;
+      for hash in &file.chunks {
+        output.write_all(&read_chunk(&chunks_dir, hash)?)?;
+      }
+    }
+
+    Ok(manifest.files.len())
+  })
+  .await?
+}
+
+/// Returns the paths of the files in a snapshot.
+pub async fn list_snapshot_files(
+  manifest_path: &Path,
+) -> Result<Vec<String>, BackupStoreError> {
+  let manifest_path = manifest_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let manifest = read_manifest(&manifest_path)?;
+    Ok(manifest.files.into_iter().map(|file| file.path).collect())
+  })
+  .await?
+}
+
+/// Returns the total size of the files in a snapshot.
+pub async fn get_snapshot_size(
+  manifest_path: &Path,
+) -> Result<u64, BackupStoreError> {
+  let manifest_path = manifest_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let manifest = read_manifest(&manifest_path)?;
+    Ok(manifest.files.iter().map(|file| file.size).sum())
+  })
+  .await?
+}
+
+/// Returns the manifest describing a snapshot.
+pub async fn read_snapshot_manifest(
+  manifest_path: &Path,
+) -> Result<BackupManifest, BackupStoreError> {
+  let manifest_path = manifest_path.to_owned();
+
+  tokio::task::spawn_blocking(move || read_manifest(&manifest_path))
+    .await?
+}
+
+/// Reads every chunk of a snapshot and checks it against its hash, without
+/// writing any files.
+pub async fn verify_snapshot(
+  manifest_path: &Path,
+  chunks_dir: &Path,
+) -> Result<(), BackupStoreError> {
+  let manifest_path = manifest_path.to_owned();
+  let chunks_dir = chunks_dir.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let manifest = read_manifest(&manifest_path)?;
+
+    // Files often share chunks, so each chunk is only read once.
+    let mut verified = HashSet::new();
+    for file in &manifest.files {
+      for hash in &file.chunks {
+        if !is_chunk_hash(hash) {
+          return Err(BackupStoreError::CorruptChunk(hash.clone()));
+        }
+        if verified.insert(hash.as_str()) {
+          read_chunk(&chunks_dir, hash)?;
+        }
+      }
+    }
+
+    Ok(())
+  })
+  .await?
+}
+
+/// Writes the files of a snapshot to a zip archive, so that it can be
+/// copied somewhere without the chunk store.
+pub async fn write_snapshot_archive(
+  manifest_path: &Path,
+  chunks_dir: &Path,
+  archive_path: &Path,
+) -> Result<(), BackupStoreError> {
+  let manifest_path = manifest_path.to_owned();
+  let chunks_dir = chunks_dir.to_owned();
+  let archive_path = archive_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let manifest = read_manifest(&manifest_path)?;
+    let mut zip = ZipWriter::new(File::create(&archive_path)?);
+    let options: FileOptions<'_, ()> =
+      FileOptions::default().compression_method(Deflated);
+
+    for file in &manifest.files {
+      zip.start_file(file.path.as_str(), options)?;
+      for hash in &file.chunks {
+        if !is_chunk_hash(hash) {
+          return Err(BackupStoreError::CorruptChunk(hash.clone()));
+        }
+        zip.write_all(&read_chunk(&chunks_dir, hash)?)?;
+      }
+    }
+
+    zip.finish()?.sync_all()?;
+    Ok(())
+  })
+  .await?
+}
+
+/// Removes the chunks that none of the given manifests refer to.
+/// Manifests that do not exist are ignored.
+pub async fn collect_garbage(
+  manifest_paths: &[PathBuf],
+  chunks_dir: &Path,
+  _guard: &StoreGuard,
+) -> Result<usize, BackupStoreError> {
+  let manifest_paths = manifest_paths.to_vec();
+  let chunks_dir = chunks_dir.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    if !chunks_dir.exists() {
+      return Ok(0);
+    }
+
+    let mut referenced = HashSet::new();
+    for manifest_path in &manifest_paths {
+      let manifest = match read_manifest(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(BackupStoreError::Io(e))
+          if e.kind() == io::ErrorKind::NotFound =>
+        {
+          continue;
+        }
+        Err(e) => return Err(e),
+      };
+
+      for file in manifest.files {
+        referenced.extend(file.chunks);
+      }
+    }
+
+    let mut removed = 0;
+    for entry in WalkDir::new(&chunks_dir) {
+      let entry = entry?;
+      if !entry.file_type().is_file() {
+        continue;
+      }
+
+      let name = entry.file_name().to_string_lossy();
+      if !referenced.contains(name.as_ref()) {
+        fs::remove_file(entry.path())?;
+        removed += 1;
+      }
+    }
+
+    Ok(removed)
+  })
+  .await?
+}
@@ -0,0 +1,221 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+use ts_rs::TS;
+
+use crate::active_release::repository::{
+  ActiveReleaseRepository, ActiveReleaseRepositoryError,
+};
+use crate::backup_settings::repository::{
+  BackupKind, BackupRetentionPolicy, BackupSettingsRepository,
+  BackupSettingsRepositoryError,
+};
+use crate::backups::retention::{
+  apply_backup_retention_policy, ApplyBackupRetentionError,
+};
+use crate::backups::scopes::BackupScope;
+use crate::data_profiles::repository::{
+  DataProfilesRepository, DataProfilesRepositoryError,
+};
+use crate::launch_game::repository::BackupRepository;
+use crate::launch_game::utils::{take_automatic_backup, BackupError};
+use crate::variants::GameVariant;
+
+/// What caused an automatic backup to be taken.
+#[derive(
+  Debug,
+  Display,
+  EnumString,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Serialize,
+  Deserialize,
+  TS,
+)]
+#[ts(export)]
+pub enum BackupTrigger {
+  BeforeLaunch,
+  OnExit,
+  Periodic,
+  BeforeUpdate,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TriggeredBackupError {
+  #[error("failed to take backup: {0}")]
+  Backup(#[from] BackupError),
+
+  #[error("failed to apply backup retention policy: {0}")]
+  Retention(#[from] ApplyBackupRetentionError),
+
+  #[error("failed to get backup settings: {0}")]
+  BackupSettings(#[from] BackupSettingsRepositoryError),
+
+  #[error("failed to get active release: {0}")]
+  ActiveRelease(#[from] ActiveReleaseRepositoryError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfilesRepositoryError),
+
+  #[error("failed to get current time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+/// Periodic backups of a running game, taken until they are stopped.
+pub struct PeriodicBackups {
+  stop: oneshot::Sender<()>,
+  task: JoinHandle<()>,
+}
+
+impl PeriodicBackups {
+  /// Stops taking backups. A backup that is being taken is finished
+  /// first, so that it is not left without its files.
+  pub async fn stop(self) {
+    let _ = self.stop.send(());
+    let _ = self.task.await;
+  }
+}
+
+/// Takes the automatic backups of a game that is running.
+#[derive(Clone)]
+pub struct TriggeredBackups<R> {
+  pub variant: GameVariant,
+  pub data_profile: Option<String>,
+  pub release_version: String,
+  pub scopes: Vec<BackupScope>,
+  pub retention_policy: BackupRetentionPolicy,
+  pub data_dir: PathBuf,
+  pub backup_repository: R,
+}
+
+impl<R: BackupRepository + Clone + 'static> TriggeredBackups<R> {
+  /// Takes a backup and removes the backups that no longer fit the
+  /// retention policy.
+  pub async fn take(
+    &self,
+    trigger: &BackupTrigger,
+  ) -> Result<(), TriggeredBackupError> {
+    let timestamp =
+      SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    take_automatic_backup(
+      &self.variant,
+      self.data_profile.as_deref(),
+      &self.release_version,
+      timestamp,
+      &self.scopes,
+      trigger,
+      &self.data_dir,
+      &self.backup_repository,
+    )
+    .await?;
+
+    apply_backup_retention_policy(
+      &self.variant,
+      &self.retention_policy,
+      timestamp,
+      &self.data_dir,
+      self.backup_repository.clone(),
+    )
+    .await?;
+
+    Ok(())
+  }
+
+  /// Takes a backup every `interval_minutes` until the returned backups
+  /// are stopped. Failed backups are passed to `on_error` and do not stop
+  /// later ones.
+  pub fn spawn_periodic<F, Fut>(
+    self,
+    interval_minutes: u32,
+    on_error: F,
+  ) -> PeriodicBackups
+  where
+    F: Fn(TriggeredBackupError) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+  {
+    let period =
+      Duration::from_secs(u64::from(interval_minutes) * 60);
+    let (stop, mut stopped) = oneshot::channel();
+
+    let task = tokio::spawn(async move {
+      // The game was backed up right before it started, so the first
+      // periodic backup is only taken after a full interval.
+      let mut interval = interval_at(Instant::now() + period, period);
+      interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+      loop {
+        tokio::select! {
+          _ = interval.tick() => {}
+          _ = &mut stopped => return,
+        }
+
+        if let Err(e) = self.take(&BackupTrigger::Periodic).await {
+          on_error(e).await;
+        }
+      }
+    });
+
+    PeriodicBackups { stop, task }
+  }
+}
+
+/// Backs up the saves before installing a release switches the active
+/// release of a variant. The backup is labelled with the release that was
+/// active until now, as that is the release the saves were played with.
+pub async fn take_backup_before_update(
+  variant: &GameVariant,
+  new_version: &str,
+  data_dir: &Path,
+  backup_repository: impl BackupRepository + Clone + 'static,
+  backup_settings_repository: &dyn BackupSettingsRepository,
+  active_release_repository: &dyn ActiveReleaseRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+) -> Result<(), TriggeredBackupError> {
+  let trigger_settings = backup_settings_repository
+    .get_trigger_settings(variant)
+    .await?;
+  if !trigger_settings.before_update {
+    return Ok(());
+  }
+
+  let Some(active_version) = active_release_repository
+    .get_active_release(variant)
+    .await?
+  else {
+    return Ok(());
+  };
+  if active_version == new_version {
+    return Ok(());
+  }
+
+  let data_profile = data_profiles_repository
+    .get_active_data_profile(variant)
+    .await?;
+  let scopes = backup_settings_repository
+    .get_backup_scopes(variant, &BackupKind::Automatic)
+    .await?;
+  let retention_policy = backup_settings_repository
+    .get_retention_policy(variant)
+    .await?;
+
+  TriggeredBackups {
+    variant: *variant,
+    data_profile,
+    release_version: active_version,
+    scopes,
+    retention_policy,
+    data_dir: data_dir.to_path_buf(),
+    backup_repository,
+  }
+  .take(&BackupTrigger::BeforeUpdate)
+  .await
+}
@@ -1,4 +1,27 @@
 use std::num::{NonZeroU16, NonZeroUsize};
+use std::time::Duration;
 
 pub const MAX_BACKUPS: NonZeroUsize = NonZeroUsize::new(5).unwrap();
-pub const PARALLEL_REQUESTS: NonZeroU16 = NonZeroU16::new(4).unwrap();
+pub const DOWNLOAD_MAX_ATTEMPTS: NonZeroU16 =
+  NonZeroU16::new(5).unwrap();
+pub const DOWNLOAD_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+pub const DOWNLOAD_MAX_BACKOFF: Duration = Duration::from_secs(30);
+pub const CRASH_REPORT_LOG_LINES: NonZeroUsize =
+  NonZeroUsize::new(500).unwrap();
+pub const MAX_CRASH_REPORTS: NonZeroUsize =
+  NonZeroUsize::new(20).unwrap();
+pub const GRACEFUL_EXIT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const BACKUP_CHUNK_SIZE: NonZeroUsize =
+  NonZeroUsize::new(1024 * 1024).unwrap();
+pub const BACKUP_UPLOAD_MAX_ATTEMPTS: NonZeroU16 =
+  NonZeroU16::new(3).unwrap();
+pub const BACKUP_UPLOAD_INITIAL_BACKOFF: Duration =
+  Duration::from_secs(2);
+pub const BACKUP_UPLOAD_MAX_BACKOFF: Duration =
+  Duration::from_secs(30);
+pub const BACKUP_MIRROR_INTERVAL: Duration =
+  Duration::from_secs(15 * 60);
+pub const BACKUP_UPLOAD_MAX_FAILED_MIRRORS: NonZeroU16 =
+  NonZeroU16::new(8).unwrap();
+pub const BACKUP_UPLOAD_MAX_RETRY_DELAY: Duration =
+  Duration::from_secs(24 * 60 * 60);
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::crash_reports::crash_reports::{
+  delete_crash_report as delete_crash_report_impl,
+  export_crash_report as export_crash_report_impl,
+  list_crash_reports as list_crash_reports_impl, CrashReportError,
+};
+use crate::crash_reports::repository::sqlite_crash_reports_repository::SqliteCrashReportsRepository;
+use crate::crash_reports::repository::CrashReport;
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum CrashReportCommandError {
+  #[error("crash report operation failed: {0}")]
+  CrashReport(#[from] CrashReportError),
+
+  #[error("failed to get system directory: {0}")]
+  SystemDir(#[from] tauri::Error),
+}
+
+#[command]
+pub async fn list_crash_reports(
+  variant: GameVariant,
+  repository: State<'_, SqliteCrashReportsRepository>,
+) -> Result<Vec<CrashReport>, CrashReportCommandError> {
+  let reports =
+    list_crash_reports_impl(&variant, &*repository).await?;
+  Ok(reports)
+}
+
+#[command]
+pub async fn export_crash_report(
+  app_handle: AppHandle,
+  id: i64,
+  destination: PathBuf,
+  repository: State<'_, SqliteCrashReportsRepository>,
+) -> Result<(), CrashReportCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  export_crash_report_impl(id, &destination, &data_dir, &*repository)
+    .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn delete_crash_report(
+  app_handle: AppHandle,
+  id: i64,
+  repository: State<'_, SqliteCrashReportsRepository>,
+) -> Result<(), CrashReportCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  delete_crash_report_impl(id, &data_dir, &*repository).await?;
+
+  Ok(())
+}
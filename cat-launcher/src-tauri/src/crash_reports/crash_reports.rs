@@ -0,0 +1,229 @@
+use std::io;
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::constants::MAX_CRASH_REPORTS;
+use crate::crash_reports::repository::{
+  CrashReport, CrashReportsRepository, CrashReportsRepositoryError,
+};
+use crate::filesystem::paths::{
+  get_crash_report_dir, get_crash_reports_dir, get_user_game_data_dir,
+};
+use crate::infra::archive::{
+  create_zip_archive, ArchiveCreationError,
+};
+use crate::launch_game::launch_game::GameExitStatus;
+use crate::variants::GameVariant;
+
+// Files the game writes into its config directory that are useful for
+// diagnosing a crash.
+const GAME_CRASH_ARTIFACTS: [&str; 2] = ["crash.log", "debug.log"];
+
+const LAUNCHER_LOG_FILENAME: &str = "launcher.log";
+const REPORT_FILENAME: &str = "report.json";
+
+#[derive(thiserror::Error, Debug)]
+pub enum CollectCrashReportError {
+  #[error("failed to access crash reports: {0}")]
+  Repository(#[from] CrashReportsRepositoryError),
+
+  #[error("failed to write crash report files: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to serialize crash report: {0}")]
+  Serialize(#[from] serde_json::Error),
+}
+
+async fn write_crash_report_files(
+  report: &CrashReport,
+  user_data_dir: &Path,
+  log_lines: &[String],
+  report_dir: &Path,
+) -> Result<(), CollectCrashReportError> {
+  fs::create_dir_all(report_dir).await?;
+
+  let config_dir = user_data_dir.join("config");
+  for filename in GAME_CRASH_ARTIFACTS {
+    match fs::copy(
+      config_dir.join(filename),
+      report_dir.join(filename),
+    )
+    .await
+    {
+      Ok(_) => {}
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+      Err(e) => return Err(e.into()),
+    }
+  }
+
+  fs::write(
+    report_dir.join(LAUNCHER_LOG_FILENAME),
+    log_lines.join("\n"),
+  )
+  .await?;
+  fs::write(
+    report_dir.join(REPORT_FILENAME),
+    serde_json::to_vec_pretty(report)?,
+  )
+  .await?;
+
+  Ok(())
+}
+
+/// Records a crash report and copies the files useful for diagnosing the
+/// crash into its directory. Only the newest `MAX_CRASH_REPORTS` reports
+/// of the variant are kept.
+#[allow(clippy::too_many_arguments)]
+pub async fn collect_crash_report(
+  variant: &GameVariant,
+  release_version: &str,
+  data_profile: Option<&str>,
+  exit_status: &GameExitStatus,
+  log_lines: &[String],
+  timestamp: u64,
+  data_dir: &Path,
+  repository: &dyn CrashReportsRepository,
+) -> Result<CrashReport, CollectCrashReportError> {
+  let id = repository
+    .add_crash_report(
+      variant,
+      release_version,
+      timestamp,
+      exit_status,
+    )
+    .await?;
+
+  let report = CrashReport {
+    id,
+    game_variant: *variant,
+    release_version: release_version.to_string(),
+    timestamp,
+    exit_status: exit_status.clone(),
+  };
+
+  let user_data_dir =
+    get_user_game_data_dir(variant, data_profile, data_dir);
+  let report_dir =
+    get_crash_report_dir(variant, id, timestamp, data_dir);
+
+  if let Err(e) = write_crash_report_files(
+    &report,
+    &user_data_dir,
+    log_lines,
+    &report_dir,
+  )
+  .await
+  {
+    let _ = repository.delete_crash_report(id).await;
+    let _ = fs::remove_dir_all(&report_dir).await;
+    return Err(e);
+  }
+
+  // The report was collected, so failing to remove old ones does not fail
+  // it.
+  if let Err(e) =
+    remove_old_crash_reports(variant, data_dir, repository).await
+  {
+    eprintln!("Failed to remove old crash reports: {}", e);
+  }
+
+  Ok(report)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CrashReportError {
+  #[error("failed to access crash reports: {0}")]
+  Repository(#[from] CrashReportsRepositoryError),
+
+  #[error("files for crash report {0} are missing")]
+  MissingFiles(i64),
+
+  #[error("failed to create crash report archive: {0}")]
+  Archive(#[from] ArchiveCreationError),
+
+  #[error("failed to remove crash report files: {0}")]
+  Remove(#[from] io::Error),
+}
+
+async fn remove_crash_report(
+  report: &CrashReport,
+  data_dir: &Path,
+  repository: &dyn CrashReportsRepository,
+) -> Result<(), CrashReportError> {
+  repository.delete_crash_report(report.id).await?;
+
+  let report_dir = get_crash_report_dir(
+    &report.game_variant,
+    report.id,
+    report.timestamp,
+    data_dir,
+  );
+  match fs::remove_dir_all(&report_dir).await {
+    Ok(()) => {}
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  Ok(())
+}
+
+// Removes the reports of the variant beyond the newest MAX_CRASH_REPORTS.
+async fn remove_old_crash_reports(
+  variant: &GameVariant,
+  data_dir: &Path,
+  repository: &dyn CrashReportsRepository,
+) -> Result<(), CrashReportError> {
+  let reports = repository.get_crash_reports(variant).await?;
+  for report in reports.iter().skip(MAX_CRASH_REPORTS.get()) {
+    remove_crash_report(report, data_dir, repository).await?;
+  }
+
+  Ok(())
+}
+
+pub async fn list_crash_reports(
+  variant: &GameVariant,
+  repository: &dyn CrashReportsRepository,
+) -> Result<Vec<CrashReport>, CrashReportError> {
+  Ok(repository.get_crash_reports(variant).await?)
+}
+
+pub async fn export_crash_report(
+  id: i64,
+  destination: &Path,
+  data_dir: &Path,
+  repository: &dyn CrashReportsRepository,
+) -> Result<(), CrashReportError> {
+  let report = repository.get_crash_report(id).await?;
+  let report_dir = get_crash_report_dir(
+    &report.game_variant,
+    report.id,
+    report.timestamp,
+    data_dir,
+  );
+
+  if !fs::metadata(&report_dir).await.is_ok_and(|m| m.is_dir()) {
+    return Err(CrashReportError::MissingFiles(id));
+  }
+
+  // The bundle directory itself is archived so that extracting the zip
+  // yields a single, self-describing folder.
+  create_zip_archive(
+    &get_crash_reports_dir(data_dir),
+    &[report_dir],
+    destination,
+  )
+  .await?;
+
+  Ok(())
+}
+
+pub async fn delete_crash_report(
+  id: i64,
+  data_dir: &Path,
+  repository: &dyn CrashReportsRepository,
+) -> Result<(), CrashReportError> {
+  let report = repository.get_crash_report(id).await?;
+  remove_crash_report(&report, data_dir, repository).await
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod crash_reports;
+
+pub mod commands;
+pub mod repository;
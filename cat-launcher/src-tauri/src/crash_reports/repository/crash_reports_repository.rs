@@ -0,0 +1,59 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::launch_game::launch_game::GameExitStatus;
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct CrashReport {
+  pub id: i64,
+  pub game_variant: GameVariant,
+  pub release_version: String,
+  pub timestamp: u64,
+  pub exit_status: GameExitStatus,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CrashReportsRepositoryError {
+  #[error("failed to add crash report: {0}")]
+  Add(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get crash reports: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete crash report: {0}")]
+  Delete(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("crash report with id {0} not found")]
+  NotFound(i64),
+}
+
+#[async_trait]
+pub trait CrashReportsRepository: Send + Sync {
+  async fn add_crash_report(
+    &self,
+    game_variant: &GameVariant,
+    release_version: &str,
+    timestamp: u64,
+    exit_status: &GameExitStatus,
+  ) -> Result<i64, CrashReportsRepositoryError>;
+
+  async fn get_crash_reports(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<CrashReport>, CrashReportsRepositoryError>;
+
+  async fn get_crash_report(
+    &self,
+    id: i64,
+  ) -> Result<CrashReport, CrashReportsRepositoryError>;
+
+  async fn delete_crash_report(
+    &self,
+    id: i64,
+  ) -> Result<(), CrashReportsRepositoryError>;
+}
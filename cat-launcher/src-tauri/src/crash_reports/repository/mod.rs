@@ -0,0 +1,6 @@
+pub mod crash_reports_repository;
+pub mod sqlite_crash_reports_repository;
+
+pub use crash_reports_repository::{
+  CrashReport, CrashReportsRepository, CrashReportsRepositoryError,
+};
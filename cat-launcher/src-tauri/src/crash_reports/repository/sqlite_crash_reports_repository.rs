@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::Row;
+
+use crate::crash_reports::repository::{
+  CrashReport, CrashReportsRepository, CrashReportsRepositoryError,
+};
+use crate::infra::repository::db_helper::run_db;
+use crate::launch_game::launch_game::GameExitStatus;
+use crate::variants::GameVariant;
+
+const SELECT_CRASH_REPORT: &str = "SELECT id, game_variant, release_version, timestamp, exit_kind, exit_code, exit_signal FROM crash_reports";
+
+#[derive(Clone)]
+pub struct SqliteCrashReportsRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteCrashReportsRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("unknown exit kind: {0}")]
+struct UnknownExitKindError(String);
+
+fn exit_status_to_columns(
+  exit_status: &GameExitStatus,
+) -> (&'static str, Option<i32>, Option<i32>) {
+  match exit_status {
+    GameExitStatus::Clean => ("Clean", None, None),
+    GameExitStatus::NonZero { code } => {
+      ("NonZero", Some(*code), None)
+    }
+    GameExitStatus::Signal { signal } => {
+      ("Signal", None, Some(*signal))
+    }
+  }
+}
+
+fn row_to_crash_report(row: &Row) -> rusqlite::Result<CrashReport> {
+  let game_variant_str: String = row.get(1)?;
+  let game_variant = GameVariant::from_str(&game_variant_str)
+    .map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        1,
+        Type::Text,
+        Box::new(e),
+      )
+    })?;
+
+  let exit_kind: String = row.get(4)?;
+  let exit_code: Option<i32> = row.get(5)?;
+  let exit_signal: Option<i32> = row.get(6)?;
+  let exit_status = match (exit_kind.as_str(), exit_code, exit_signal)
+  {
+    ("Clean", _, _) => GameExitStatus::Clean,
+    ("NonZero", Some(code), _) => GameExitStatus::NonZero { code },
+    ("Signal", _, Some(signal)) => GameExitStatus::Signal { signal },
+    _ => {
+      return Err(rusqlite::Error::FromSqlConversionFailure(
+        4,
+        Type::Text,
+        Box::new(UnknownExitKindError(exit_kind)),
+      ))
+    }
+  };
+
+  Ok(CrashReport {
+    id: row.get(0)?,
+    game_variant,
+    release_version: row.get(2)?,
+    timestamp: row.get(3)?,
+    exit_status,
+  })
+}
+
+#[async_trait]
+impl CrashReportsRepository for SqliteCrashReportsRepository {
+  async fn add_crash_report(
+    &self,
+    game_variant: &GameVariant,
+    release_version: &str,
+    timestamp: u64,
+    exit_status: &GameExitStatus,
+  ) -> Result<i64, CrashReportsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let release_version = release_version.to_string();
+    let (exit_kind, exit_code, exit_signal) =
+      exit_status_to_columns(exit_status);
+
+    run_db(pool, move |conn| {
+      conn.query_row(
+        "INSERT INTO crash_reports (game_variant, release_version, timestamp, exit_kind, exit_code, exit_signal)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+        rusqlite::params![
+          variant_name,
+          release_version,
+          timestamp,
+          exit_kind,
+          exit_code,
+          exit_signal,
+        ],
+        |row| row.get::<_, i64>(0),
+      )
+    })
+    .await
+    .map_err(CrashReportsRepositoryError::Add)
+  }
+
+  async fn get_crash_reports(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<CrashReport>, CrashReportsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(&format!(
+        "{} WHERE game_variant = ?1 ORDER BY timestamp DESC",
+        SELECT_CRASH_REPORT
+      ))?;
+      let reports = stmt
+        .query_map([&variant_name], row_to_crash_report)?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<CrashReport>, rusqlite::Error>(reports)
+    })
+    .await
+    .map_err(CrashReportsRepositoryError::Get)
+  }
+
+  async fn get_crash_report(
+    &self,
+    id: i64,
+  ) -> Result<CrashReport, CrashReportsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let report = run_db(pool, move |conn| {
+      let result = conn.query_row(
+        &format!("{} WHERE id = ?1", SELECT_CRASH_REPORT),
+        [id],
+        row_to_crash_report,
+      );
+
+      match result {
+        Ok(report) => Ok(Some(report)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+      }
+    })
+    .await
+    .map_err(CrashReportsRepositoryError::Get)?;
+
+    report.ok_or(CrashReportsRepositoryError::NotFound(id))
+  }
+
+  async fn delete_crash_report(
+    &self,
+    id: i64,
+  ) -> Result<(), CrashReportsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute("DELETE FROM crash_reports WHERE id = ?1", [id])
+    })
+    .await
+    .map_err(CrashReportsRepositoryError::Delete)?;
+
+    if rows_affected == 0 {
+      return Err(CrashReportsRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+}
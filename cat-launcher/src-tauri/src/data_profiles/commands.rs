@@ -0,0 +1,112 @@
+use std::env::consts::OS;
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::data_profiles::data_profiles::{
+  create_data_profile as create_data_profile_impl,
+  delete_data_profile as delete_data_profile_impl,
+  get_active_data_profile as get_active_data_profile_impl,
+  list_data_profiles as list_data_profiles_impl,
+  set_active_data_profile as set_active_data_profile_impl,
+  DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+use crate::data_profiles::repository::DataProfile;
+use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum DataProfileCommandError {
+  #[error("data profile operation failed: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to get system directory: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  #[error("failed to get OS enum: {0}")]
+  Os(#[from] OSNotSupportedError),
+
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+#[command]
+pub async fn list_data_profiles(
+  variant: GameVariant,
+  repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Vec<DataProfile>, DataProfileCommandError> {
+  let profiles =
+    list_data_profiles_impl(&variant, &*repository).await?;
+  Ok(profiles)
+}
+
+#[command]
+pub async fn create_data_profile(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  name: String,
+  repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<DataProfile, DataProfileCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(OS)?;
+  let created_at =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+  let profile = create_data_profile_impl(
+    &variant,
+    &name,
+    created_at,
+    &data_dir,
+    &os,
+    &*repository,
+  )
+  .await?;
+
+  Ok(profile)
+}
+
+#[command]
+pub async fn delete_data_profile(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  name: String,
+  repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<(), DataProfileCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  delete_data_profile_impl(&variant, &name, &data_dir, &*repository)
+    .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn get_active_data_profile(
+  variant: GameVariant,
+  repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Option<String>, DataProfileCommandError> {
+  let profile =
+    get_active_data_profile_impl(&variant, &*repository).await?;
+  Ok(profile)
+}
+
+#[command]
+pub async fn set_active_data_profile(
+  variant: GameVariant,
+  name: Option<String>,
+  repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Option<String>, DataProfileCommandError> {
+  let profile = set_active_data_profile_impl(
+    &variant,
+    name.as_deref(),
+    &*repository,
+  )
+  .await?;
+  Ok(profile)
+}
@@ -0,0 +1,176 @@
+use std::io;
+use std::path::Path;
+
+use crate::data_profiles::repository::{
+  DataProfile, DataProfilesRepository, DataProfilesRepositoryError,
+};
+use crate::filesystem::paths::{
+  get_or_create_user_game_data_dir, get_user_game_data_dir,
+  GetUserGameDataDirError,
+};
+use crate::filesystem::utils::{copy_dir_all, CopyDirError};
+use crate::infra::utils::OS;
+use crate::variants::GameVariant;
+
+const MAX_DATA_PROFILE_NAME_LENGTH: usize = 64;
+
+#[derive(thiserror::Error, Debug)]
+pub enum DataProfileError {
+  #[error(
+    "invalid data profile name {0:?}: use letters, digits, spaces, '-' or '_'"
+  )]
+  InvalidName(String),
+
+  #[error("failed to access data profiles: {0}")]
+  Repository(#[from] DataProfilesRepositoryError),
+
+  #[error("failed to get user data directory: {0}")]
+  UserGameDataDir(#[from] GetUserGameDataDirError),
+
+  #[error("failed to copy config directory: {0}")]
+  CopyConfig(#[from] CopyDirError),
+
+  #[error("failed to delete user data directory: {0}")]
+  DeleteUserGameDataDir(#[from] io::Error),
+}
+
+// The name is used as a directory name, so it is restricted to characters
+// that are valid on every supported platform.
+fn validate_data_profile_name(
+  name: &str,
+) -> Result<(), DataProfileError> {
+  let is_valid = !name.is_empty()
+    && name.len() <= MAX_DATA_PROFILE_NAME_LENGTH
+    && name.trim() == name
+    && name.chars().all(|c| {
+      c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_')
+    });
+
+  if !is_valid {
+    return Err(DataProfileError::InvalidName(name.to_string()));
+  }
+
+  Ok(())
+}
+
+pub async fn list_data_profiles(
+  variant: &GameVariant,
+  repository: &dyn DataProfilesRepository,
+) -> Result<Vec<DataProfile>, DataProfileError> {
+  Ok(repository.get_data_profiles(variant).await?)
+}
+
+pub async fn create_data_profile(
+  variant: &GameVariant,
+  name: &str,
+  created_at: u64,
+  data_dir: &Path,
+  os: &OS,
+  repository: &dyn DataProfilesRepository,
+) -> Result<DataProfile, DataProfileError> {
+  validate_data_profile_name(name)?;
+
+  repository
+    .add_data_profile(variant, name, created_at)
+    .await?;
+
+  let profile_dir =
+    get_or_create_user_game_data_dir(variant, Some(name), data_dir)
+      .await?;
+
+  // New profiles start with the options, keybindings and launcher
+  // settings of the default profile, but without any of its saves.
+  let default_config_dir =
+    get_user_game_data_dir(variant, None, data_dir).join("config");
+  if default_config_dir.is_dir() {
+    copy_dir_all(
+      &default_config_dir,
+      &profile_dir.join("config"),
+      os,
+    )
+    .await?;
+  }
+
+  Ok(repository.get_data_profile(variant, name).await?)
+}
+
+pub async fn delete_data_profile(
+  variant: &GameVariant,
+  name: &str,
+  data_dir: &Path,
+  repository: &dyn DataProfilesRepository,
+) -> Result<(), DataProfileError> {
+  let profile = repository.get_data_profile(variant, name).await?;
+
+  // Deleting the profile also deselects it, which makes the default
+  // profile active again.
+  repository
+    .delete_data_profile(variant, &profile.name)
+    .await?;
+
+  let profile_dir =
+    get_user_game_data_dir(variant, Some(&profile.name), data_dir);
+  match tokio::fs::remove_dir_all(&profile_dir).await {
+    Ok(()) => {}
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  Ok(())
+}
+
+pub async fn get_active_data_profile(
+  variant: &GameVariant,
+  repository: &dyn DataProfilesRepository,
+) -> Result<Option<String>, DataProfileError> {
+  Ok(repository.get_active_data_profile(variant).await?)
+}
+
+pub async fn set_active_data_profile(
+  variant: &GameVariant,
+  name: Option<&str>,
+  repository: &dyn DataProfilesRepository,
+) -> Result<Option<String>, DataProfileError> {
+  let name = match name {
+    Some(name) => {
+      Some(repository.get_data_profile(variant, name).await?.name)
+    }
+    None => None,
+  };
+
+  repository
+    .set_active_data_profile(variant, name.as_deref())
+    .await?;
+
+  Ok(name)
+}
+
+pub async fn get_all_data_profiles(
+  variant: &GameVariant,
+  repository: &dyn DataProfilesRepository,
+) -> Result<Vec<Option<String>>, DataProfileError> {
+  let named_profiles = repository
+    .get_data_profiles(variant)
+    .await?
+    .into_iter()
+    .map(|profile| Some(profile.name));
+
+  Ok(std::iter::once(None).chain(named_profiles).collect())
+}
+
+/// Whether `data_profile` still exists. The default profile always does.
+pub async fn data_profile_exists(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  repository: &dyn DataProfilesRepository,
+) -> Result<bool, DataProfileError> {
+  let Some(name) = data_profile else {
+    return Ok(true);
+  };
+
+  match repository.get_data_profile(variant, name).await {
+    Ok(_) => Ok(true),
+    Err(DataProfilesRepositoryError::NotFound(..)) => Ok(false),
+    Err(e) => Err(e.into()),
+  }
+}
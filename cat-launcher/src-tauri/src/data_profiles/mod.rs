@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod data_profiles;
+
+pub mod commands;
+pub mod repository;
@@ -0,0 +1,78 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DataProfile {
+  pub game_variant: GameVariant,
+  pub name: String,
+  pub created_at: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DataProfilesRepositoryError {
+  #[error("failed to add data profile: {0}")]
+  Add(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get data profiles: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete data profile: {0}")]
+  Delete(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get active data profile: {0}")]
+  GetActive(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set active data profile: {0}")]
+  SetActive(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("data profile {0} already exists for variant {1}")]
+  AlreadyExists(String, String),
+
+  #[error("data profile {0} not found for variant {1}")]
+  NotFound(String, String),
+}
+
+#[async_trait]
+pub trait DataProfilesRepository: Send + Sync {
+  async fn add_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+    created_at: u64,
+  ) -> Result<(), DataProfilesRepositoryError>;
+
+  async fn get_data_profiles(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<DataProfile>, DataProfilesRepositoryError>;
+
+  async fn get_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+  ) -> Result<DataProfile, DataProfilesRepositoryError>;
+
+  async fn delete_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+  ) -> Result<(), DataProfilesRepositoryError>;
+
+  /// Returns `None` when the default profile is selected.
+  async fn get_active_data_profile(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Option<String>, DataProfilesRepositoryError>;
+
+  async fn set_active_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: Option<&str>,
+  ) -> Result<(), DataProfilesRepositoryError>;
+}
@@ -0,0 +1,6 @@
+pub mod data_profiles_repository;
+pub mod sqlite_data_profiles_repository;
+
+pub use data_profiles_repository::{
+  DataProfile, DataProfilesRepository, DataProfilesRepositoryError,
+};
@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{ErrorCode, OptionalExtension, Row};
+
+use crate::data_profiles::repository::{
+  DataProfile, DataProfilesRepository, DataProfilesRepositoryError,
+};
+use crate::infra::repository::db_helper::run_db;
+use crate::variants::GameVariant;
+
+pub struct SqliteDataProfilesRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteDataProfilesRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+fn row_to_data_profile(row: &Row) -> rusqlite::Result<DataProfile> {
+  let game_variant_str: String = row.get(0)?;
+  let game_variant = GameVariant::from_str(&game_variant_str)
+    .map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        0,
+        rusqlite::types::Type::Text,
+        Box::new(e),
+      )
+    })?;
+
+  Ok(DataProfile {
+    game_variant,
+    name: row.get(1)?,
+    created_at: row.get(2)?,
+  })
+}
+
+#[async_trait]
+impl DataProfilesRepository for SqliteDataProfilesRepository {
+  async fn add_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+    created_at: u64,
+  ) -> Result<(), DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let profile_name = name.to_string();
+
+    let result = run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT INTO data_profiles (game_variant, name, created_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![variant_name, profile_name, created_at],
+      )
+    })
+    .await;
+
+    match result {
+      Ok(_) => Ok(()),
+      Err(e)
+        if e.downcast_ref::<rusqlite::Error>().is_some_and(|e| {
+          e.sqlite_error_code()
+            == Some(ErrorCode::ConstraintViolation)
+        }) =>
+      {
+        Err(DataProfilesRepositoryError::AlreadyExists(
+          name.to_string(),
+          game_variant.to_string(),
+        ))
+      }
+      Err(e) => Err(DataProfilesRepositoryError::Add(e)),
+    }
+  }
+
+  async fn get_data_profiles(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<DataProfile>, DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT game_variant, name, created_at FROM data_profiles WHERE game_variant = ?1 ORDER BY created_at ASC",
+      )?;
+      let profiles = stmt
+        .query_map([&variant_name], row_to_data_profile)?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<DataProfile>, rusqlite::Error>(profiles)
+    })
+    .await
+    .map_err(DataProfilesRepositoryError::Get)
+  }
+
+  async fn get_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+  ) -> Result<DataProfile, DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let profile_name = name.to_string();
+
+    let profile = run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT game_variant, name, created_at FROM data_profiles WHERE game_variant = ?1 AND name = ?2",
+          [&variant_name, &profile_name],
+          row_to_data_profile,
+        )
+        .optional()
+    })
+    .await
+    .map_err(DataProfilesRepositoryError::Get)?;
+
+    profile.ok_or_else(|| {
+      DataProfilesRepositoryError::NotFound(
+        name.to_string(),
+        game_variant.to_string(),
+      )
+    })
+  }
+
+  async fn delete_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: &str,
+  ) -> Result<(), DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let profile_name = name.to_string();
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute(
+        "DELETE FROM data_profiles WHERE game_variant = ?1 AND name = ?2",
+        [&variant_name, &profile_name],
+      )
+    })
+    .await
+    .map_err(DataProfilesRepositoryError::Delete)?;
+
+    if rows_affected == 0 {
+      return Err(DataProfilesRepositoryError::NotFound(
+        name.to_string(),
+        game_variant.to_string(),
+      ));
+    }
+
+    Ok(())
+  }
+
+  async fn get_active_data_profile(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Option<String>, DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT profile_name FROM active_data_profiles WHERE game_variant = ?1",
+          [&variant_name],
+          |row| row.get(0),
+        )
+        .optional()
+    })
+    .await
+    .map_err(DataProfilesRepositoryError::GetActive)
+  }
+
+  async fn set_active_data_profile(
+    &self,
+    game_variant: &GameVariant,
+    name: Option<&str>,
+  ) -> Result<(), DataProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let profile_name = name.map(str::to_string);
+
+    run_db(pool, move |conn| {
+      match profile_name {
+        Some(profile_name) => conn.execute(
+          "INSERT OR REPLACE INTO active_data_profiles (game_variant, profile_name) VALUES (?1, ?2)",
+          [&variant_name, &profile_name],
+        ),
+        None => conn.execute(
+          "DELETE FROM active_data_profiles WHERE game_variant = ?1",
+          [&variant_name],
+        ),
+      }?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(DataProfilesRepositoryError::SetActive)
+  }
+}
@@ -52,17 +52,40 @@ pub enum AssetDownloadDirError {
   CreateDirectory(#[from] io::Error),
 }
 
+pub fn get_asset_download_dir(
+  variant: &GameVariant,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("Assets").join(variant.id())
+}
+
 pub async fn get_or_create_asset_download_dir(
   variant: &GameVariant,
   data_dir: &Path,
 ) -> Result<PathBuf, AssetDownloadDirError> {
-  let dir = data_dir.join("Assets").join(variant.id());
+  let dir = get_asset_download_dir(variant, data_dir);
 
   create_dir_all(&dir).await?;
 
   Ok(dir)
 }
 
+pub fn get_partial_download_filepath(filepath: &Path) -> PathBuf {
+  let mut file_name = filepath.as_os_str().to_owned();
+  file_name.push(".part");
+  PathBuf::from(file_name)
+}
+
+/// The file holding the ETag or Last-Modified value of the response a
+/// partial download was started from.
+pub fn get_partial_download_validator_filepath(
+  filepath: &Path,
+) -> PathBuf {
+  let mut file_name = filepath.as_os_str().to_owned();
+  file_name.push(".part.validator");
+  PathBuf::from(file_name)
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GetAutomaticBackupsDirError {
   #[error("failed to create backup directory: {0}")]
@@ -84,16 +107,25 @@ pub enum AssetExtractionDirError {
   CreateDirectory(#[from] io::Error),
 }
 
-pub async fn get_or_create_asset_installation_dir(
+pub fn get_asset_installation_dir(
   variant: &GameVariant,
   release_version: &str,
   data_dir: &Path,
-) -> Result<PathBuf, AssetExtractionDirError> {
+) -> PathBuf {
   let safe_dir_name = get_safe_filename(release_version);
-  let dir = data_dir
+  data_dir
     .join("Assets")
     .join(variant.id())
-    .join(&safe_dir_name);
+    .join(&safe_dir_name)
+}
+
+pub async fn get_or_create_asset_installation_dir(
+  variant: &GameVariant,
+  release_version: &str,
+  data_dir: &Path,
+) -> Result<PathBuf, AssetExtractionDirError> {
+  let dir =
+    get_asset_installation_dir(variant, release_version, data_dir);
 
   create_dir_all(&dir).await?;
 
@@ -341,11 +373,26 @@ pub enum GetUserGameDataDirError {
   DirFailed(#[from] io::Error),
 }
 
+pub fn get_user_game_data_dir(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> PathBuf {
+  match data_profile {
+    None => data_dir.join("UserData").join(variant.id()),
+    Some(name) => data_dir
+      .join("UserDataProfiles")
+      .join(variant.id())
+      .join(name),
+  }
+}
+
 pub async fn get_or_create_user_game_data_dir(
   variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
 ) -> Result<PathBuf, GetUserGameDataDirError> {
-  let dir = data_dir.join("UserData").join(variant.id());
+  let dir = get_user_game_data_dir(variant, data_profile, data_dir);
   create_dir_all(&dir).await?;
 
   Ok(dir)
@@ -411,6 +458,102 @@ pub async fn get_or_create_manual_backup_archive_filepath(
   )))
 }
 
+pub fn get_automatic_backup_manifest_filepath(
+  variant: &GameVariant,
+  id: i64,
+  version: &str,
+  timestamp: u64,
+  data_dir: &Path,
+) -> PathBuf {
+  data_dir.join("Backups").join("Automatic").join(format!(
+    "{}_{}_{}_{}.json",
+    id,
+    variant.id(),
+    get_safe_filename(version),
+    timestamp
+  ))
+}
+
+pub fn get_backup_chunks_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("Backups").join("Chunks")
+}
+
+pub fn get_restore_staging_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("RestoreStaging")
+}
+
+pub fn get_backup_upload_staging_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("BackupUploadStaging")
+}
+
+pub fn get_save_sync_staging_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("SaveSyncStaging")
+}
+
+pub fn get_mod_install_staging_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("ModInstallStaging")
+}
+
+pub fn get_pre_restore_dir(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> PathBuf {
+  let dir = data_dir.join("PreRestore").join(variant.id());
+  match data_profile {
+    None => dir.join("Default"),
+    Some(name) => dir.join("Profiles").join(name),
+  }
+}
+
+pub fn get_world_trash_dir(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> PathBuf {
+  let dir = data_dir.join("WorldTrash").join(variant.id());
+  match data_profile {
+    None => dir.join("Default"),
+    Some(name) => dir.join("Profiles").join(name),
+  }
+}
+
+pub fn get_crash_reports_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("CrashReports")
+}
+
+pub fn get_crash_report_dir(
+  variant: &GameVariant,
+  id: i64,
+  timestamp: u64,
+  data_dir: &Path,
+) -> PathBuf {
+  get_crash_reports_dir(data_dir).join(format!(
+    "{}_{}_{}",
+    id,
+    variant.id(),
+    timestamp
+  ))
+}
+
+pub fn get_session_logs_dir(data_dir: &Path) -> PathBuf {
+  data_dir.join("SessionLogs")
+}
+
+pub fn get_session_log_filepath(
+  variant: &GameVariant,
+  id: i64,
+  started_at: u64,
+  data_dir: &Path,
+) -> PathBuf {
+  get_session_logs_dir(data_dir).join(format!(
+    "{}_{}_{}.log",
+    id,
+    variant.id(),
+    started_at
+  ))
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GetOrCreateDirectoryError {
   #[error("failed to create directory: {0}")]
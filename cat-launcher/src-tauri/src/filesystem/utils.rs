@@ -1,8 +1,9 @@
 use std::io;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use tokio::fs::{create_dir_all, read_dir};
 use tokio::process::Command;
+use walkdir::WalkDir;
 
 use crate::infra::utils::OS;
 
@@ -13,6 +14,24 @@ pub fn get_safe_filename(name: &str) -> String {
     .collect()
 }
 
+/// Joins a `/` or `\` separated path read from an archive or manifest onto
+/// `root`. Returns None if the path could leave `root`.
+pub fn join_relative_path(
+  root: &Path,
+  relative_path: &str,
+) -> Option<PathBuf> {
+  let mut path = root.to_path_buf();
+  for part in relative_path.split(['/', '\\']) {
+    let mut components = Path::new(part).components();
+    match (components.next(), components.next()) {
+      (Some(Component::Normal(_)), None) => path.push(part),
+      _ => return None,
+    }
+  }
+
+  Some(path)
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum CopyDirError {
   #[error("IO error: {0}")]
@@ -58,3 +77,30 @@ pub async fn copy_dir_all(
 
   Ok(())
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetDirSizeError {
+  #[error("failed to walk directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  #[error("failed to join task: {0}")]
+  Join(#[from] tokio::task::JoinError),
+}
+
+pub async fn get_dir_size(
+  dir: &Path,
+) -> Result<u64, GetDirSizeError> {
+  let dir = dir.to_path_buf();
+
+  tokio::task::spawn_blocking(move || {
+    let mut size = 0;
+    for entry in WalkDir::new(&dir) {
+      let entry = entry?;
+      if entry.file_type().is_file() {
+        size += entry.metadata()?.len();
+      }
+    }
+    Ok(size)
+  })
+  .await?
+}
@@ -41,6 +41,7 @@ pub struct GameRelease {
 pub enum GameReleaseStatus {
   NotAvailable,
   NotDownloaded,
+  PartiallyDownloaded,
   Corrupted,
   NotInstalled,
   ReadyToPlay,
@@ -108,5 +108,13 @@ pub async fn get_release_by_id(
   release.status =
     release.get_installation_status(os, data_dir).await?;
 
+  if release.status == GameReleaseStatus::NotDownloaded
+    && release
+      .has_partial_download(&gh_release.assets, data_dir)
+      .await
+  {
+    release.status = GameReleaseStatus::PartiallyDownloaded;
+  }
+
   Ok(release)
 }
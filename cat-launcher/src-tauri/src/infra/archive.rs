@@ -13,7 +13,9 @@ use zip::write::FileOptions;
 use zip::CompressionMethod::Deflated;
 use zip::ZipWriter;
 
-use crate::filesystem::utils::{copy_dir_all, CopyDirError};
+use crate::filesystem::utils::{
+  copy_dir_all, join_relative_path, CopyDirError,
+};
 use crate::infra::utils::OS;
 
 #[derive(thiserror::Error, Debug)]
@@ -109,6 +111,92 @@ pub async fn extract_archive(
   }
 }
 
+/// Returns the names of the entries in a zip archive. Only the central
+/// directory is read, so nothing is extracted.
+pub async fn list_zip_entries(
+  archive_path: &Path,
+) -> Result<Vec<String>, ExtractionError> {
+  let archive_path = archive_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let archive = zip::ZipArchive::new(File::open(&archive_path)?)?;
+    Ok(
+      archive
+        .file_names()
+        .map(|name| name.replace('\\', "/"))
+        .collect(),
+    )
+  })
+  .await?
+}
+
+/// Reads every entry of a zip archive without extracting it, which checks
+/// the entries against their CRC-32 checksums.
+pub async fn test_zip_archive(
+  archive_path: &Path,
+) -> Result<(), ExtractionError> {
+  let archive_path = archive_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut archive =
+      zip::ZipArchive::new(File::open(&archive_path)?)?;
+
+    for index in 0..archive.len() {
+      let mut entry = archive.by_index(index)?;
+      io::copy(&mut entry, &mut io::sink())?;
+    }
+
+    Ok(())
+  })
+  .await?
+}
+
+/// Extracts the files of a zip archive whose names match `filter`. Names are
+/// passed to `filter` with `/` separators. Returns the number of files
+/// extracted.
+pub async fn extract_zip_entries<F>(
+  archive_path: &Path,
+  target_dir: &Path,
+  filter: F,
+) -> Result<usize, ExtractionError>
+where
+  F: Fn(&str) -> bool + Send + 'static,
+{
+  let archive_path = archive_path.to_owned();
+  let target_dir = target_dir.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut archive =
+      zip::ZipArchive::new(File::open(&archive_path)?)?;
+    let mut extracted = 0;
+
+    for index in 0..archive.len() {
+      let mut entry = archive.by_index(index)?;
+      let name = entry.name().replace('\\', "/");
+      if entry.is_dir() || !filter(&name) {
+        continue;
+      }
+
+      let target = join_relative_path(&target_dir, &name)
+        .ok_or_else(|| {
+          io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid entry path: {}", name),
+          )
+        })?;
+      if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)?;
+      }
+
+      io::copy(&mut entry, &mut File::create(&target)?)?;
+      extracted += 1;
+    }
+
+    Ok(extracted)
+  })
+  .await?
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ArchiveCreationError {
   #[error("destination is a directory")]
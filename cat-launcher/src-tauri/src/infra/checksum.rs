@@ -0,0 +1,31 @@
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tokio::task::JoinError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ComputeSha256Error {
+  #[error("failed to read file: {0}")]
+  Read(#[from] io::Error),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+pub async fn compute_file_sha256(
+  path: &Path,
+) -> Result<String, ComputeSha256Error> {
+  let path = path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut file = File::open(&path)?;
+    let mut hasher = Sha256::new();
+    // Stream the file instead of reading it whole; release archives
+    // can be hundreds of megabytes.
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+  })
+  .await?
+}
@@ -1,30 +1,124 @@
+use std::io;
 use std::num::NonZeroU16;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use downloader::progress::Reporter;
-use reqwest::Client;
+use reqwest::header::{
+  HeaderMap, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::{Client, StatusCode, Url};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
+use tokio::fs::{self, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::filesystem::paths::{
+  get_partial_download_filepath,
+  get_partial_download_validator_filepath,
+};
 
 #[derive(Error, Debug)]
 pub enum DownloadFileError {
-  #[error("downloader creation failed: {0}")]
-  DownloaderCreation(#[from] downloader::Error),
+  #[error("could not determine file name from url: {0}")]
+  InvalidUrl(String),
+
+  #[error("request failed: {0}")]
+  Request(#[from] reqwest::Error),
+
+  #[error("server responded with status {0}")]
+  Status(StatusCode),
+
+  #[error("connection closed after {received} of {expected} bytes")]
+  Incomplete { received: u64, expected: u64 },
+
+  #[error("server did not resume the download at byte {0}")]
+  UnexpectedRange(u64),
+
+  #[error(
+    "downloaded file is corrupted: expected SHA-256 {expected}, got {actual}"
+  )]
+  DigestMismatch { expected: String, actual: String },
+
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+}
 
-  #[error("no download result found")]
-  NoDownloadResult,
+impl DownloadFileError {
+  fn is_retryable(&self) -> bool {
+    match self {
+      DownloadFileError::Request(_)
+      | DownloadFileError::Incomplete { .. }
+      | DownloadFileError::UnexpectedRange(_) => true,
+      DownloadFileError::Status(status) => {
+        status.is_server_error()
+          || *status == StatusCode::TOO_MANY_REQUESTS
+          || *status == StatusCode::RANGE_NOT_SATISFIABLE
+      }
+      DownloadFileError::InvalidUrl(_)
+      | DownloadFileError::Io(_)
+      | DownloadFileError::DigestMismatch { .. } => false,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+  pub max_attempts: NonZeroU16,
+  pub initial_backoff: Duration,
+  pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+  pub fn backoff_for_attempt(&self, attempt: u16) -> Duration {
+    let factor = 2u32.saturating_pow(u32::from(attempt - 1));
+    self
+      .initial_backoff
+      .saturating_mul(factor)
+      .min(self.max_backoff)
+  }
+}
+
+// The SHA-256 of the bytes written to a partial file so far. It is kept
+// across attempts, so that a resumed download does not read the file
+// again.
+#[derive(Default)]
+struct PartialDigest {
+  hasher: Sha256,
+  len: u64,
+}
+
+impl PartialDigest {
+  fn update(&mut self, data: &[u8]) {
+    self.hasher.update(data);
+    self.len += data.len() as u64;
+  }
+
+  async fn of_file(path: &Path) -> io::Result<Self> {
+    let mut file = fs::File::open(path).await?;
+    let mut digest = Self::default();
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+      let read = file.read(&mut buf).await?;
+      if read == 0 {
+        return Ok(digest);
+      }
+      digest.update(&buf[..read]);
+    }
+  }
 }
 
 pub struct Downloader {
   client: Client,
-  parallel_requests: NonZeroU16,
+  retry_policy: RetryPolicy,
 }
 
 impl Downloader {
-  pub fn new(client: Client, parallel_requests: NonZeroU16) -> Self {
+  pub fn new(client: Client, retry_policy: RetryPolicy) -> Self {
     Self {
       client,
-      parallel_requests,
+      retry_policy,
     }
   }
 
@@ -34,25 +128,436 @@ impl Downloader {
     download_dir: &Path,
     reporter: Arc<dyn Reporter + Send + Sync>,
   ) -> Result<PathBuf, DownloadFileError> {
-    let mut builder = downloader::Downloader::builder();
-    builder
-      .download_folder(download_dir)
-      .parallel_requests(self.parallel_requests.get());
+    self
+      .download_verified_file(url, download_dir, None, reporter)
+      .await
+  }
+
+  /// Downloads a file and, if `expected_sha256` is given, fails unless
+  /// the downloaded bytes have that digest. The digest is computed while
+  /// the file is written.
+  pub async fn download_verified_file(
+    &self,
+    url: &str,
+    download_dir: &Path,
+    expected_sha256: Option<&str>,
+    reporter: Arc<dyn Reporter + Send + Sync>,
+  ) -> Result<PathBuf, DownloadFileError> {
+    let file_name = get_download_filename(url).ok_or_else(|| {
+      DownloadFileError::InvalidUrl(url.to_string())
+    })?;
+
+    fs::create_dir_all(download_dir).await?;
+    let filepath = download_dir.join(file_name);
+    let partial_filepath = get_partial_download_filepath(&filepath);
+    let validator_filepath =
+      get_partial_download_validator_filepath(&filepath);
+
+    let mut digest = PartialDigest::default();
+    let max_attempts = self.retry_policy.max_attempts.get();
+    let mut attempt = 1;
+    loop {
+      match self
+        .download_attempt(
+          url,
+          &partial_filepath,
+          &validator_filepath,
+          &mut digest,
+          reporter.as_ref(),
+        )
+        .await
+      {
+        Ok(()) => break,
+        Err(e) if e.is_retryable() && attempt < max_attempts => {
+          eprintln!(
+            "Download of {} failed (attempt {}/{}): {}",
+            url, attempt, max_attempts, e
+          );
+          tokio::time::sleep(
+            self.retry_policy.backoff_for_attempt(attempt),
+          )
+          .await;
+          attempt += 1;
+        }
+        // The partial file is intentionally kept so that a later
+        // download can resume from where this one stopped.
+        Err(e) => return Err(e),
+      }
+    }
 
-    let mut downloader =
-      builder.build_with_client(self.client.clone())?;
+    let actual = format!("{:x}", digest.hasher.finalize());
+    if let Some(expected) = expected_sha256 {
+      if !actual.eq_ignore_ascii_case(expected) {
+        // A corrupted file must not be resumed by the next download.
+        discard_partial_download(
+          &partial_filepath,
+          &validator_filepath,
+        )
+        .await?;
+        return Err(DownloadFileError::DigestMismatch {
+          expected: expected.to_string(),
+          actual,
+        });
+      }
+    }
 
-    let dl = downloader::Download::new(url).progress(reporter);
+    fs::rename(&partial_filepath, &filepath).await?;
+    remove_file_if_exists(&validator_filepath).await?;
+    reporter.done();
 
-    let results = downloader.async_download(&[dl]).await?;
+    Ok(filepath)
+  }
 
-    if let Some(res) = results.into_iter().next() {
-      match res {
-        Ok(summary) => Ok(summary.file_name),
-        Err(e) => Err(DownloadFileError::DownloaderCreation(e)),
+  async fn download_attempt(
+    &self,
+    url: &str,
+    partial_filepath: &Path,
+    validator_filepath: &Path,
+    digest: &mut PartialDigest,
+    reporter: &(dyn Reporter + Send + Sync),
+  ) -> Result<(), DownloadFileError> {
+    let offset = match fs::metadata(partial_filepath).await {
+      Ok(metadata) => metadata.len(),
+      Err(_) => 0,
+    };
+    // A partial file is only resumed if the server can confirm that the
+    // remote file has not changed since it was started. Otherwise the
+    // download starts over.
+    let validator = if offset > 0 {
+      fs::read_to_string(validator_filepath).await.ok()
+    } else {
+      None
+    };
+
+    let mut request = self.client.get(url);
+    if let Some(validator) = &validator {
+      request = request
+        .header(RANGE, format!("bytes={}-", offset))
+        .header(IF_RANGE, validator.as_str());
+    }
+
+    let mut response = request.send().await?;
+    let status = response.status();
+
+    let (mut file, mut downloaded) = if status
+      == StatusCode::PARTIAL_CONTENT
+    {
+      if validator.is_none()
+        || get_range_start(response.headers()) != Some(offset)
+      {
+        discard_partial_download(
+          partial_filepath,
+          validator_filepath,
+        )
+        .await?;
+        return Err(DownloadFileError::UnexpectedRange(offset));
+      }
+
+      // The partial file was left by an earlier download, whose digest
+      // is not known.
+      if digest.len != offset {
+        *digest = PartialDigest::of_file(partial_filepath).await?;
       }
+
+      let file = OpenOptions::new()
+        .append(true)
+        .open(partial_filepath)
+        .await?;
+      (file, offset)
+    } else if status.is_success() {
+      // Either nothing was downloaded yet, or the remote file changed and
+      // the server sent all of it instead of the requested range.
+      let file = fs::File::create(partial_filepath).await?;
+      match get_validator(response.headers()) {
+        Some(validator) => {
+          fs::write(validator_filepath, validator).await?
+        }
+        None => remove_file_if_exists(validator_filepath).await?,
+      }
+      *digest = PartialDigest::default();
+      (file, 0)
+    } else if status == StatusCode::RANGE_NOT_SATISFIABLE {
+      // The partial file cannot be checked against the remote file, so
+      // throw it away and let the retry start from scratch.
+      discard_partial_download(partial_filepath, validator_filepath)
+        .await?;
+      return Err(DownloadFileError::Status(status));
     } else {
-      Err(DownloadFileError::NoDownloadResult)
+      return Err(DownloadFileError::Status(status));
+    };
+
+    let expected =
+      response.content_length().map(|len| len + downloaded);
+    reporter.setup(expected, url);
+    reporter.progress(downloaded);
+
+    while let Some(chunk) = response.chunk().await? {
+      file.write_all(&chunk).await?;
+      digest.update(&chunk);
+      downloaded += chunk.len() as u64;
+      reporter.progress(downloaded);
+    }
+
+    file.flush().await?;
+
+    if let Some(expected) = expected {
+      if downloaded < expected {
+        return Err(DownloadFileError::Incomplete {
+          received: downloaded,
+          expected,
+        });
+      }
+    }
+
+    Ok(())
+  }
+}
+
+pub fn get_download_filename(url: &str) -> Option<PathBuf> {
+  let url = Url::parse(url).ok()?;
+  let file_name = url.path_segments()?.next_back()?;
+
+  if file_name.is_empty() {
+    return None;
+  }
+
+  Some(PathBuf::from(file_name))
+}
+
+// If-Range only accepts strong ETags, so a weak one is replaced by the
+// modification date.
+fn get_validator(headers: &HeaderMap) -> Option<String> {
+  let header = |name| {
+    headers
+      .get(name)
+      .and_then(|value| value.to_str().ok())
+      .map(str::to_string)
+  };
+
+  header(ETAG)
+    .filter(|etag| !etag.starts_with("W/"))
+    .or_else(|| header(LAST_MODIFIED))
+}
+
+// A 206 response carries the range it contains as
+// "bytes <start>-<end>/<size>".
+fn get_range_start(headers: &HeaderMap) -> Option<u64> {
+  headers
+    .get(CONTENT_RANGE)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("bytes "))
+    .and_then(|range| range.split_once('-'))
+    .and_then(|(start, _)| start.parse().ok())
+}
+
+async fn remove_file_if_exists(path: &Path) -> io::Result<()> {
+  match fs::remove_file(path).await {
+    Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+    _ => Ok(()),
+  }
+}
+
+async fn discard_partial_download(
+  partial_filepath: &Path,
+  validator_filepath: &Path,
+) -> io::Result<()> {
+  remove_file_if_exists(partial_filepath).await?;
+  remove_file_if_exists(validator_filepath).await
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Mutex;
+
+  use super::*;
+  use crate::infra::test_utils::{self, Response};
+
+  const BODY: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+  struct NoopReporter;
+
+  impl Reporter for NoopReporter {
+    fn setup(&self, _max_progress: Option<u64>, _message: &str) {}
+    fn progress(&self, _current: u64) {}
+    fn set_message(&self, _message: &str) {}
+    fn done(&self) {}
+  }
+
+  // Serves every request with the response returned for its index and
+  // head, and records the request heads.
+  async fn serve(
+    respond: impl Fn(usize, &str) -> Response + Send + Sync + 'static,
+  ) -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+
+    let received = requests.clone();
+    let address = test_utils::serve(move |head, _| {
+      let mut received = received.lock().unwrap();
+      let response = respond(received.len(), head);
+      received.push(head.to_string());
+      response
+    })
+    .await;
+
+    (format!("http://{}/release.zip", address), requests)
+  }
+
+  fn full_response(etag: &str) -> Response {
+    Response {
+      status: "200 OK",
+      headers: vec![format!("etag: {}", etag)],
+      content_length: BODY.len(),
+      body: BODY.to_vec(),
     }
   }
+
+  fn downloader() -> Downloader {
+    Downloader::new(
+      Client::new(),
+      RetryPolicy {
+        max_attempts: NonZeroU16::new(3).unwrap(),
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+      },
+    )
+  }
+
+  #[tokio::test]
+  async fn resumes_after_the_connection_drops() {
+    let (url, requests) = serve(|index, _| match index {
+      0 => Response {
+        body: BODY[..10].to_vec(),
+        ..full_response("\"v1\"")
+      },
+      _ => Response {
+        status: "206 Partial Content",
+        headers: vec![
+          "etag: \"v1\"".to_string(),
+          format!(
+            "content-range: bytes 10-{}/{}",
+            BODY.len() - 1,
+            BODY.len()
+          ),
+        ],
+        content_length: BODY.len() - 10,
+        body: BODY[10..].to_vec(),
+      },
+    })
+    .await;
+    let dir = test_utils::temp_dir("download");
+
+    let path = downloader()
+      .download_file(&url, &dir, Arc::new(NoopReporter))
+      .await
+      .unwrap();
+
+    assert_eq!(fs::read(&path).await.unwrap(), BODY);
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].contains("range: bytes=10-"));
+    assert!(requests[1].contains("if-range: \"v1\""));
+    assert!(!fs::try_exists(
+      get_partial_download_validator_filepath(&path)
+    )
+    .await
+    .unwrap());
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn starts_over_when_the_remote_file_changed() {
+    // The server answers the If-Range request with the whole new file.
+    let (url, requests) = serve(|_, _| full_response("\"v2\"")).await;
+    let dir = test_utils::temp_dir("download");
+    fs::create_dir_all(&dir).await.unwrap();
+    let filepath = dir.join("release.zip");
+    fs::write(get_partial_download_filepath(&filepath), b"stale")
+      .await
+      .unwrap();
+    fs::write(
+      get_partial_download_validator_filepath(&filepath),
+      "\"v1\"",
+    )
+    .await
+    .unwrap();
+
+    let path = downloader()
+      .download_file(&url, &dir, Arc::new(NoopReporter))
+      .await
+      .unwrap();
+
+    assert_eq!(fs::read(&path).await.unwrap(), BODY);
+    assert!(requests.lock().unwrap()[0].contains("if-range: \"v1\""));
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn does_not_resume_without_a_validator() {
+    let (url, requests) = serve(|_, _| Response {
+      headers: Vec::new(),
+      ..full_response("")
+    })
+    .await;
+    let dir = test_utils::temp_dir("download");
+    fs::create_dir_all(&dir).await.unwrap();
+    let filepath = dir.join("release.zip");
+    fs::write(get_partial_download_filepath(&filepath), b"stale")
+      .await
+      .unwrap();
+
+    let path = downloader()
+      .download_file(&url, &dir, Arc::new(NoopReporter))
+      .await
+      .unwrap();
+
+    assert_eq!(fs::read(&path).await.unwrap(), BODY);
+    assert!(!requests.lock().unwrap()[0].contains("range:"));
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
+
+  #[tokio::test]
+  async fn starts_over_when_the_range_is_not_satisfiable() {
+    let (url, requests) = serve(|index, _| match index {
+      0 => Response {
+        status: "416 Range Not Satisfiable",
+        headers: vec![format!(
+          "content-range: bytes */{}",
+          BODY.len()
+        )],
+        content_length: 0,
+        body: Vec::new(),
+      },
+      _ => full_response("\"v1\""),
+    })
+    .await;
+    let dir = test_utils::temp_dir("download");
+    fs::create_dir_all(&dir).await.unwrap();
+    let filepath = dir.join("release.zip");
+    // The same size as the remote file, but different content.
+    fs::write(
+      get_partial_download_filepath(&filepath),
+      [0; BODY.len()],
+    )
+    .await
+    .unwrap();
+    fs::write(
+      get_partial_download_validator_filepath(&filepath),
+      "\"v1\"",
+    )
+    .await
+    .unwrap();
+
+    let path = downloader()
+      .download_file(&url, &dir, Arc::new(NoopReporter))
+      .await
+      .unwrap();
+
+    assert_eq!(fs::read(&path).await.unwrap(), BODY);
+    assert!(!requests.lock().unwrap()[1].contains("range:"));
+
+    let _ = fs::remove_dir_all(&dir).await;
+  }
 }
@@ -21,6 +21,7 @@ pub enum AssetDownloadError {
 }
 
 impl GitHubAsset {
+  /// Downloads the asset, verifying it against its digest if it has one.
   pub async fn download(
     &self,
     downloader: &Downloader,
@@ -28,12 +29,26 @@ impl GitHubAsset {
     progress: Arc<dyn Reporter + Send + Sync>,
   ) -> Result<PathBuf, AssetDownloadError> {
     downloader
-      .download_file(
+      .download_verified_file(
         &self.browser_download_url,
         download_dir,
+        self.sha256_digest().as_deref(),
         progress,
       )
       .await
       .map_err(AssetDownloadError::from)
   }
+
+  // GitHub reports digests as "<algorithm>:<hex>". Only SHA-256 is
+  // currently published; anything else is treated as absent.
+  pub fn sha256_digest(&self) -> Option<String> {
+    let digest = self.digest.as_deref()?;
+    let (algorithm, hash) = digest.split_once(':')?;
+
+    if !algorithm.eq_ignore_ascii_case("sha256") || hash.is_empty() {
+      return None;
+    }
+
+    Some(hash.to_ascii_lowercase())
+  }
 }
@@ -44,3 +44,28 @@ pub async fn get_last_commit(
 
   Ok(commit)
 }
+
+/// Returns the commit a branch, tag or commit SHA of a repository
+/// points to. Branches are given as `heads/<branch>` and tags as
+/// `tags/<tag>`.
+pub async fn get_commit(
+  repo: &str,
+  reference: &str,
+  client: &Client,
+) -> Result<GitHubCommit, GetLastCommitError> {
+  let api_url = format!(
+    "https://api.github.com/repos/{}/commits/{}",
+    repo, reference
+  );
+
+  let response = client.get(&api_url).send().await?;
+
+  if !response.status().is_success() {
+    return Err(GetLastCommitError::InvalidResponse(format!(
+      "GitHub API returned status: {}",
+      response.status()
+    )));
+  }
+
+  Ok(response.json().await?)
+}
@@ -5,6 +5,7 @@ use crate::infra::rfc3339;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct GitHubCommit {
+  pub sha: String,
   pub commit: GitHubCommitData,
 }
 
@@ -1,9 +1,12 @@
 pub mod archive;
 pub mod autoupdate;
+pub mod checksum;
 pub mod download;
 pub mod github;
 pub mod http_client;
 pub mod installation_progress_monitor;
 pub mod repository;
 pub mod rfc3339;
+#[cfg(test)]
+pub mod test_utils;
 pub mod utils;
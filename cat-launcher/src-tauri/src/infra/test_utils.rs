@@ -0,0 +1,103 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The response of a server started by `serve`.
+pub struct Response {
+  pub status: &'static str,
+  pub headers: Vec<String>,
+  pub content_length: usize,
+  pub body: Vec<u8>,
+}
+
+impl Response {
+  pub fn new(status: &'static str, body: Vec<u8>) -> Self {
+    Self {
+      status,
+      headers: Vec::new(),
+      content_length: body.len(),
+      body,
+    }
+  }
+}
+
+// Returns the lowercased head of a request and its body.
+async fn read_request(stream: &mut TcpStream) -> (String, Vec<u8>) {
+  let mut bytes = Vec::new();
+  let mut buf = [0; 4096];
+  let head_len = loop {
+    if let Some(index) =
+      bytes.windows(4).position(|window| window == b"\r\n\r\n")
+    {
+      break index + 4;
+    }
+    let read = stream.read(&mut buf).await.unwrap();
+    assert_ne!(read, 0, "connection closed before request head");
+    bytes.extend_from_slice(&buf[..read]);
+  };
+
+  let head =
+    String::from_utf8_lossy(&bytes[..head_len]).to_lowercase();
+  // Uploads must not be chunked, so every body has a length.
+  let content_length = head
+    .lines()
+    .find_map(|line| line.strip_prefix("content-length: "))
+    .map_or(0, |len| len.trim().parse().unwrap());
+
+  let mut body = bytes[head_len..].to_vec();
+  while body.len() < content_length {
+    let read = stream.read(&mut buf).await.unwrap();
+    assert_ne!(read, 0, "connection closed before request body");
+    body.extend_from_slice(&buf[..read]);
+  }
+
+  (head, body)
+}
+
+/// Stands in for an HTTP server. Every connection is served with the
+/// response returned for its request head and body, then closed. A
+/// response whose body is shorter than its content length simulates a
+/// dropped connection.
+pub async fn serve(
+  mut respond: impl FnMut(&str, Vec<u8>) -> Response + Send + 'static,
+) -> SocketAddr {
+  let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let address = listener.local_addr().unwrap();
+
+  tokio::spawn(async move {
+    loop {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let (head, body) = read_request(&mut stream).await;
+      let response = respond(&head, body);
+
+      let mut bytes = format!(
+        "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n",
+        response.status, response.content_length
+      );
+      for header in &response.headers {
+        bytes.push_str(header);
+        bytes.push_str("\r\n");
+      }
+      bytes.push_str("\r\n");
+
+      let mut bytes = bytes.into_bytes();
+      bytes.extend_from_slice(&response.body);
+      let _ = stream.write_all(&bytes).await;
+      let _ = stream.shutdown().await;
+    }
+  });
+
+  address
+}
+
+/// Returns a unique directory below the system's temporary directory.
+/// It is not created.
+pub fn temp_dir(name: &str) -> PathBuf {
+  std::env::temp_dir().join(format!(
+    "cat-launcher-{}-{}",
+    name,
+    uuid::Uuid::new_v4()
+  ))
+}
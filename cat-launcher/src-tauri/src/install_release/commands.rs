@@ -1,5 +1,6 @@
 use std::env::consts::OS;
 use std::sync::Arc;
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
 
 use strum::IntoStaticStr;
 use tauri::ipc::Channel;
@@ -8,6 +9,10 @@ use tauri::{command, AppHandle, Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::backup_settings::repository::sqlite_backup_settings_repository::SqliteBackupSettingsRepository;
+use crate::backup_targets::mirror::BackupMirror;
+use crate::backups::triggers::{take_backup_before_update, TriggeredBackupError};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::game_release::game_release::GameRelease;
 use crate::game_release::utils::{get_release_by_id, GetReleaseError};
@@ -15,6 +20,9 @@ use crate::infra::download::Downloader;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_arch_enum, get_os_enum, ArchNotSupportedError, OSNotSupportedError};
 use crate::install_release::install_release::ReleaseInstallationError;
+use crate::installed_releases::repository::sqlite_installed_releases_repository::SqliteInstalledReleasesRepository;
+use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
 
 use crate::variants::GameVariant;
 
@@ -36,6 +44,12 @@ pub enum InstallReleaseCommandError {
 
   #[error("failed to get arch enum: {0}")]
   Arch(#[from] ArchNotSupportedError),
+
+  #[error("failed to get current time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+
+  #[error("failed to back up saves before update: {0}")]
+  Backup(#[from] TriggeredBackupError),
 }
 
 #[command]
@@ -45,6 +59,18 @@ pub async fn install_release(
   release_id: &str,
   releases_repository: State<'_, SqliteReleasesRepository>,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  installed_releases_repository: State<
+    '_,
+    SqliteInstalledReleasesRepository,
+  >,
+  backup_repository: State<'_, SqliteBackupRepository>,
+  backup_settings_repository: State<
+    '_,
+    SqliteBackupSettingsRepository,
+  >,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  backup_mirror: State<'_, BackupMirror>,
+  running_games: State<'_, RunningGamesRegistry>,
   downloader: State<'_, Downloader>,
   on_download_progress: Channel,
 ) -> Result<GameRelease, InstallReleaseCommandError> {
@@ -64,7 +90,21 @@ pub async fn install_release(
   )
   .await?;
 
+  take_backup_before_update(
+    &variant,
+    &release.version,
+    &data_dir,
+    backup_repository.inner().clone(),
+    &*backup_settings_repository,
+    &*active_release_repository,
+    &*data_profiles_repository,
+  )
+  .await?;
+  backup_mirror.request_sync();
+
   let progress = Arc::new(ChannelReporter::new(on_download_progress));
+  let installed_at =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
   release
     .install_release(
@@ -75,6 +115,9 @@ pub async fn install_release(
       &resource_dir,
       &*releases_repository,
       &*active_release_repository,
+      &*installed_releases_repository,
+      &running_games,
+      installed_at,
       progress,
     )
     .await?;
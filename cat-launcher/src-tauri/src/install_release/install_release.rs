@@ -16,10 +16,17 @@ use crate::game_release::game_release::{
   GameRelease, GameReleaseStatus,
 };
 use crate::infra::archive::{extract_archive, ExtractionError};
-use crate::infra::download::Downloader;
+use crate::infra::download::{DownloadFileError, Downloader};
 use crate::infra::github::asset::AssetDownloadError;
 use crate::infra::utils::{Arch, OS};
 use crate::install_release::installation_status::status::GetInstallationStatusError;
+use crate::installed_releases::installed_releases::{
+  apply_release_retention_policy, record_installed_release,
+};
+use crate::installed_releases::repository::{
+  InstalledReleasesRepository, InstalledReleasesRepositoryError,
+};
+use crate::running_games::running_games::RunningGamesRegistry;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ReleaseInstallationError {
@@ -29,15 +36,17 @@ pub enum ReleaseInstallationError {
   #[error("failed to get extraction directory: {0}")]
   ExtractionDir(#[from] AssetExtractionDirError),
 
-  #[error("failed to create downloader: {0}")]
-  Downloader(#[from] downloader::Error),
-
   #[error("no compatible asset found")]
   NoCompatibleAsset,
 
   #[error("failed to download asset: {0}")]
   Download(#[from] AssetDownloadError),
 
+  #[error(
+    "downloaded asset is corrupted: expected SHA-256 {expected}, got {actual}"
+  )]
+  DigestMismatch { expected: String, actual: String },
+
   #[error("failed to extract asset: {0}")]
   Extract(#[from] ExtractionError),
 
@@ -46,6 +55,9 @@ pub enum ReleaseInstallationError {
 
   #[error("failed to set active release: {0}")]
   ActiveRelease(#[from] ActiveReleaseError),
+
+  #[error("failed to get installed releases: {0}")]
+  InstalledReleases(#[from] InstalledReleasesRepositoryError),
 }
 
 impl GameRelease {
@@ -59,6 +71,9 @@ impl GameRelease {
     resources_dir: &Path,
     releases_repository: &dyn ReleasesRepository,
     active_release_repository: &dyn ActiveReleaseRepository,
+    installed_releases_repository: &dyn InstalledReleasesRepository,
+    running_games: &RunningGamesRegistry,
+    installed_at: u64,
     progress: Arc<dyn Reporter + Send + Sync>,
   ) -> Result<(), ReleaseInstallationError> {
     if self.status == GameReleaseStatus::Unknown {
@@ -71,6 +86,29 @@ impl GameRelease {
         .variant
         .set_active_release(&self.version, active_release_repository)
         .await?;
+
+      // Installations made before releases were tracked are recorded the
+      // first time they are selected again.
+      let is_recorded = installed_releases_repository
+        .get_installed_releases(&self.variant)
+        .await?
+        .iter()
+        .any(|release| release.version == self.version);
+      if !is_recorded {
+        if let Err(e) = record_installed_release(
+          &self.variant,
+          &self.version,
+          installed_at,
+          false,
+          data_dir,
+          installed_releases_repository,
+        )
+        .await
+        {
+          eprintln!("Failed to record installed release: {}", e);
+        }
+      }
+
       return Ok(());
     }
 
@@ -82,15 +120,43 @@ impl GameRelease {
       .await
       .ok_or(ReleaseInstallationError::NoCompatibleAsset)?;
 
+    // Older releases and the bundled release lists have no digest, so
+    // their archives are installed unverified.
+    let is_verified = asset.sha256_digest().is_some();
+    if !is_verified {
+      eprintln!(
+        "Release {} has no published digest, installing it unverified",
+        self.version
+      );
+    }
+
     if self.status == GameReleaseStatus::NotDownloaded
+      || self.status == GameReleaseStatus::PartiallyDownloaded
       || self.status == GameReleaseStatus::Corrupted
       || self.status == GameReleaseStatus::Unknown
     {
-      asset.download(downloader, &download_dir, progress).await?;
+      match asset.download(downloader, &download_dir, progress).await
+      {
+        Ok(_) => {}
+        // A truncated or tampered archive must not be extracted. The
+        // downloader has already removed it, so that the next attempt
+        // downloads it from scratch.
+        Err(AssetDownloadError::Download(
+          DownloadFileError::DigestMismatch { expected, actual },
+        )) => {
+          self.status = GameReleaseStatus::Corrupted;
+          return Err(ReleaseInstallationError::DigestMismatch {
+            expected,
+            actual,
+          });
+        }
+        Err(e) => return Err(e.into()),
+      }
       self.status = GameReleaseStatus::NotInstalled;
     }
 
     let download_filepath = download_dir.join(&asset.name);
+
     let installation_dir = get_or_create_asset_installation_dir(
       &self.variant,
       &self.version,
@@ -111,42 +177,41 @@ impl GameRelease {
     // Failure to remove file does not mean failure to install
     let _ = fs::remove_file(&download_filepath).await;
 
-    delete_other_installations(&installation_dir).await;
-
-    Ok(())
-  }
-}
-
-async fn delete_other_installations(installation_dir: &Path) {
-  let Some(parent) = installation_dir.parent() else {
-    return;
-  };
-
-  let Ok(mut entries) = fs::read_dir(parent).await else {
-    return;
-  };
-
-  let Ok(kept_path) = fs::canonicalize(installation_dir).await else {
-    return;
-  };
-
-  while let Ok(Some(entry)) = entries.next_entry().await {
-    let path = entry.path();
-
-    let Ok(metadata) = fs::metadata(&path).await else {
-      continue;
-    };
-
-    if !metadata.is_dir() {
-      continue;
+    // The installation itself succeeded, so failing to track it or to
+    // clean up older releases must not be reported as a failed install.
+    match record_installed_release(
+      &self.variant,
+      &self.version,
+      installed_at,
+      is_verified,
+      data_dir,
+      installed_releases_repository,
+    )
+    .await
+    {
+      Ok(()) => {
+        if let Err(e) = apply_release_retention_policy(
+          &self.variant,
+          &self.version,
+          data_dir,
+          resources_dir,
+          installed_releases_repository,
+          releases_repository,
+          running_games,
+        )
+        .await
+        {
+          eprintln!(
+            "Failed to apply release retention policy: {}",
+            e
+          );
+        }
+      }
+      Err(e) => {
+        eprintln!("Failed to record installed release: {}", e)
+      }
     }
 
-    let Ok(canonical_path) = fs::canonicalize(&path).await else {
-      continue;
-    };
-
-    if canonical_path != kept_path {
-      let _ = fs::remove_dir_all(&path).await;
-    }
+    Ok(())
   }
 }
@@ -3,12 +3,14 @@ use std::path::Path;
 use tokio::fs;
 
 use crate::filesystem::paths::{
-  get_game_executable_filepath, AssetDownloadDirError,
+  get_asset_download_dir, get_game_executable_filepath,
+  get_partial_download_filepath, AssetDownloadDirError,
   AssetExtractionDirError, GetExecutablePathError,
 };
 use crate::game_release::game_release::{
   GameRelease, GameReleaseStatus,
 };
+use crate::infra::github::asset::GitHubAsset;
 use crate::infra::utils::OS;
 
 #[derive(thiserror::Error, Debug)]
@@ -53,4 +55,24 @@ impl GameRelease {
 
     Ok(GameReleaseStatus::ReadyToPlay)
   }
+
+  pub async fn has_partial_download(
+    &self,
+    assets: &[GitHubAsset],
+    data_dir: &Path,
+  ) -> bool {
+    let download_dir =
+      get_asset_download_dir(&self.variant, data_dir);
+
+    for asset in assets {
+      let partial_filepath = get_partial_download_filepath(
+        &download_dir.join(&asset.name),
+      );
+      if fs::try_exists(&partial_filepath).await.unwrap_or(false) {
+        return true;
+      }
+    }
+
+    false
+  }
 }
@@ -0,0 +1,133 @@
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::installed_releases::installed_releases::{
+  get_release_retention_policy as get_release_retention_policy_impl,
+  list_installed_releases as list_installed_releases_impl,
+  set_installed_release_pinned as set_installed_release_pinned_impl,
+  set_release_retention_policy as set_release_retention_policy_impl,
+  uninstall_release as uninstall_release_impl, ListInstalledReleasesError,
+  ReleaseRetentionPolicyError, SetInstalledReleasePinnedError,
+  UninstallReleaseError,
+};
+use crate::installed_releases::repository::sqlite_installed_releases_repository::SqliteInstalledReleasesRepository;
+use crate::installed_releases::repository::{
+  InstalledRelease, ReleaseRetentionPolicy,
+};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum ListInstalledReleasesCommandError {
+  #[error("failed to list installed releases: {0}")]
+  List(#[from] ListInstalledReleasesError),
+}
+
+#[command]
+pub async fn list_installed_releases(
+  variant: GameVariant,
+  repository: State<'_, SqliteInstalledReleasesRepository>,
+) -> Result<Vec<InstalledRelease>, ListInstalledReleasesCommandError>
+{
+  let releases =
+    list_installed_releases_impl(&variant, &*repository).await?;
+  Ok(releases)
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum UninstallReleaseCommandError {
+  #[error("failed to get system directory: {0}")]
+  SystemDir(#[from] tauri::Error),
+
+  #[error("failed to uninstall release: {0}")]
+  Uninstall(#[from] UninstallReleaseError),
+}
+
+#[command]
+pub async fn uninstall_release(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  version: String,
+  installed_releases_repository: State<
+    '_,
+    SqliteInstalledReleasesRepository,
+  >,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), UninstallReleaseCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  uninstall_release_impl(
+    &variant,
+    &version,
+    &data_dir,
+    &*installed_releases_repository,
+    &*active_release_repository,
+    &running_games,
+  )
+  .await?;
+
+  Ok(())
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum SetInstalledReleasePinnedCommandError {
+  #[error("failed to pin installed release: {0}")]
+  SetPinned(#[from] SetInstalledReleasePinnedError),
+}
+
+#[command]
+pub async fn set_installed_release_pinned(
+  variant: GameVariant,
+  version: String,
+  is_pinned: bool,
+  repository: State<'_, SqliteInstalledReleasesRepository>,
+) -> Result<(), SetInstalledReleasePinnedCommandError> {
+  set_installed_release_pinned_impl(
+    &variant,
+    &version,
+    is_pinned,
+    &*repository,
+  )
+  .await?;
+  Ok(())
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum ReleaseRetentionPolicyCommandError {
+  #[error("failed to access release retention policy: {0}")]
+  RetentionPolicy(#[from] ReleaseRetentionPolicyError),
+}
+
+#[command]
+pub async fn get_release_retention_policy(
+  variant: GameVariant,
+  repository: State<'_, SqliteInstalledReleasesRepository>,
+) -> Result<ReleaseRetentionPolicy, ReleaseRetentionPolicyCommandError>
+{
+  let policy =
+    get_release_retention_policy_impl(&variant, &*repository).await?;
+  Ok(policy)
+}
+
+#[command]
+pub async fn set_release_retention_policy(
+  variant: GameVariant,
+  policy: ReleaseRetentionPolicy,
+  repository: State<'_, SqliteInstalledReleasesRepository>,
+) -> Result<(), ReleaseRetentionPolicyCommandError> {
+  set_release_retention_policy_impl(&variant, &policy, &*repository)
+    .await?;
+  Ok(())
+}
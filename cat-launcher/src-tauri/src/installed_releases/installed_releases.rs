@@ -0,0 +1,301 @@
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+
+use tokio::fs;
+
+use crate::active_release::repository::{
+  ActiveReleaseRepository, ActiveReleaseRepositoryError,
+};
+use crate::fetch_releases::repository::{
+  ReleasesRepository, ReleasesRepositoryError,
+};
+use crate::fetch_releases::utils::get_default_releases;
+use crate::filesystem::paths::{
+  get_asset_download_dir, get_asset_installation_dir,
+};
+use crate::filesystem::utils::{get_dir_size, GetDirSizeError};
+use crate::installed_releases::repository::{
+  InstalledRelease, InstalledReleasesRepository,
+  InstalledReleasesRepositoryError, ReleaseRetentionPolicy,
+};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RecordInstalledReleaseError {
+  #[error("failed to compute installation size: {0}")]
+  Size(#[from] GetDirSizeError),
+
+  #[error("failed to record installed release: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+}
+
+pub async fn record_installed_release(
+  variant: &GameVariant,
+  version: &str,
+  installed_at: u64,
+  is_verified: bool,
+  data_dir: &Path,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<(), RecordInstalledReleaseError> {
+  let installation_dir =
+    get_asset_installation_dir(variant, version, data_dir);
+  let size_in_bytes = get_dir_size(&installation_dir).await?;
+
+  repository
+    .add_installed_release(&InstalledRelease {
+      game_variant: *variant,
+      version: version.to_string(),
+      size_in_bytes,
+      installed_at,
+      is_pinned: false,
+      is_verified,
+    })
+    .await?;
+
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListInstalledReleasesError {
+  #[error("failed to get installed releases: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+}
+
+pub async fn list_installed_releases(
+  variant: &GameVariant,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<Vec<InstalledRelease>, ListInstalledReleasesError> {
+  Ok(repository.get_installed_releases(variant).await?)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum UninstallReleaseError {
+  #[error(
+    "release {0} is the active release and cannot be uninstalled"
+  )]
+  ActiveRelease(String),
+
+  #[error(
+    "release {0} is in use by a running game and cannot be uninstalled"
+  )]
+  InUse(String),
+
+  #[error("failed to get active release: {0}")]
+  GetActiveRelease(#[from] ActiveReleaseRepositoryError),
+
+  #[error("failed to access installed releases: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+
+  #[error("failed to delete installation directory: {0}")]
+  DeleteInstallationDir(#[from] io::Error),
+}
+
+pub async fn uninstall_release(
+  variant: &GameVariant,
+  version: &str,
+  data_dir: &Path,
+  installed_releases_repository: &dyn InstalledReleasesRepository,
+  active_release_repository: &dyn ActiveReleaseRepository,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), UninstallReleaseError> {
+  let active_release = active_release_repository
+    .get_active_release(variant)
+    .await?;
+  if active_release.as_deref() == Some(version) {
+    return Err(UninstallReleaseError::ActiveRelease(
+      version.to_string(),
+    ));
+  }
+
+  let is_running = running_games.list().iter().any(|game| {
+    game.game_variant == *variant && game.release_version == version
+  });
+  if is_running {
+    return Err(UninstallReleaseError::InUse(version.to_string()));
+  }
+
+  remove_installation(
+    variant,
+    version,
+    data_dir,
+    installed_releases_repository,
+  )
+  .await
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SetInstalledReleasePinnedError {
+  #[error("failed to update installed release: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+}
+
+pub async fn set_installed_release_pinned(
+  variant: &GameVariant,
+  version: &str,
+  is_pinned: bool,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<(), SetInstalledReleasePinnedError> {
+  repository
+    .set_installed_release_pinned(variant, version, is_pinned)
+    .await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReleaseRetentionPolicyError {
+  #[error("failed to access release retention policy: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+}
+
+pub async fn get_release_retention_policy(
+  variant: &GameVariant,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<ReleaseRetentionPolicy, ReleaseRetentionPolicyError> {
+  Ok(repository.get_retention_policy(variant).await?)
+}
+
+pub async fn set_release_retention_policy(
+  variant: &GameVariant,
+  policy: &ReleaseRetentionPolicy,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<(), ReleaseRetentionPolicyError> {
+  repository.set_retention_policy(variant, policy).await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ApplyRetentionPolicyError {
+  #[error("failed to access installed releases: {0}")]
+  Repository(#[from] InstalledReleasesRepositoryError),
+
+  #[error("failed to uninstall release: {0}")]
+  Uninstall(#[from] UninstallReleaseError),
+
+  #[error("failed to get releases: {0}")]
+  Releases(#[from] ReleasesRepositoryError),
+
+  #[error("failed to read installations directory: {0}")]
+  ReadDir(#[from] io::Error),
+}
+
+/// Uninstalls the releases of a variant beyond the ones its retention
+/// policy keeps. Releases that are running are always kept.
+pub async fn apply_release_retention_policy(
+  variant: &GameVariant,
+  current_version: &str,
+  data_dir: &Path,
+  resources_dir: &Path,
+  repository: &dyn InstalledReleasesRepository,
+  releases_repository: &dyn ReleasesRepository,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), ApplyRetentionPolicyError> {
+  let ReleaseRetentionPolicy::KeepLatest { count } =
+    repository.get_retention_policy(variant).await?
+  else {
+    return Ok(());
+  };
+
+  let running_versions: HashSet<String> = running_games
+    .list()
+    .into_iter()
+    .filter(|game| game.game_variant == *variant)
+    .map(|game| game.release_version)
+    .collect();
+  let mut kept_dirs: HashSet<_> = running_versions
+    .iter()
+    .map(String::as_str)
+    .chain([current_version])
+    .map(|version| {
+      get_asset_installation_dir(variant, version, data_dir)
+    })
+    .collect();
+
+  // Releases are ordered from the most recently installed one. Pinned
+  // releases are always kept and do not count towards the limit.
+  let mut remaining = count as usize;
+  for release in repository.get_installed_releases(variant).await? {
+    let keep = release.is_pinned
+      || release.version == current_version
+      || running_versions.contains(&release.version)
+      || remaining > 0;
+
+    if !keep {
+      remove_installation(
+        variant,
+        &release.version,
+        data_dir,
+        repository,
+      )
+      .await?;
+      continue;
+    }
+
+    if !release.is_pinned {
+      remaining = remaining.saturating_sub(1);
+    }
+    kept_dirs.insert(get_asset_installation_dir(
+      variant,
+      &release.version,
+      data_dir,
+    ));
+  }
+
+  // Installations made before releases were tracked have no entry in
+  // the repository. Only the directories of known releases are removed,
+  // as anything else in the directory was not installed by the launcher.
+  let release_dirs: HashSet<_> = releases_repository
+    .get_cached_releases(variant)
+    .await?
+    .into_iter()
+    .chain(get_default_releases(variant, resources_dir).await)
+    .map(|release| {
+      get_asset_installation_dir(variant, &release.tag_name, data_dir)
+    })
+    .collect();
+
+  let mut entries =
+    match fs::read_dir(get_asset_download_dir(variant, data_dir))
+      .await
+    {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+      Err(e) => return Err(e.into()),
+    };
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    if !entry.file_type().await?.is_dir()
+      || kept_dirs.contains(&path)
+      || !release_dirs.contains(&path)
+    {
+      continue;
+    }
+
+    let _ = fs::remove_dir_all(&path).await;
+  }
+
+  Ok(())
+}
+
+async fn remove_installation(
+  variant: &GameVariant,
+  version: &str,
+  data_dir: &Path,
+  repository: &dyn InstalledReleasesRepository,
+) -> Result<(), UninstallReleaseError> {
+  let installation_dir =
+    get_asset_installation_dir(variant, version, data_dir);
+
+  match fs::remove_dir_all(&installation_dir).await {
+    Ok(()) => {}
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  repository
+    .delete_installed_release(variant, version)
+    .await?;
+
+  Ok(())
+}
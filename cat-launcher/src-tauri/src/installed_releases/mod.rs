@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod installed_releases;
+
+pub mod commands;
+pub mod repository;
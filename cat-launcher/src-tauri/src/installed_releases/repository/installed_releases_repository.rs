@@ -0,0 +1,99 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct InstalledRelease {
+  pub game_variant: GameVariant,
+  pub version: String,
+  pub size_in_bytes: u64,
+  pub installed_at: u64,
+  pub is_pinned: bool,
+  /// False if the release had no published digest to check the download
+  /// against, or was installed before downloads were verified.
+  pub is_verified: bool,
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+#[serde(tag = "type", content = "payload")]
+pub enum ReleaseRetentionPolicy {
+  KeepLatest { count: u32 },
+  KeepAll,
+}
+
+impl Default for ReleaseRetentionPolicy {
+  // Matches the behaviour from before multiple installations were
+  // supported: only the most recently installed release is kept.
+  fn default() -> Self {
+    ReleaseRetentionPolicy::KeepLatest { count: 1 }
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum InstalledReleasesRepositoryError {
+  #[error("failed to add installed release: {0}")]
+  Add(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get installed releases: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete installed release: {0}")]
+  Delete(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to update installed release: {0}")]
+  Update(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get release retention policy: {0}")]
+  GetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set release retention policy: {0}")]
+  SetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("installed release {0} not found for variant {1}")]
+  NotFound(String, String),
+}
+
+#[async_trait]
+pub trait InstalledReleasesRepository: Send + Sync {
+  async fn add_installed_release(
+    &self,
+    release: &InstalledRelease,
+  ) -> Result<(), InstalledReleasesRepositoryError>;
+
+  async fn get_installed_releases(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<InstalledRelease>, InstalledReleasesRepositoryError>;
+
+  async fn delete_installed_release(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+  ) -> Result<(), InstalledReleasesRepositoryError>;
+
+  async fn set_installed_release_pinned(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+    is_pinned: bool,
+  ) -> Result<(), InstalledReleasesRepositoryError>;
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<ReleaseRetentionPolicy, InstalledReleasesRepositoryError>;
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseRetentionPolicy,
+  ) -> Result<(), InstalledReleasesRepositoryError>;
+}
@@ -0,0 +1,7 @@
+pub mod installed_releases_repository;
+pub mod sqlite_installed_releases_repository;
+
+pub use installed_releases_repository::{
+  InstalledRelease, InstalledReleasesRepository,
+  InstalledReleasesRepositoryError, ReleaseRetentionPolicy,
+};
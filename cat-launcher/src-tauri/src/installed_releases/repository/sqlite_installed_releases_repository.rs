@@ -0,0 +1,211 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::installed_releases::repository::{
+  InstalledRelease, InstalledReleasesRepository,
+  InstalledReleasesRepositoryError, ReleaseRetentionPolicy,
+};
+use crate::variants::GameVariant;
+
+pub struct SqliteInstalledReleasesRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteInstalledReleasesRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl InstalledReleasesRepository
+  for SqliteInstalledReleasesRepository
+{
+  async fn add_installed_release(
+    &self,
+    release: &InstalledRelease,
+  ) -> Result<(), InstalledReleasesRepositoryError> {
+    let pool = self.pool.clone();
+    let release = release.clone();
+
+    run_db(pool, move |conn| {
+      // Reinstalling a release refreshes its size and install time but
+      // keeps the pin chosen by the user.
+      conn.execute(
+        "INSERT INTO installed_releases (game_variant, version, size_in_bytes, installed_at, is_pinned, is_verified)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT (game_variant, version) DO UPDATE SET
+           size_in_bytes = excluded.size_in_bytes,
+           installed_at = excluded.installed_at,
+           is_verified = excluded.is_verified",
+        rusqlite::params![
+          release.game_variant.to_string(),
+          release.version,
+          release.size_in_bytes,
+          release.installed_at,
+          release.is_pinned,
+          release.is_verified,
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::Add)
+  }
+
+  async fn get_installed_releases(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<InstalledRelease>, InstalledReleasesRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT game_variant, version, size_in_bytes, installed_at, is_pinned, is_verified
+         FROM installed_releases
+         WHERE game_variant = ?1
+         ORDER BY installed_at DESC",
+      )?;
+      let releases = stmt
+        .query_map([&variant_name], |row| {
+          let game_variant_str: String = row.get(0)?;
+          let game_variant = GameVariant::from_str(&game_variant_str)
+            .map_err(|e| {
+              rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+              )
+            })?;
+          Ok(InstalledRelease {
+            game_variant,
+            version: row.get(1)?,
+            size_in_bytes: row.get(2)?,
+            installed_at: row.get(3)?,
+            is_pinned: row.get(4)?,
+            is_verified: row.get(5)?,
+          })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<InstalledRelease>, rusqlite::Error>(releases)
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::Get)
+  }
+
+  async fn delete_installed_release(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+  ) -> Result<(), InstalledReleasesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let version_clone = version.to_string();
+
+    let rows_affected = run_db(pool, move |conn| {
+      let count = conn.execute(
+        "DELETE FROM installed_releases WHERE game_variant = ?1 AND version = ?2",
+        [&variant_name, &version_clone],
+      )?;
+      Ok::<usize, rusqlite::Error>(count)
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::Delete)?;
+
+    if rows_affected == 0 {
+      return Err(InstalledReleasesRepositoryError::NotFound(
+        version.to_string(),
+        game_variant.to_string(),
+      ));
+    }
+
+    Ok(())
+  }
+
+  async fn set_installed_release_pinned(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+    is_pinned: bool,
+  ) -> Result<(), InstalledReleasesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let version_clone = version.to_string();
+
+    let rows_affected = run_db(pool, move |conn| {
+      let count = conn.execute(
+        "UPDATE installed_releases SET is_pinned = ?3 WHERE game_variant = ?1 AND version = ?2",
+        rusqlite::params![variant_name, version_clone, is_pinned],
+      )?;
+      Ok::<usize, rusqlite::Error>(count)
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::Update)?;
+
+    if rows_affected == 0 {
+      return Err(InstalledReleasesRepositoryError::NotFound(
+        version.to_string(),
+        game_variant.to_string(),
+      ));
+    }
+
+    Ok(())
+  }
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<ReleaseRetentionPolicy, InstalledReleasesRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let keep_count = conn
+        .query_row(
+          "SELECT keep_count FROM release_retention_policies WHERE game_variant = ?1",
+          [&variant_name],
+          |row| row.get::<_, Option<u32>>(0),
+        )
+        .optional()?;
+
+      let policy = match keep_count {
+        None => ReleaseRetentionPolicy::default(),
+        Some(None) => ReleaseRetentionPolicy::KeepAll,
+        Some(Some(count)) => ReleaseRetentionPolicy::KeepLatest { count },
+      };
+      Ok::<ReleaseRetentionPolicy, rusqlite::Error>(policy)
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::GetRetentionPolicy)
+  }
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &ReleaseRetentionPolicy,
+  ) -> Result<(), InstalledReleasesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let keep_count = match policy {
+      ReleaseRetentionPolicy::KeepLatest { count } => Some(*count),
+      ReleaseRetentionPolicy::KeepAll => None,
+    };
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO release_retention_policies (game_variant, keep_count) VALUES (?1, ?2)",
+        rusqlite::params![variant_name, keep_count],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(InstalledReleasesRepositoryError::SetRetentionPolicy)
+  }
+}
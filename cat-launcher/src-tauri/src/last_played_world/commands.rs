@@ -1,6 +1,10 @@
 use strum::IntoStaticStr;
-use tauri::{command, AppHandle, Manager};
+use tauri::{command, AppHandle, Manager, State};
 
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::last_played_world::last_played_world::{
   get_last_played_world as get_last_played_world_impl,
   GetLastPlayedWorldError,
@@ -17,16 +21,27 @@ pub enum GetLastPlayedWorldCommandError {
 
   #[error("failed to get app local data directory: {0}")]
   AppLocalDataDir(#[from] tauri::Error),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[command]
 pub async fn get_last_played_world(
   app_handle: AppHandle,
   variant: GameVariant,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<Option<String>, GetLastPlayedWorldCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
-  let last_played_world =
-    get_last_played_world_impl(&data_dir, &variant).await?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+  let last_played_world = get_last_played_world_impl(
+    &data_dir,
+    &variant,
+    data_profile.as_deref(),
+  )
+  .await?;
 
   Ok(last_played_world)
 }
@@ -8,6 +8,18 @@ use crate::last_played_world::paths::get_last_world_path;
 use crate::last_played_world::types::LastWorld;
 use crate::variants::GameVariant;
 
+#[derive(thiserror::Error, Debug)]
+pub enum UpdateLastPlayedWorldError {
+  #[error("failed to get user game data directory: {0}")]
+  GetUserGameDataDir(#[from] GetUserGameDataDirError),
+
+  #[error("failed to read or write lastworld.json: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to parse lastworld.json: {0}")]
+  Parse(#[from] serde_json::Error),
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum GetLastPlayedWorldError {
   #[error("failed to get user game data directory: {0}")]
@@ -23,9 +35,10 @@ pub enum GetLastPlayedWorldError {
 pub async fn get_last_played_world(
   data_dir: &Path,
   variant: &GameVariant,
+  data_profile: Option<&str>,
 ) -> Result<Option<String>, GetLastPlayedWorldError> {
   let last_world_path =
-    get_last_world_path(data_dir, variant).await?;
+    get_last_world_path(data_dir, variant, data_profile).await?;
 
   if !last_world_path.exists() {
     return Ok(None);
@@ -36,3 +49,44 @@ pub async fn get_last_played_world(
 
   Ok(Some(last_world.world_name))
 }
+
+/// Points lastworld.json at `new_world` if it points at `world`, or removes
+/// it if `new_world` is None because the world no longer exists. The other
+/// fields the game writes are kept.
+pub async fn update_last_played_world(
+  data_dir: &Path,
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  new_world: Option<&str>,
+) -> Result<(), UpdateLastPlayedWorldError> {
+  let last_world_path =
+    get_last_world_path(data_dir, variant, data_profile).await?;
+
+  if !fs::try_exists(&last_world_path).await? {
+    return Ok(());
+  }
+
+  let content = fs::read_to_string(&last_world_path).await?;
+  let mut last_world: serde_json::Value =
+    serde_json::from_str(&content)?;
+  if last_world.get("world_name").and_then(|name| name.as_str())
+    != Some(world)
+  {
+    return Ok(());
+  }
+
+  match new_world {
+    Some(new_world) => {
+      last_world["world_name"] = new_world.into();
+      fs::write(
+        &last_world_path,
+        serde_json::to_string_pretty(&last_world)?,
+      )
+      .await?;
+    }
+    None => fs::remove_file(&last_world_path).await?,
+  }
+
+  Ok(())
+}
@@ -7,8 +7,10 @@ use std::path::PathBuf;
 pub async fn get_last_world_path(
   data_dir: &Path,
   variant: &GameVariant,
+  data_profile: Option<&str>,
 ) -> Result<PathBuf, GetUserGameDataDirError> {
   let user_data_dir =
-    get_or_create_user_game_data_dir(variant, data_dir).await?;
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
   Ok(user_data_dir.join("config").join("lastworld.json"))
 }
@@ -7,13 +7,23 @@ use tauri::{command, AppHandle, Emitter, Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::backup_settings::repository::sqlite_backup_settings_repository::SqliteBackupSettingsRepository;
+use crate::backup_targets::mirror::BackupMirror;
+use crate::crash_reports::repository::sqlite_crash_reports_repository::SqliteCrashReportsRepository;
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::launch_game::launch_game::{
   launch_and_monitor_game, GameEvent, LaunchGameError,
 };
 use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::launch_profiles::repository::sqlite_launch_profiles_repository::SqliteLaunchProfilesRepository;
+use crate::play_time::sqlite_play_time_repository::SqlitePlayTimeRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::session_logs::repository::sqlite_session_logs_repository::SqliteSessionLogsRepository;
 use crate::variants::GameVariant;
+use crate::world_versions::repository::sqlite_world_versions_repository::SqliteWorldVersionsRepository;
+use crate::world_versions::world_versions::WorldVersionError;
 
 #[derive(
   thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
@@ -22,6 +32,11 @@ pub enum LaunchGameCommandError {
   #[error("failed to launch game: {0}")]
   LaunchGame(#[from] LaunchGameError),
 
+  // Distinct from LaunchGame, so that the launch can be confirmed and
+  // retried with allow_downgrade.
+  #[error("{0}")]
+  WorldDowngrade(WorldVersionError),
+
   #[error("system directory not found: {0}")]
   SystemDirectoryNotFound(#[from] tauri::Error),
 
@@ -39,9 +54,28 @@ pub async fn launch_game(
   variant: GameVariant,
   release_id: &str,
   world: Option<&str>,
+  profile_id: Option<i64>,
+  data_profile: Option<&str>,
+  allow_duplicate: Option<bool>,
+  allow_downgrade: Option<bool>,
   releases_repository: State<'_, SqliteReleasesRepository>,
   backup_repository: State<'_, SqliteBackupRepository>,
   active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  launch_profiles_repository: State<
+    '_,
+    SqliteLaunchProfilesRepository,
+  >,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  backup_settings_repository: State<
+    '_,
+    SqliteBackupSettingsRepository,
+  >,
+  crash_reports_repository: State<'_, SqliteCrashReportsRepository>,
+  session_logs_repository: State<'_, SqliteSessionLogsRepository>,
+  play_time_repository: State<'_, SqlitePlayTimeRepository>,
+  world_versions_repository: State<'_, SqliteWorldVersionsRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+  backup_mirror: State<'_, BackupMirror>,
 ) -> Result<(), LaunchGameCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let resource_dir = app_handle.path().resource_dir()?;
@@ -50,6 +84,11 @@ pub async fn launch_game(
 
   let os = get_os_enum(OS)?;
 
+  let mirror_handle = app_handle.clone();
+  let on_backups_taken = move || {
+    mirror_handle.state::<BackupMirror>().request_sync();
+  };
+
   let emitter = app_handle.clone();
   let on_game_event = move |event: GameEvent| {
     let emitter = emitter.clone();
@@ -59,10 +98,12 @@ pub async fn launch_game(
     }
   };
 
-  launch_and_monitor_game(
+  let result = launch_and_monitor_game(
     &variant,
     release_id,
     world,
+    profile_id,
+    data_profile,
     &os,
     time,
     &data_dir,
@@ -70,9 +111,29 @@ pub async fn launch_game(
     &*releases_repository,
     backup_repository.inner().clone(),
     &*active_release_repository,
+    &*launch_profiles_repository,
+    &*data_profiles_repository,
+    &*backup_settings_repository,
+    crash_reports_repository.inner().clone(),
+    session_logs_repository.inner().clone(),
+    play_time_repository.inner().clone(),
+    world_versions_repository.inner().clone(),
+    &running_games,
+    allow_duplicate.unwrap_or(false),
+    allow_downgrade.unwrap_or(false),
     on_game_event,
+    on_backups_taken,
   )
-  .await?;
+  .await;
+
+  // The backup taken before launch is uploaded right away. The ones taken
+  // while the game runs are uploaded once it exits.
+  backup_mirror.request_sync();
 
-  Ok(())
+  match result {
+    Err(LaunchGameError::WorldVersion(
+      e @ WorldVersionError::Downgrade { .. },
+    )) => Err(LaunchGameCommandError::WorldDowngrade(e)),
+    result => result.map_err(Into::into),
+  }
 }
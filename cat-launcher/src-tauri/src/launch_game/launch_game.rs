@@ -1,35 +1,75 @@
+use std::collections::VecDeque;
 use std::future::Future;
 use std::io;
-use std::path::Path;
-use std::process::Stdio;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use serde::Serialize;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::task::JoinError;
-use tokio::task::JoinSet;
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
 use ts_rs::TS;
 
 use crate::active_release::repository::ActiveReleaseRepository;
-use crate::constants::MAX_BACKUPS;
+use crate::backup_settings::repository::{
+  BackupKind, BackupSettingsRepository, BackupSettingsRepositoryError,
+};
+use crate::backups::retention::apply_backup_retention_policy;
+use crate::backups::scopes::BackupScope;
+use crate::backups::triggers::{BackupTrigger, TriggeredBackups};
+use crate::constants::CRASH_REPORT_LOG_LINES;
+use crate::crash_reports::crash_reports::collect_crash_report;
+use crate::crash_reports::repository::{
+  CrashReport, CrashReportsRepository,
+};
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, set_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::fetch_releases::repository::ReleasesRepository;
 use crate::filesystem::paths::{
-  get_game_executable_filepath,
-  get_or_create_automatic_backup_archive_filepath,
-  get_or_create_user_game_data_dir, AssetDownloadDirError,
-  AssetExtractionDirError, GetAutomaticBackupArchivePathError,
-  GetExecutablePathError, GetUserGameDataDirError,
+  get_game_executable_filepath, get_or_create_user_game_data_dir,
+  AssetDownloadDirError, AssetExtractionDirError,
+  GetAutomaticBackupArchivePathError, GetExecutablePathError,
+  GetUserGameDataDirError,
 };
 use crate::game_release::game_release::GameRelease;
 use crate::game_release::utils::{
   get_release_by_id, GetReleaseError,
 };
 use crate::infra::utils::OS;
+use crate::last_played_world::last_played_world::get_last_played_world;
 use crate::launch_game::repository::{
   BackupRepository, BackupRepositoryError,
 };
-use crate::launch_game::utils::{backup_save_files, BackupError};
+use crate::launch_game::utils::{take_automatic_backup, BackupError};
+use crate::launch_profiles::launch_profiles::{
+  get_launch_profile, LaunchProfileError,
+};
+use crate::launch_profiles::repository::{
+  LaunchProfileOptions, LaunchProfilesRepository,
+};
+use crate::play_time::play_time::record_play_session;
+use crate::play_time::repository::PlayTimeRepository;
+use crate::running_games::running_games::{
+  isolate_process_tree, terminate_child, terminate_process_tree,
+  RunningGameRegistration, RunningGamesError, RunningGamesRegistry,
+};
+use crate::save_sync::save_sync::{
+  hold_save_sync, SaveSyncInProgressError,
+};
+use crate::session_logs::repository::SessionLogsRepository;
+use crate::session_logs::session_logs::{
+  finish_session_log, start_session_log, LogStream, SessionLog,
+};
 use crate::variants::GameVariant;
+use crate::world_versions::repository::WorldVersionsRepository;
+use crate::world_versions::world_versions::{
+  check_world_version, record_world_version, was_world_saved_since,
+  WorldVersionError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum LaunchGameError {
@@ -45,6 +85,18 @@ pub enum LaunchGameError {
   #[error("executable directory not found")]
   ExecutableDir,
 
+  #[error("working directory not found: {0}")]
+  WorkingDir(String),
+
+  #[error("failed to get launch profile: {0}")]
+  LaunchProfile(#[from] LaunchProfileError),
+
+  #[error("failed to select data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to register running game: {0}")]
+  RunningGames(#[from] RunningGamesError),
+
   #[error("failed to launch game: {0}")]
   Launch(#[from] io::Error),
 
@@ -54,6 +106,9 @@ pub enum LaunchGameError {
   #[error("failed to access backup repository: {0}")]
   BackupRepository(#[from] BackupRepositoryError),
 
+  #[error("failed to get backup scopes: {0}")]
+  BackupSettings(#[from] BackupSettingsRepositoryError),
+
   #[error("failed to get user data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
@@ -66,14 +121,17 @@ pub enum LaunchGameError {
   #[error("failed to obtain release: {0}")]
   Release(#[from] GetReleaseError),
 
-  #[error("failed to wait for subtasks: {0}")]
-  Subtasks(#[from] JoinError),
-
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
 
   #[error("failed to remove backup file: {0}")]
   RemoveBackupFile(io::Error),
+
+  #[error("failed to check world version: {0}")]
+  WorldVersion(#[from] WorldVersionError),
+
+  #[error("cannot launch game: {0}")]
+  SaveSyncInProgress(#[from] SaveSyncInProgressError),
 }
 
 #[derive(Serialize, Clone, TS)]
@@ -82,6 +140,7 @@ pub enum LaunchGameError {
 pub enum GameEvent {
   Log(String),
   Exit(GameExitPayload),
+  Crash(CrashReport),
   Error(GameErrorPayload),
 }
 
@@ -95,13 +154,107 @@ pub struct GameErrorPayload {
 #[ts(export)]
 pub struct GameExitPayload {
   pub code: Option<i32>,
+  pub status: GameExitStatus,
+}
+
+#[derive(
+  Serialize, Clone, Debug, PartialEq, Eq, serde::Deserialize, TS,
+)]
+#[ts(export)]
+#[serde(tag = "type", content = "payload")]
+pub enum GameExitStatus {
+  Clean,
+  NonZero { code: i32 },
+  Signal { signal: i32 },
+}
+
+impl GameExitStatus {
+  pub fn is_abnormal(&self) -> bool {
+    *self != GameExitStatus::Clean
+  }
+
+  /// Whether the exit indicates a crash of `variant`. BrightNights exits
+  /// with a nonzero code almost always, even when it exits normally, so
+  /// only a signal or a Windows exception code counts as its crash.
+  pub fn is_crash(&self, variant: &GameVariant) -> bool {
+    match (self, variant) {
+      (
+        GameExitStatus::NonZero { code },
+        GameVariant::BrightNights,
+      ) => is_windows_exception_code(*code),
+      (status, _) => status.is_abnormal(),
+    }
+  }
+
+  pub fn code(&self) -> Option<i32> {
+    match self {
+      GameExitStatus::Clean => Some(0),
+      GameExitStatus::NonZero { code } => Some(*code),
+      GameExitStatus::Signal { .. } => None,
+    }
+  }
+}
+
+// A process killed by an unhandled exception, such as an access
+// violation (0xC0000005), exits with the exception's NTSTATUS code, whose
+// two highest bits mark it as an error.
+fn is_windows_exception_code(code: i32) -> bool {
+  code as u32 & 0xC000_0000 == 0xC000_0000
+}
+
+impl From<ExitStatus> for GameExitStatus {
+  fn from(status: ExitStatus) -> Self {
+    if status.success() {
+      return GameExitStatus::Clean;
+    }
+
+    #[cfg(unix)]
+    {
+      use std::os::unix::process::ExitStatusExt;
+
+      if let Some(signal) = status.signal() {
+        return GameExitStatus::Signal { signal };
+      }
+    }
+
+    // Without a signal, an unsuccessful exit always carries a code.
+    GameExitStatus::NonZero {
+      code: status.code().unwrap_or(-1),
+    }
+  }
+}
+
+pub struct GameExit {
+  pub status: GameExitStatus,
+  /// Whether the game exited because the user terminated it.
+  pub terminated: bool,
+  /// The time from spawning the game until it exited.
+  pub duration: Duration,
+  /// The last lines the game wrote to stdout and stderr.
+  pub log_lines: Vec<String>,
+}
+
+fn push_log_line(log_lines: &Mutex<VecDeque<String>>, line: &str) {
+  let Ok(mut log_lines) = log_lines.lock() else {
+    return;
+  };
+
+  if log_lines.len() == CRASH_REPORT_LOG_LINES.get() {
+    log_lines.pop_front();
+  }
+  log_lines.push_back(line.to_string());
 }
 
 impl GameRelease {
+  #[allow(clippy::too_many_arguments)]
   pub async fn prepare_launch(
     &self,
     os: &OS,
     world: Option<&str>,
+    data_profile: Option<&str>,
+    profile: Option<&LaunchProfileOptions>,
+    skip_backup: bool,
+    backup_scopes: &[BackupScope],
     timestamp: u64,
     data_dir: &Path,
     backup_repository: &dyn BackupRepository,
@@ -119,52 +272,88 @@ impl GameRelease {
       .ok_or(LaunchGameError::ExecutableDir)?
       .to_path_buf();
 
-    let backup_id = backup_repository
-      .add_backup_entry(&self.variant, &self.version, timestamp)
+    let working_dir = match profile
+      .and_then(|profile| profile.working_dir.as_ref())
+    {
+      Some(working_dir) => {
+        let working_dir = PathBuf::from(working_dir);
+        if !working_dir.is_dir() {
+          return Err(LaunchGameError::WorkingDir(
+            working_dir.display().to_string(),
+          ));
+        }
+        working_dir
+      }
+      None => executable_dir,
+    };
+
+    if !skip_backup {
+      take_automatic_backup(
+        &self.variant,
+        data_profile,
+        &self.version,
+        timestamp,
+        backup_scopes,
+        &BackupTrigger::BeforeLaunch,
+        data_dir,
+        backup_repository,
+      )
       .await?;
+    }
 
-    if let Err(e) = backup_save_files(
+    let user_data_dir = get_or_create_user_game_data_dir(
       &self.variant,
-      backup_id,
-      &self.version,
-      timestamp,
+      data_profile,
       data_dir,
     )
-    .await
-    {
-      let _ = backup_repository.delete_backup_entry(backup_id).await;
-      return Err(e.into());
-    }
-
-    let user_data_dir =
-      get_or_create_user_game_data_dir(&self.variant, data_dir)
-        .await?;
-    let mut command = Command::new(executable_path);
+    .await?;
+    // A wrapper such as gamemoderun or firejail is run as the program
+    // and receives the game executable as its first extra argument.
+    let wrapper_command = profile
+      .map(|profile| profile.wrapper_command.as_slice())
+      .unwrap_or_default();
+    let mut command = match wrapper_command.split_first() {
+      Some((program, args)) => {
+        let mut command = Command::new(program);
+        command.args(args).arg(executable_path);
+        command
+      }
+      None => Command::new(executable_path),
+    };
 
     command
-      .current_dir(executable_dir)
+      .current_dir(working_dir)
       .arg("--userdir")
       .arg(user_data_dir)
       .stdout(Stdio::piped())
       .stderr(Stdio::piped());
+    isolate_process_tree(&mut command);
 
     if let Some(world) = world {
       command.arg("--world").arg(world);
     }
 
+    if let Some(profile) = profile {
+      command.args(&profile.extra_args).envs(&profile.env_vars);
+    }
+
     Ok(command)
   }
 }
 
 pub async fn run_game_and_monitor<F, Fut>(
-  mut command: Command,
+  mut child: Child,
+  mut terminate: oneshot::Receiver<()>,
+  session_log: Option<Arc<SessionLog>>,
   on_game_event: F,
-) -> Result<(), LaunchGameError>
+) -> Result<GameExit, LaunchGameError>
 where
   F: Fn(GameEvent) -> Fut + Send + Sync + 'static + Clone,
   Fut: Future<Output = ()> + Send,
 {
-  let mut child = command.spawn()?;
+  let spawned_at = Instant::now();
+
+  let log_lines = Arc::new(Mutex::new(VecDeque::new()));
 
   let stdout = child.stdout.take().ok_or(LaunchGameError::Stdout)?;
   let stderr = child.stderr.take().ok_or(LaunchGameError::Stderr)?;
@@ -173,98 +362,100 @@ where
   let mut stderr_reader = BufReader::new(stderr).lines();
 
   let on_game_event_clone = on_game_event.clone();
+  let log_lines_clone = log_lines.clone();
+  let session_log_clone = session_log.clone();
   let stdout_task = tokio::spawn(async move {
     while let Some(line) = stdout_reader.next_line().await.transpose()
     {
       if let Ok(line) = line {
+        push_log_line(&log_lines_clone, &line);
+        if let Some(session_log) = &session_log_clone {
+          // A failing log file must not interrupt the game.
+          let _ =
+            session_log.write_line(LogStream::Stdout, &line).await;
+        }
         on_game_event_clone(GameEvent::Log(line)).await;
       }
     }
   });
 
   let on_game_event_clone = on_game_event.clone();
+  let log_lines_clone = log_lines.clone();
+  let session_log_clone = session_log.clone();
   let stderr_task = tokio::spawn(async move {
     while let Some(line) = stderr_reader.next_line().await.transpose()
     {
       if let Ok(line) = line {
+        push_log_line(&log_lines_clone, &line);
+        if let Some(session_log) = &session_log_clone {
+          // A failing log file must not interrupt the game.
+          let _ =
+            session_log.write_line(LogStream::Stderr, &line).await;
+        }
         on_game_event_clone(GameEvent::Log(line)).await;
       }
     }
   });
 
-  let status = child.wait().await?;
-
-  let stdout_task_result = stdout_task.await;
-  let stderr_task_result = stderr_task.await;
-
-  // Exit is emitted before waiting for other tasks to complete so that
-  // an error does not prevent the exit event from being ever emitted.
-  on_game_event(GameEvent::Exit(GameExitPayload {
-    code: status.code(),
-  }))
-  .await;
-
-  stdout_task_result?;
-  stderr_task_result?;
-
-  Ok(())
-}
-
-async fn cleanup_old_backups(
-  backup_repository: impl BackupRepository + Clone + 'static,
-  variant: &GameVariant,
-  data_dir: &Path,
-) -> Result<(), LaunchGameError> {
-  let backups = backup_repository
-    .get_backups_sorted_by_timestamp(variant)
-    .await?;
-
-  if backups.len() <= MAX_BACKUPS.get() {
-    return Ok(());
+  let pid = child.id();
+  let status = tokio::select! {
+    status = child.wait() => Some(status?),
+    Ok(()) = &mut terminate => None,
+  };
+  let mut terminated = status.is_none();
+  let status = match status {
+    Some(status) => status,
+    None => terminate_child(&mut child).await?,
+  };
+
+  // A wrapper or launcher script can exit before the game it started,
+  // which keeps the output open. The game is only done once the output
+  // is closed. A failure to forward output must not prevent the exit
+  // from being handled, so it is only reported.
+  let output_closed = async {
+    for task in [stdout_task, stderr_task] {
+      if let Err(e) = task.await {
+        eprintln!("Error forwarding game output: {}", e);
+      }
+    }
+  };
+  tokio::pin!(output_closed);
+  if !terminated {
+    tokio::select! {
+      () = &mut output_closed => {}
+      Ok(()) = &mut terminate => terminated = true,
+    }
   }
-
-  let num_to_delete = backups.len() - MAX_BACKUPS.get();
-  let backups_to_delete = backups.into_iter().take(num_to_delete);
-
-  let mut set = JoinSet::new();
-
-  for backup in backups_to_delete {
-    let data_dir_clone = data_dir.to_owned();
-    let variant_clone = *variant;
-    let backup_repo_clone = backup_repository.clone();
-    set.spawn(async move {
-      let path_res = get_or_create_automatic_backup_archive_filepath(
-        &variant_clone,
-        backup.id,
-        &backup.release_version,
-        backup.timestamp,
-        &data_dir_clone,
-      )
-      .await;
-
-      if backup_repo_clone
-        .delete_backup_entry(backup.id)
-        .await
-        .is_ok()
-      {
-        if let Ok(path) = path_res {
-          // file deletion fails is ignored.
-          let _ = tokio::fs::remove_file(&path).await;
-        }
+  if terminated {
+    match pid {
+      Some(pid) => {
+        terminate_process_tree(pid, &mut output_closed).await
       }
-    });
+      None => (&mut output_closed).await,
+    }
   }
-
-  while set.join_next().await.is_some() {}
-
-  Ok(())
+  let duration = spawned_at.elapsed();
+
+  let log_lines = log_lines
+    .lock()
+    .map(|log_lines| log_lines.iter().cloned().collect())
+    .unwrap_or_default();
+
+  Ok(GameExit {
+    status: GameExitStatus::from(status),
+    terminated,
+    duration,
+    log_lines,
+  })
 }
 
 #[allow(clippy::too_many_arguments)]
-pub async fn launch_and_monitor_game<F, Fut>(
+pub async fn launch_and_monitor_game<F, Fut, G>(
   variant: &GameVariant,
   release_id: &str,
   world: Option<&str>,
+  profile_id: Option<i64>,
+  data_profile: Option<&str>,
   os: &OS,
   timestamp: u64,
   data_dir: &Path,
@@ -272,11 +463,25 @@ pub async fn launch_and_monitor_game<F, Fut>(
   releases_repository: &dyn ReleasesRepository,
   backup_repository: impl BackupRepository + Clone + 'static,
   active_release_repository: &dyn ActiveReleaseRepository,
+  launch_profiles_repository: &dyn LaunchProfilesRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  backup_settings_repository: &dyn BackupSettingsRepository,
+  crash_reports_repository: impl CrashReportsRepository + Clone + 'static,
+  session_logs_repository: impl SessionLogsRepository + Clone + 'static,
+  play_time_repository: impl PlayTimeRepository + Clone + 'static,
+  world_versions_repository: impl WorldVersionsRepository
+    + Clone
+    + 'static,
+  running_games: &RunningGamesRegistry,
+  allow_duplicate: bool,
+  allow_downgrade: bool,
   on_game_event: F,
+  on_backups_taken: G,
 ) -> Result<(), LaunchGameError>
 where
   F: Fn(GameEvent) -> Fut + Send + Sync + 'static + Clone,
   Fut: Future<Output = ()> + Send,
+  G: FnOnce() + Send + 'static,
 {
   let release = get_release_by_id(
     variant,
@@ -288,30 +493,115 @@ where
   )
   .await?;
 
+  let profile = match profile_id {
+    Some(id) => Some(
+      get_launch_profile(id, variant, launch_profiles_repository)
+        .await?,
+    ),
+    None => None,
+  };
+
+  // Launching with an explicit data profile also makes it the active one,
+  // so that mods, backups and settings follow the profile that was played.
+  let data_profile = match data_profile {
+    Some(name) => {
+      set_active_data_profile(
+        variant,
+        Some(name),
+        data_profiles_repository,
+      )
+      .await?
+    }
+    None => {
+      get_active_data_profile(variant, data_profiles_repository)
+        .await?
+    }
+  };
+
+  if let (Some(world), false) = (world, allow_downgrade) {
+    check_world_version(
+      variant,
+      data_profile.as_deref(),
+      world,
+      &release,
+      &world_versions_repository,
+    )
+    .await?;
+  }
+
+  // A sync replaces worlds in place, so the game only starts once no sync
+  // is in progress. Syncs skip running games, so holding the sync lock
+  // until the game is registered is enough.
+  let save_sync_guard = hold_save_sync()?;
+
+  // The game stays registered until it exits, or until the launch fails
+  // and the guard is dropped.
+  let RunningGameRegistration {
+    guard,
+    duplicate,
+    terminate,
+  } = running_games.register(
+    variant,
+    data_profile.as_deref(),
+    &release.version,
+    world,
+    timestamp,
+    allow_duplicate,
+  )?;
+  drop(save_sync_guard);
+
+  let backup_scopes = backup_settings_repository
+    .get_backup_scopes(variant, &BackupKind::Automatic)
+    .await?;
+  let retention_policy = backup_settings_repository
+    .get_retention_policy(variant)
+    .await?;
+  let trigger_settings = backup_settings_repository
+    .get_trigger_settings(variant)
+    .await?;
+
   // Ignore non-critical error where active release could not be set
   let _ = variant
     .set_active_release(release_id, active_release_repository)
     .await;
 
-  let command = release
+  // The saves of a running game are being written to, so they are only
+  // backed up when no other instance uses the same user data directory.
+  let mut command = release
     .prepare_launch(
       os,
       world,
+      data_profile.as_deref(),
+      profile.as_ref().map(|profile| &profile.options),
+      duplicate || !trigger_settings.before_launch,
+      &backup_scopes,
       timestamp,
       data_dir,
       &backup_repository,
     )
     .await?;
 
+  let triggered_backups = TriggeredBackups {
+    variant: *variant,
+    data_profile: data_profile.clone(),
+    release_version: release.version.clone(),
+    scopes: backup_scopes,
+    retention_policy,
+    data_dir: data_dir.to_path_buf(),
+    backup_repository: backup_repository.clone(),
+  };
+
   let backup_repository_clone = backup_repository.clone();
   let variant_clone = *variant;
   let data_dir_clone = data_dir.to_path_buf();
   let on_game_event_for_cleanup = on_game_event.clone();
   tokio::spawn(async move {
-    if let Err(e) = cleanup_old_backups(
-      backup_repository_clone,
+    if let Err(e) = apply_backup_retention_policy(
       &variant_clone,
+      &retention_policy,
+      timestamp,
       &data_dir_clone,
+      backup_repository_clone,
     )
     .await
     {
@@ -324,22 +614,252 @@ where
     }
   });
 
-  let on_game_event_for_error = on_game_event.clone();
+  // The game is still launched if its session cannot be logged.
+  let session_log = match start_session_log(
+    variant,
+    &release.version,
+    timestamp,
+    data_dir,
+    &session_logs_repository,
+  )
+  .await
+  {
+    Ok(session_log) => Some(Arc::new(session_log)),
+    Err(e) => {
+      eprintln!("Error starting session log: {}", e);
+      None
+    }
+  };
+
+  let session_started_at = SystemTime::now();
+  let child = command.spawn()?;
+  guard.set_pid(child.id());
+
+  if let Some(world) = world {
+    if let Err(e) = record_world_version(
+      variant,
+      data_profile.as_deref(),
+      world,
+      &release,
+      timestamp,
+      &world_versions_repository,
+    )
+    .await
+    {
+      eprintln!("Error recording world version: {}", e);
+    }
+  }
+
+  // Like the backup before launch, backups while the game runs and after
+  // it exits are skipped for duplicate instances.
+  let periodic_backups =
+    match trigger_settings.periodic_interval_minutes {
+      Some(interval_minutes) if !duplicate => {
+        let on_game_event_for_backup = on_game_event.clone();
+        Some(triggered_backups.clone().spawn_periodic(
+          interval_minutes,
+          move |e| {
+            eprintln!("Error taking periodic backup: {}", e);
+
+            let error_payload = GameErrorPayload {
+              message: e.to_string(),
+            };
+            on_game_event_for_backup(GameEvent::Error(error_payload))
+          },
+        ))
+      }
+      _ => None,
+    };
+  let backup_on_exit = trigger_settings.on_exit && !duplicate;
+
+  let variant_clone = *variant;
+  let version = release.version.clone();
+  let release_clone = release.clone();
+  let world = world.map(str::to_string);
+  let data_dir_clone = data_dir.to_path_buf();
 
   // It's important to not await the task here, as it be blocking.
   // run_game_and_monitor streams to the frontend.
   tokio::spawn(async move {
-    let result = run_game_and_monitor(command, on_game_event).await;
+    let result = run_game_and_monitor(
+      child,
+      terminate,
+      session_log.clone(),
+      on_game_event.clone(),
+    )
+    .await;
 
-    if let Err(e) = result {
-      eprintln!("Error running game: {}", e);
+    if let Some(periodic_backups) = periodic_backups {
+      periodic_backups.stop().await;
+    }
 
-      let error_payload = GameErrorPayload {
-        message: e.to_string(),
-      };
-      on_game_event_for_error(GameEvent::Error(error_payload)).await;
+    // The game is still registered while it is backed up, so that it
+    // cannot be launched again while its saves are read.
+    if backup_on_exit {
+      if let Err(e) =
+        triggered_backups.take(&BackupTrigger::OnExit).await
+      {
+        eprintln!("Error taking backup on exit: {}", e);
+
+        let error_payload = GameErrorPayload {
+          message: e.to_string(),
+        };
+        on_game_event(GameEvent::Error(error_payload)).await;
+      }
+    }
+    drop(guard);
+    // The periodic backups and the backup on exit are complete now.
+    on_backups_taken();
+
+    // The launch time is only used if the clock went backwards.
+    let ended_at = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or(timestamp);
+
+    if let Some(session_log) = session_log {
+      if let Err(e) = finish_session_log(
+        &session_log,
+        ended_at,
+        &data_dir_clone,
+        &session_logs_repository,
+      )
+      .await
+      {
+        eprintln!("Error finishing session log: {}", e);
+      }
+    }
+
+    // The world picked in the game's menu is only known from
+    // lastworld.json once the game has saved it. A world left over from
+    // an earlier session must keep the release it was played with.
+    match get_last_played_world(
+      &data_dir_clone,
+      &variant_clone,
+      data_profile.as_deref(),
+    )
+    .await
+    {
+      Ok(Some(last_world)) => match was_world_saved_since(
+        &variant_clone,
+        data_profile.as_deref(),
+        &last_world,
+        &data_dir_clone,
+        session_started_at,
+      )
+      .await
+      {
+        Ok(true) => {
+          if let Err(e) = record_world_version(
+            &variant_clone,
+            data_profile.as_deref(),
+            &last_world,
+            &release_clone,
+            ended_at,
+            &world_versions_repository,
+          )
+          .await
+          {
+            eprintln!("Error recording world version: {}", e);
+          }
+        }
+        Ok(false) => {}
+        Err(e) => {
+          eprintln!("Error checking last played world: {}", e)
+        }
+      },
+      Ok(None) => {}
+      Err(e) => eprintln!("Error reading last played world: {}", e),
+    }
+
+    let exit = match result {
+      Ok(exit) => exit,
+      Err(e) => {
+        eprintln!("Error running game: {}", e);
+
+        let error_payload = GameErrorPayload {
+          message: e.to_string(),
+        };
+        on_game_event(GameEvent::Error(error_payload)).await;
+        return;
+      }
+    };
+
+    let started_at = ended_at.saturating_sub(exit.duration.as_secs());
+    if let Err(e) = record_play_session(
+      &variant_clone,
+      &version,
+      world.as_deref(),
+      started_at,
+      ended_at,
+      exit.status.code(),
+      &play_time_repository,
+    )
+    .await
+    {
+      eprintln!("Error recording play session: {}", e);
+    }
+
+    // Exit is emitted once the session is recorded, so that play time
+    // refreshed on exit already includes it.
+    on_game_event(GameEvent::Exit(GameExitPayload {
+      code: exit.status.code(),
+      status: exit.status.clone(),
+    }))
+    .await;
+
+    // A game terminated from the launcher did not crash.
+    if exit.terminated || !exit.status.is_crash(&variant_clone) {
+      return;
+    }
+
+    let result = collect_crash_report(
+      &variant_clone,
+      &version,
+      data_profile.as_deref(),
+      &exit.status,
+      &exit.log_lines,
+      ended_at,
+      &data_dir_clone,
+      &crash_reports_repository,
+    )
+    .await;
+
+    match result {
+      Ok(report) => on_game_event(GameEvent::Crash(report)).await,
+      Err(e) => {
+        eprintln!("Error collecting crash report: {}", e);
+
+        let error_payload = GameErrorPayload {
+          message: e.to_string(),
+        };
+        on_game_event(GameEvent::Error(error_payload)).await;
+      }
     }
   });
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn bright_nights_nonzero_exit_is_not_a_crash() {
+    let status = GameExitStatus::NonZero { code: 1 };
+
+    assert!(!status.is_crash(&GameVariant::BrightNights));
+    assert!(status.is_crash(&GameVariant::DarkDaysAhead));
+  }
+
+  #[test]
+  fn windows_exception_code_is_a_crash() {
+    let access_violation = GameExitStatus::NonZero {
+      code: 0xC000_0005_u32 as i32,
+    };
+
+    assert!(access_violation.is_crash(&GameVariant::BrightNights));
+    assert!(access_violation.is_crash(&GameVariant::DarkDaysAhead));
+  }
+}
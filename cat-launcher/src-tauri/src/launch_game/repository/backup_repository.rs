@@ -2,6 +2,9 @@ use async_trait::async_trait;
 use serde::Serialize;
 use ts_rs::TS;
 
+use crate::backups::integrity::BackupIntegrity;
+use crate::backups::scopes::BackupScope;
+use crate::backups::triggers::BackupTrigger;
 use crate::variants::GameVariant;
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -11,6 +14,16 @@ pub struct BackupEntry {
   pub game_variant: GameVariant,
   pub release_version: String,
   pub timestamp: u64,
+  pub data_profile: Option<String>,
+  /// The directories of the user data directory the backup contains.
+  pub scopes: Vec<BackupScope>,
+  /// Pinned backups are never removed by the retention policy.
+  pub is_pinned: bool,
+  /// What caused the backup to be taken.
+  pub trigger: BackupTrigger,
+  /// What the backup contained when it was created, or None if it was
+  /// created before this was recorded.
+  pub integrity: Option<BackupIntegrity>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -21,6 +34,9 @@ pub enum BackupRepositoryError {
   #[error("failed to get backup entries: {0}")]
   Get(Box<dyn std::error::Error + Send + Sync>),
 
+  #[error("failed to update backup entry: {0}")]
+  Update(Box<dyn std::error::Error + Send + Sync>),
+
   #[error("failed to delete backup entry: {0}")]
   Delete(Box<dyn std::error::Error + Send + Sync>),
 
@@ -35,6 +51,9 @@ pub trait BackupRepository: Send + Sync {
     game_variant: &GameVariant,
     release_version: &str,
     timestamp: u64,
+    data_profile: Option<&str>,
+    scopes: &[BackupScope],
+    trigger: &BackupTrigger,
   ) -> Result<i64, BackupRepositoryError>;
 
   async fn get_backups_sorted_by_timestamp(
@@ -42,11 +61,28 @@ pub trait BackupRepository: Send + Sync {
     game_variant: &GameVariant,
   ) -> Result<Vec<BackupEntry>, BackupRepositoryError>;
 
+  /// Returns the backups of all variants.
+  async fn get_all_backups(
+    &self,
+  ) -> Result<Vec<BackupEntry>, BackupRepositoryError>;
+
   async fn get_backup_entry(
     &self,
     id: i64,
   ) -> Result<BackupEntry, BackupRepositoryError>;
 
+  async fn set_backup_integrity(
+    &self,
+    id: i64,
+    integrity: &BackupIntegrity,
+  ) -> Result<(), BackupRepositoryError>;
+
+  async fn set_backup_pinned(
+    &self,
+    id: i64,
+    is_pinned: bool,
+  ) -> Result<(), BackupRepositoryError>;
+
   async fn delete_backup_entry(
     &self,
     id: i64,
@@ -4,6 +4,9 @@ use async_trait::async_trait;
 use r2d2_sqlite::SqliteConnectionManager;
 use tokio::task;
 
+use crate::backups::integrity::BackupIntegrity;
+use crate::backups::scopes::{default_backup_scopes, BackupScope};
+use crate::backups::triggers::BackupTrigger;
 use crate::launch_game::repository::{
   BackupEntry, BackupRepository, BackupRepositoryError,
 };
@@ -11,6 +14,8 @@ use crate::variants::GameVariant;
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
+const SELECT_BACKUP: &str = "SELECT b.id, b.game_variant, b.release_version, b.timestamp, p.profile_name, s.scopes, pin.backup_id IS NOT NULL, t.backup_trigger, i.size_in_bytes, i.sha256, i.file_count, i.worlds FROM backups b LEFT JOIN backup_data_profiles p ON p.backup_id = b.id LEFT JOIN backup_scopes s ON s.backup_id = b.id LEFT JOIN pinned_backups pin ON pin.backup_id = b.id LEFT JOIN backup_triggers t ON t.backup_id = b.id LEFT JOIN backup_integrity i ON i.backup_id = b.id";
+
 #[derive(Clone)]
 pub struct SqliteBackupRepository {
   pool: Pool,
@@ -22,6 +27,71 @@ impl SqliteBackupRepository {
   }
 }
 
+// Backups taken before scopes were recorded only contain the save
+// directory.
+fn get_scopes_column(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<Vec<BackupScope>> {
+  let scopes: Option<String> = row.get(index)?;
+  match scopes {
+    Some(scopes) => serde_json::from_str(&scopes).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        index,
+        rusqlite::types::Type::Text,
+        Box::new(e),
+      )
+    }),
+    None => Ok(default_backup_scopes()),
+  }
+}
+
+// Backups taken before triggers were recorded were all taken before the
+// game was launched.
+fn get_trigger_column(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<BackupTrigger> {
+  let trigger: Option<String> = row.get(index)?;
+  match trigger {
+    Some(trigger) => BackupTrigger::from_str(&trigger).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        index,
+        rusqlite::types::Type::Text,
+        Box::new(e),
+      )
+    }),
+    None => Ok(BackupTrigger::BeforeLaunch),
+  }
+}
+
+// Backups created before their integrity was recorded have no row.
+fn get_integrity_columns(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<Option<BackupIntegrity>> {
+  let sha256: Option<String> = row.get(index + 1)?;
+  let Some(sha256) = sha256 else {
+    return Ok(None);
+  };
+
+  let worlds: String = row.get(index + 3)?;
+  let worlds = serde_json::from_str(&worlds).map_err(|e| {
+    rusqlite::Error::FromSqlConversionFailure(
+      index + 3,
+      rusqlite::types::Type::Text,
+      Box::new(e),
+    )
+  })?;
+
+  Ok(Some(BackupIntegrity {
+    size_in_bytes: row.get(index)?,
+    sha256,
+    file_count: row.get(index + 2)?,
+    worlds,
+  }))
+}
+
 #[async_trait]
 impl BackupRepository for SqliteBackupRepository {
   async fn add_backup_entry(
@@ -29,18 +99,41 @@ impl BackupRepository for SqliteBackupRepository {
     game_variant: &GameVariant,
     release_version: &str,
     timestamp: u64,
+    data_profile: Option<&str>,
+    scopes: &[BackupScope],
+    trigger: &BackupTrigger,
   ) -> Result<i64, BackupRepositoryError> {
     let pool = self.pool.clone();
     let game_variant = game_variant.to_string();
     let release_version = release_version.to_string();
+    let data_profile = data_profile.map(str::to_string);
+    let scopes = serde_json::to_string(scopes)
+      .map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+    let trigger = trigger.to_string();
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
-            let id = conn.query_row(
+            let mut conn = pool.get().map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            let tx = conn.transaction().map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            let id: i64 = tx.query_row(
                 "INSERT INTO backups (game_variant, release_version, timestamp) VALUES (?1, ?2, ?3) RETURNING id",
                 rusqlite::params![game_variant, release_version, timestamp],
                 |row| row.get(0),
             ).map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            if let Some(data_profile) = data_profile {
+                tx.execute(
+                    "INSERT INTO backup_data_profiles (backup_id, profile_name) VALUES (?1, ?2)",
+                    rusqlite::params![id, data_profile],
+                ).map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            }
+            tx.execute(
+                "INSERT INTO backup_scopes (backup_id, scopes) VALUES (?1, ?2)",
+                rusqlite::params![id, scopes],
+            ).map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            tx.execute(
+                "INSERT INTO backup_triggers (backup_id, backup_trigger) VALUES (?1, ?2)",
+                rusqlite::params![id, trigger],
+            ).map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
+            tx.commit().map_err(|e| BackupRepositoryError::Add(Box::new(e)))?;
             Ok(id)
         })
         .await
@@ -55,30 +148,94 @@ impl BackupRepository for SqliteBackupRepository {
     let game_variant = game_variant.to_string();
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
-            let mut stmt = conn.prepare(
-                "SELECT id, game_variant, release_version, timestamp FROM backups WHERE game_variant = ?1 ORDER BY timestamp ASC",
-            ).map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
-            let backups = stmt
-                .query_map(rusqlite::params![game_variant], |row| {
-                    let id = row.get(0)?;
-                    let game_variant_str: String = row.get(1)?;
-                    let game_variant = GameVariant::from_str(&game_variant_str)
-                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
-                    Ok(BackupEntry {
-                        id,
-                        game_variant,
-                        release_version: row.get(2)?,
-                        timestamp: row.get(3)?,
-                    })
-                })
-                .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
-                .collect::<Result<Vec<BackupEntry>, _>>()
-                .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
-            Ok(backups)
+      let conn = pool
+        .get()
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!(
+          "{} WHERE b.game_variant = ?1 ORDER BY b.timestamp ASC",
+          SELECT_BACKUP
+        ))
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let backups = stmt
+        .query_map(rusqlite::params![game_variant], |row| {
+          let id = row.get(0)?;
+          let game_variant_str: String = row.get(1)?;
+          let game_variant = GameVariant::from_str(&game_variant_str)
+            .map_err(|e| {
+              rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+              )
+            })?;
+          Ok(BackupEntry {
+            id,
+            game_variant,
+            release_version: row.get(2)?,
+            timestamp: row.get(3)?,
+            data_profile: row.get(4)?,
+            scopes: get_scopes_column(row, 5)?,
+            is_pinned: row.get(6)?,
+            trigger: get_trigger_column(row, 7)?,
+            integrity: get_integrity_columns(row, 8)?,
+          })
         })
-        .await
         .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
+        .collect::<Result<Vec<BackupEntry>, _>>()
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      Ok(backups)
+    })
+    .await
+    .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn get_all_backups(
+    &self,
+  ) -> Result<Vec<BackupEntry>, BackupRepositoryError> {
+    let pool = self.pool.clone();
+
+    task::spawn_blocking(move || {
+      let conn = pool
+        .get()
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!(
+          "{} ORDER BY b.timestamp ASC",
+          SELECT_BACKUP
+        ))
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let backups = stmt
+        .query_map([], |row| {
+          let id = row.get(0)?;
+          let game_variant_str: String = row.get(1)?;
+          let game_variant = GameVariant::from_str(&game_variant_str)
+            .map_err(|e| {
+              rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+              )
+            })?;
+          Ok(BackupEntry {
+            id,
+            game_variant,
+            release_version: row.get(2)?,
+            timestamp: row.get(3)?,
+            data_profile: row.get(4)?,
+            scopes: get_scopes_column(row, 5)?,
+            is_pinned: row.get(6)?,
+            trigger: get_trigger_column(row, 7)?,
+            integrity: get_integrity_columns(row, 8)?,
+          })
+        })
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
+        .collect::<Result<Vec<BackupEntry>, _>>()
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      Ok(backups)
+    })
+    .await
+    .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
   }
 
   async fn get_backup_entry(
@@ -88,32 +245,114 @@ impl BackupRepository for SqliteBackupRepository {
     let pool = self.pool.clone();
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
-            let mut stmt = conn.prepare(
-                "SELECT id, game_variant, release_version, timestamp FROM backups WHERE id = ?1",
-            ).map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
-            let backup = stmt
-                .query_row(rusqlite::params![id], |row| {
-                    let id = row.get(0)?;
-                    let game_variant_str: String = row.get(1)?;
-                    let game_variant = GameVariant::from_str(&game_variant_str)
-                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
-                    Ok(BackupEntry {
-                        id,
-                        game_variant,
-                        release_version: row.get(2)?,
-                        timestamp: row.get(3)?,
-                    })
-                });
-
-            match backup {
-                Ok(backup) => Ok(backup),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Err(BackupRepositoryError::NotFound(id)),
-                Err(e) => Err(BackupRepositoryError::Get(Box::new(e))),
-            }
+      let conn = pool
+        .get()
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!("{} WHERE b.id = ?1", SELECT_BACKUP))
+        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?;
+      let backup = stmt.query_row(rusqlite::params![id], |row| {
+        let id = row.get(0)?;
+        let game_variant_str: String = row.get(1)?;
+        let game_variant = GameVariant::from_str(&game_variant_str)
+          .map_err(|e| {
+          rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            Box::new(e),
+          )
+        })?;
+        Ok(BackupEntry {
+          id,
+          game_variant,
+          release_version: row.get(2)?,
+          timestamp: row.get(3)?,
+          data_profile: row.get(4)?,
+          scopes: get_scopes_column(row, 5)?,
+          is_pinned: row.get(6)?,
+          trigger: get_trigger_column(row, 7)?,
+          integrity: get_integrity_columns(row, 8)?,
         })
-        .await
-        .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
+      });
+
+      match backup {
+        Ok(backup) => Ok(backup),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+          Err(BackupRepositoryError::NotFound(id))
+        }
+        Err(e) => Err(BackupRepositoryError::Get(Box::new(e))),
+      }
+    })
+    .await
+    .map_err(|e| BackupRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn set_backup_integrity(
+    &self,
+    id: i64,
+    integrity: &BackupIntegrity,
+  ) -> Result<(), BackupRepositoryError> {
+    let pool = self.pool.clone();
+    let integrity = integrity.clone();
+    let worlds = serde_json::to_string(&integrity.worlds)
+      .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+
+    task::spawn_blocking(move || {
+      let conn = pool
+        .get()
+        .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+      conn
+        .execute(
+          "INSERT OR REPLACE INTO backup_integrity (backup_id, size_in_bytes, sha256, file_count, worlds) VALUES (?1, ?2, ?3, ?4, ?5)",
+          rusqlite::params![
+            id,
+            integrity.size_in_bytes,
+            integrity.sha256,
+            integrity.file_count,
+            worlds
+          ],
+        )
+        .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+      Ok(())
+    })
+    .await
+    .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?
+  }
+
+  async fn set_backup_pinned(
+    &self,
+    id: i64,
+    is_pinned: bool,
+  ) -> Result<(), BackupRepositoryError> {
+    let pool = self.pool.clone();
+
+    task::spawn_blocking(move || {
+      let conn = pool
+        .get()
+        .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+      let exists: bool = conn
+        .query_row(
+          "SELECT EXISTS (SELECT 1 FROM backups WHERE id = ?1)",
+          rusqlite::params![id],
+          |row| row.get(0),
+        )
+        .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+      if !exists {
+        return Err(BackupRepositoryError::NotFound(id));
+      }
+
+      let sql = if is_pinned {
+        "INSERT OR IGNORE INTO pinned_backups (backup_id) VALUES (?1)"
+      } else {
+        "DELETE FROM pinned_backups WHERE backup_id = ?1"
+      };
+      conn
+        .execute(sql, rusqlite::params![id])
+        .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?;
+      Ok(())
+    })
+    .await
+    .map_err(|e| BackupRepositoryError::Update(Box::new(e)))?
   }
 
   async fn delete_backup_entry(
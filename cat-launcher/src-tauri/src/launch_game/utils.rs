@@ -1,45 +1,127 @@
 use std::path::Path;
 
+use crate::backups::integrity::{
+  compute_snapshot_integrity, BackupIntegrityError,
+};
+use crate::backups::scopes::{get_scope_dirs, BackupScope};
+use crate::backups::store::{
+  lock_store, store_snapshot, BackupStoreError, StoreGuard,
+};
+use crate::backups::triggers::BackupTrigger;
 use crate::filesystem::paths::{
-  get_or_create_automatic_backup_archive_filepath,
-  get_or_create_user_game_data_dir,
-  GetAutomaticBackupArchivePathError, GetUserGameDataDirError,
+  get_automatic_backup_manifest_filepath, get_backup_chunks_dir,
+  get_or_create_user_game_data_dir, GetUserGameDataDirError,
 };
-use crate::infra::archive::{
-  create_zip_archive, ArchiveCreationError,
+use crate::launch_game::repository::{
+  BackupRepository, BackupRepositoryError,
 };
 use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug)]
 pub enum BackupError {
-  #[error("failed to get backup archive path: {0}")]
-  BackupArchivePath(#[from] GetAutomaticBackupArchivePathError),
-
-  #[error("failed to create archive: {0}")]
-  ArchiveCreation(#[from] ArchiveCreationError),
+  #[error("failed to store backup: {0}")]
+  Store(#[from] BackupStoreError),
 
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
+
+  #[error("failed to access backup repository: {0}")]
+  Repository(#[from] BackupRepositoryError),
+
+  #[error("failed to record backup integrity: {0}")]
+  Integrity(#[from] BackupIntegrityError),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn backup_save_files(
   variant: &GameVariant,
+  data_profile: Option<&str>,
   id: i64,
   version: &str,
   timestamp: u64,
+  scopes: &[BackupScope],
   data_dir: &Path,
+  guard: &StoreGuard,
 ) -> Result<(), BackupError> {
   let user_data_dir =
-    get_or_create_user_game_data_dir(variant, data_dir).await?;
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
 
-  let dirs_to_backup = vec![user_data_dir.join("save")];
-  let archive_path = get_or_create_automatic_backup_archive_filepath(
+  let dirs_to_backup = get_scope_dirs(&user_data_dir, scopes);
+  let manifest_path = get_automatic_backup_manifest_filepath(
     variant, id, version, timestamp, data_dir,
+  );
+
+  store_snapshot(
+    &user_data_dir,
+    &dirs_to_backup,
+    &get_backup_chunks_dir(data_dir),
+    &manifest_path,
+    guard,
   )
   .await?;
 
-  create_zip_archive(&user_data_dir, &dirs_to_backup, &archive_path)
+  Ok(())
+}
+
+/// Records an automatic backup, stores its files and records what they
+/// contain. The entry is removed again if any of this fails.
+#[allow(clippy::too_many_arguments)]
+pub async fn take_automatic_backup(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  version: &str,
+  timestamp: u64,
+  scopes: &[BackupScope],
+  trigger: &BackupTrigger,
+  data_dir: &Path,
+  backup_repository: &dyn BackupRepository,
+) -> Result<i64, BackupError> {
+  // Held until the snapshot is written, as garbage collection would
+  // otherwise treat the new entry's chunks as unused.
+  let guard = lock_store().await;
+
+  let backup_id = backup_repository
+    .add_backup_entry(
+      variant,
+      version,
+      timestamp,
+      data_profile,
+      scopes,
+      trigger,
+    )
     .await?;
 
-  Ok(())
+  let manifest_path = get_automatic_backup_manifest_filepath(
+    variant, backup_id, version, timestamp, data_dir,
+  );
+  let result = async {
+    backup_save_files(
+      variant,
+      data_profile,
+      backup_id,
+      version,
+      timestamp,
+      scopes,
+      data_dir,
+      &guard,
+    )
+    .await?;
+
+    let integrity =
+      compute_snapshot_integrity(&manifest_path).await?;
+    backup_repository
+      .set_backup_integrity(backup_id, &integrity)
+      .await?;
+    Ok::<(), BackupError>(())
+  }
+  .await;
+
+  if let Err(e) = result {
+    let _ = backup_repository.delete_backup_entry(backup_id).await;
+    let _ = tokio::fs::remove_file(&manifest_path).await;
+    return Err(e);
+  }
+
+  Ok(backup_id)
 }
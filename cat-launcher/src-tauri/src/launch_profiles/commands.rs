@@ -0,0 +1,66 @@
+use strum::IntoStaticStr;
+use tauri::{command, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::launch_profiles::launch_profiles::{
+  create_launch_profile as create_launch_profile_impl,
+  delete_launch_profile as delete_launch_profile_impl,
+  list_launch_profiles as list_launch_profiles_impl,
+  update_launch_profile as update_launch_profile_impl,
+  LaunchProfileError,
+};
+use crate::launch_profiles::repository::sqlite_launch_profiles_repository::SqliteLaunchProfilesRepository;
+use crate::launch_profiles::repository::{
+  LaunchProfile, LaunchProfileOptions,
+};
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum LaunchProfileCommandError {
+  #[error("launch profile operation failed: {0}")]
+  LaunchProfile(#[from] LaunchProfileError),
+}
+
+#[command]
+pub async fn list_launch_profiles(
+  variant: GameVariant,
+  repository: State<'_, SqliteLaunchProfilesRepository>,
+) -> Result<Vec<LaunchProfile>, LaunchProfileCommandError> {
+  let profiles =
+    list_launch_profiles_impl(&variant, &*repository).await?;
+  Ok(profiles)
+}
+
+#[command]
+pub async fn create_launch_profile(
+  variant: GameVariant,
+  options: LaunchProfileOptions,
+  repository: State<'_, SqliteLaunchProfilesRepository>,
+) -> Result<i64, LaunchProfileCommandError> {
+  let id =
+    create_launch_profile_impl(&variant, &options, &*repository)
+      .await?;
+  Ok(id)
+}
+
+#[command]
+pub async fn update_launch_profile(
+  id: i64,
+  options: LaunchProfileOptions,
+  repository: State<'_, SqliteLaunchProfilesRepository>,
+) -> Result<(), LaunchProfileCommandError> {
+  update_launch_profile_impl(id, &options, &*repository).await?;
+  Ok(())
+}
+
+#[command]
+pub async fn delete_launch_profile(
+  id: i64,
+  repository: State<'_, SqliteLaunchProfilesRepository>,
+) -> Result<(), LaunchProfileCommandError> {
+  delete_launch_profile_impl(id, &*repository).await?;
+  Ok(())
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use crate::launch_profiles::repository::{
+  LaunchProfile, LaunchProfileOptions, LaunchProfilesRepository,
+  LaunchProfilesRepositoryError,
+};
+use crate::variants::GameVariant;
+
+#[derive(thiserror::Error, Debug)]
+pub enum InvalidLaunchProfileError {
+  #[error("launch profile name must not be empty")]
+  EmptyName,
+
+  #[error("invalid environment variable name: {0:?}")]
+  EnvVarName(String),
+
+  #[error("wrapper command must start with a program")]
+  EmptyWrapperProgram,
+
+  #[error("working directory must be an absolute path: {0}")]
+  RelativeWorkingDir(String),
+}
+
+fn validate_launch_profile(
+  options: &LaunchProfileOptions,
+) -> Result<(), InvalidLaunchProfileError> {
+  if options.name.trim().is_empty() {
+    return Err(InvalidLaunchProfileError::EmptyName);
+  }
+
+  if let Some(key) = options
+    .env_vars
+    .keys()
+    .find(|key| key.is_empty() || key.contains(['=', '\0']))
+  {
+    return Err(InvalidLaunchProfileError::EnvVarName(key.clone()));
+  }
+
+  if options
+    .wrapper_command
+    .first()
+    .is_some_and(|program| program.trim().is_empty())
+  {
+    return Err(InvalidLaunchProfileError::EmptyWrapperProgram);
+  }
+
+  if let Some(working_dir) = &options.working_dir {
+    if !Path::new(working_dir).is_absolute() {
+      return Err(InvalidLaunchProfileError::RelativeWorkingDir(
+        working_dir.clone(),
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LaunchProfileError {
+  #[error("invalid launch profile: {0}")]
+  Invalid(#[from] InvalidLaunchProfileError),
+
+  #[error("launch profile {0} does not belong to {1}")]
+  VariantMismatch(i64, String),
+
+  #[error("failed to access launch profiles: {0}")]
+  Repository(#[from] LaunchProfilesRepositoryError),
+}
+
+pub async fn list_launch_profiles(
+  variant: &GameVariant,
+  repository: &dyn LaunchProfilesRepository,
+) -> Result<Vec<LaunchProfile>, LaunchProfileError> {
+  Ok(repository.get_launch_profiles(variant).await?)
+}
+
+pub async fn get_launch_profile(
+  id: i64,
+  variant: &GameVariant,
+  repository: &dyn LaunchProfilesRepository,
+) -> Result<LaunchProfile, LaunchProfileError> {
+  let profile = repository.get_launch_profile(id).await?;
+
+  if profile.game_variant != *variant {
+    return Err(LaunchProfileError::VariantMismatch(
+      id,
+      variant.to_string(),
+    ));
+  }
+
+  Ok(profile)
+}
+
+pub async fn create_launch_profile(
+  variant: &GameVariant,
+  options: &LaunchProfileOptions,
+  repository: &dyn LaunchProfilesRepository,
+) -> Result<i64, LaunchProfileError> {
+  validate_launch_profile(options)?;
+  Ok(repository.add_launch_profile(variant, options).await?)
+}
+
+pub async fn update_launch_profile(
+  id: i64,
+  options: &LaunchProfileOptions,
+  repository: &dyn LaunchProfilesRepository,
+) -> Result<(), LaunchProfileError> {
+  validate_launch_profile(options)?;
+  repository.update_launch_profile(id, options).await?;
+  Ok(())
+}
+
+pub async fn delete_launch_profile(
+  id: i64,
+  repository: &dyn LaunchProfilesRepository,
+) -> Result<(), LaunchProfileError> {
+  repository.delete_launch_profile(id).await?;
+  Ok(())
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod launch_profiles;
+
+pub mod commands;
+pub mod repository;
@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct LaunchProfile {
+  pub id: i64,
+  pub game_variant: GameVariant,
+  #[serde(flatten)]
+  pub options: LaunchProfileOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LaunchProfileOptions {
+  pub name: String,
+  pub extra_args: Vec<String>,
+  pub env_vars: BTreeMap<String, String>,
+  pub wrapper_command: Vec<String>,
+  pub working_dir: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LaunchProfilesRepositoryError {
+  #[error("failed to add launch profile: {0}")]
+  Add(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get launch profiles: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to update launch profile: {0}")]
+  Update(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete launch profile: {0}")]
+  Delete(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("launch profile with id {0} not found")]
+  NotFound(i64),
+}
+
+#[async_trait]
+pub trait LaunchProfilesRepository: Send + Sync {
+  async fn add_launch_profile(
+    &self,
+    game_variant: &GameVariant,
+    options: &LaunchProfileOptions,
+  ) -> Result<i64, LaunchProfilesRepositoryError>;
+
+  async fn get_launch_profiles(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<LaunchProfile>, LaunchProfilesRepositoryError>;
+
+  async fn get_launch_profile(
+    &self,
+    id: i64,
+  ) -> Result<LaunchProfile, LaunchProfilesRepositoryError>;
+
+  async fn update_launch_profile(
+    &self,
+    id: i64,
+    options: &LaunchProfileOptions,
+  ) -> Result<(), LaunchProfilesRepositoryError>;
+
+  async fn delete_launch_profile(
+    &self,
+    id: i64,
+  ) -> Result<(), LaunchProfilesRepositoryError>;
+}
@@ -0,0 +1,7 @@
+pub mod launch_profiles_repository;
+pub mod sqlite_launch_profiles_repository;
+
+pub use launch_profiles_repository::{
+  LaunchProfile, LaunchProfileOptions, LaunchProfilesRepository,
+  LaunchProfilesRepositoryError,
+};
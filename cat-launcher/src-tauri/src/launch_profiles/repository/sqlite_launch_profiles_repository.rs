@@ -0,0 +1,218 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::Row;
+use serde::de::DeserializeOwned;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::launch_profiles::repository::{
+  LaunchProfile, LaunchProfileOptions, LaunchProfilesRepository,
+  LaunchProfilesRepositoryError,
+};
+use crate::variants::GameVariant;
+
+const SELECT_LAUNCH_PROFILE: &str = "SELECT id, game_variant, name, extra_args, env_vars, wrapper_command, working_dir FROM launch_profiles";
+
+pub struct SqliteLaunchProfilesRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteLaunchProfilesRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+struct SerializedOptions {
+  name: String,
+  extra_args: String,
+  env_vars: String,
+  wrapper_command: String,
+  working_dir: Option<String>,
+}
+
+fn serialize_options(
+  options: &LaunchProfileOptions,
+) -> Result<SerializedOptions, serde_json::Error> {
+  Ok(SerializedOptions {
+    name: options.name.clone(),
+    extra_args: serde_json::to_string(&options.extra_args)?,
+    env_vars: serde_json::to_string(&options.env_vars)?,
+    wrapper_command: serde_json::to_string(&options.wrapper_command)?,
+    working_dir: options.working_dir.clone(),
+  })
+}
+
+fn get_json_column<T: DeserializeOwned>(
+  row: &Row,
+  index: usize,
+) -> rusqlite::Result<T> {
+  let value: String = row.get(index)?;
+  serde_json::from_str(&value).map_err(|e| {
+    rusqlite::Error::FromSqlConversionFailure(
+      index,
+      Type::Text,
+      Box::new(e),
+    )
+  })
+}
+
+fn row_to_launch_profile(
+  row: &Row,
+) -> rusqlite::Result<LaunchProfile> {
+  let game_variant_str: String = row.get(1)?;
+  let game_variant = GameVariant::from_str(&game_variant_str)
+    .map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        1,
+        Type::Text,
+        Box::new(e),
+      )
+    })?;
+
+  Ok(LaunchProfile {
+    id: row.get(0)?,
+    game_variant,
+    options: LaunchProfileOptions {
+      name: row.get(2)?,
+      extra_args: get_json_column(row, 3)?,
+      env_vars: get_json_column(row, 4)?,
+      wrapper_command: get_json_column(row, 5)?,
+      working_dir: row.get(6)?,
+    },
+  })
+}
+
+#[async_trait]
+impl LaunchProfilesRepository for SqliteLaunchProfilesRepository {
+  async fn add_launch_profile(
+    &self,
+    game_variant: &GameVariant,
+    options: &LaunchProfileOptions,
+  ) -> Result<i64, LaunchProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let options = serialize_options(options)
+      .map_err(|e| LaunchProfilesRepositoryError::Add(Box::new(e)))?;
+
+    run_db(pool, move |conn| {
+      conn.query_row(
+        "INSERT INTO launch_profiles (game_variant, name, extra_args, env_vars, wrapper_command, working_dir)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+        rusqlite::params![
+          variant_name,
+          options.name,
+          options.extra_args,
+          options.env_vars,
+          options.wrapper_command,
+          options.working_dir,
+        ],
+        |row| row.get::<_, i64>(0),
+      )
+    })
+    .await
+    .map_err(LaunchProfilesRepositoryError::Add)
+  }
+
+  async fn get_launch_profiles(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<LaunchProfile>, LaunchProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(&format!(
+        "{} WHERE game_variant = ?1 ORDER BY name ASC",
+        SELECT_LAUNCH_PROFILE
+      ))?;
+      let profiles = stmt
+        .query_map([&variant_name], row_to_launch_profile)?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<LaunchProfile>, rusqlite::Error>(profiles)
+    })
+    .await
+    .map_err(LaunchProfilesRepositoryError::Get)
+  }
+
+  async fn get_launch_profile(
+    &self,
+    id: i64,
+  ) -> Result<LaunchProfile, LaunchProfilesRepositoryError> {
+    let pool = self.pool.clone();
+
+    let profile = run_db(pool, move |conn| {
+      let result = conn.query_row(
+        &format!("{} WHERE id = ?1", SELECT_LAUNCH_PROFILE),
+        [id],
+        row_to_launch_profile,
+      );
+
+      match result {
+        Ok(profile) => Ok(Some(profile)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+      }
+    })
+    .await
+    .map_err(LaunchProfilesRepositoryError::Get)?;
+
+    profile.ok_or(LaunchProfilesRepositoryError::NotFound(id))
+  }
+
+  async fn update_launch_profile(
+    &self,
+    id: i64,
+    options: &LaunchProfileOptions,
+  ) -> Result<(), LaunchProfilesRepositoryError> {
+    let pool = self.pool.clone();
+    let options = serialize_options(options).map_err(|e| {
+      LaunchProfilesRepositoryError::Update(Box::new(e))
+    })?;
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute(
+        "UPDATE launch_profiles
+         SET name = ?2, extra_args = ?3, env_vars = ?4, wrapper_command = ?5, working_dir = ?6
+         WHERE id = ?1",
+        rusqlite::params![
+          id,
+          options.name,
+          options.extra_args,
+          options.env_vars,
+          options.wrapper_command,
+          options.working_dir,
+        ],
+      )
+    })
+    .await
+    .map_err(LaunchProfilesRepositoryError::Update)?;
+
+    if rows_affected == 0 {
+      return Err(LaunchProfilesRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+
+  async fn delete_launch_profile(
+    &self,
+    id: i64,
+  ) -> Result<(), LaunchProfilesRepositoryError> {
+    let pool = self.pool.clone();
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute("DELETE FROM launch_profiles WHERE id = ?1", [id])
+    })
+    .await
+    .map_err(LaunchProfilesRepositoryError::Delete)?;
+
+    if rows_affected == 0 {
+      return Err(LaunchProfilesRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+}
@@ -3,28 +3,58 @@ pub mod filesystem;
 pub mod settings;
 
 pub mod active_release;
+mod backup_settings;
+mod backup_targets;
 mod backups;
+mod crash_reports;
+mod data_profiles;
 mod fetch_releases;
 mod game_release;
 mod game_tips;
 mod infra;
 mod install_release;
+mod installed_releases;
 mod last_played_world;
 mod launch_game;
+mod launch_profiles;
 mod manual_backups;
 mod master_reset;
 mod mods;
 mod play_time;
+mod running_games;
+mod save_sync;
+mod session_logs;
 mod soundpacks;
 mod theme;
 mod tilesets;
 mod users;
 mod utils;
 mod variants;
+mod world_versions;
+mod worlds;
 
 use crate::active_release::commands::get_active_release;
+use crate::backup_settings::commands::{
+  get_backup_retention_policy, get_backup_scopes,
+  get_backup_trigger_settings, set_backup_retention_policy,
+  set_backup_scopes, set_backup_trigger_settings,
+};
+use crate::backup_targets::commands::{
+  get_backup_target, list_backup_uploads, list_remote_backups,
+  restore_remote_backup, retry_backup_uploads, set_backup_target,
+};
 use crate::backups::commands::{
-  delete_backup_by_id, list_backups_for_variant, restore_backup_by_id,
+  delete_backup_by_id, get_last_restore, list_backup_worlds_by_id,
+  list_backups_for_variant, restore_backup_by_id,
+  restore_backup_selection_by_id, set_backup_pinned,
+  undo_last_restore, verify_all_backups, verify_backup,
+};
+use crate::crash_reports::commands::{
+  delete_crash_report, export_crash_report, list_crash_reports,
+};
+use crate::data_profiles::commands::{
+  create_data_profile, delete_data_profile, get_active_data_profile,
+  list_data_profiles, set_active_data_profile,
 };
 use crate::fetch_releases::commands::{
   fetch_release_notes, fetch_releases_for_variant,
@@ -32,21 +62,46 @@ use crate::fetch_releases::commands::{
 use crate::game_tips::commands::get_tips;
 use crate::install_release::commands::install_release;
 use crate::install_release::installation_status::commands::get_installation_status;
+use crate::installed_releases::commands::{
+  get_release_retention_policy, list_installed_releases,
+  set_installed_release_pinned, set_release_retention_policy,
+  uninstall_release,
+};
 use crate::last_played_world::commands::get_last_played_world;
 use crate::launch_game::commands::launch_game;
+use crate::launch_profiles::commands::{
+  create_launch_profile, delete_launch_profile, list_launch_profiles,
+  update_launch_profile,
+};
 use crate::manual_backups::commands::{
   create_manual_backup_for_variant, delete_manual_backup_by_id,
-  list_manual_backups_for_variant, restore_manual_backup_by_id,
+  export_manual_backup, import_backup,
+  list_manual_backup_worlds_by_id, list_manual_backups_for_variant,
+  restore_manual_backup_by_id, restore_manual_backup_selection_by_id,
 };
 use crate::master_reset::commands::master_reset;
 use crate::mods::commands::{
+  check_mod_updates_command,
   get_last_activity_on_third_party_mod_command,
   get_third_party_mod_installation_status_command,
   install_third_party_mod_command, list_all_mods_command,
-  uninstall_third_party_mod_command,
+  uninstall_third_party_mod_command, update_third_party_mod_command,
 };
 use crate::play_time::commands::{
-  get_play_time_for_variant, get_play_time_for_version, log_play_time,
+  get_play_time_for_variant, get_play_time_for_version,
+  list_play_sessions, log_play_time,
+};
+use crate::running_games::commands::{
+  list_running_games, terminate_game,
+};
+use crate::save_sync::commands::{
+  get_save_sync_remote, resolve_save_sync_conflict,
+  set_save_sync_remote, sync_saves,
+};
+use crate::session_logs::commands::{
+  delete_session_log, get_session_log_retention_policy,
+  list_session_logs, read_session_log,
+  set_session_log_retention_policy,
 };
 use crate::settings::commands::{
   get_color_themes, get_default_settings, get_fonts, get_settings,
@@ -67,13 +122,19 @@ use crate::tilesets::commands::{
 };
 use crate::users::commands::get_user_id;
 use crate::utils::{
-  autoupdate, manage_downloader, manage_http_client,
-  manage_online_mod_repository_registry, manage_posthog,
-  manage_repositories, manage_settings, migrate_to_local_data_dir,
-  on_quit,
+  autoupdate, manage_backup_mirror, manage_downloader,
+  manage_http_client, manage_online_mod_repository_registry,
+  manage_posthog, manage_repositories, manage_running_games,
+  manage_settings, migrate_to_local_data_dir, on_quit,
 };
 use crate::variants::commands::get_game_variants_info;
 use crate::variants::commands::update_game_variant_order;
+use crate::world_versions::commands::get_world_version;
+use crate::worlds::commands::{
+  delete_world, duplicate_world, get_world_details,
+  list_deleted_worlds, list_worlds, purge_deleted_world,
+  rename_world, restore_deleted_world,
+};
 use tauri::{command, AppHandle};
 
 #[command]
@@ -94,6 +155,8 @@ pub fn run() {
       manage_settings(app)?;
       manage_online_mod_repository_registry(app);
       manage_downloader(app);
+      manage_running_games(app);
+      manage_backup_mirror(app);
       manage_posthog(app);
 
       migrate_to_local_data_dir(app);
@@ -108,24 +171,87 @@ pub fn run() {
       fetch_releases_for_variant,
       fetch_release_notes,
       install_release,
+      list_installed_releases,
+      uninstall_release,
+      set_installed_release_pinned,
+      get_release_retention_policy,
+      set_release_retention_policy,
       launch_game,
+      list_running_games,
+      terminate_game,
+      list_crash_reports,
+      export_crash_report,
+      delete_crash_report,
+      list_session_logs,
+      read_session_log,
+      delete_session_log,
+      get_session_log_retention_policy,
+      set_session_log_retention_policy,
+      list_launch_profiles,
+      create_launch_profile,
+      update_launch_profile,
+      delete_launch_profile,
+      list_data_profiles,
+      create_data_profile,
+      delete_data_profile,
+      get_active_data_profile,
+      set_active_data_profile,
       get_active_release,
       get_installation_status,
       get_tips,
       get_play_time_for_variant,
       get_play_time_for_version,
       log_play_time,
+      list_play_sessions,
       update_game_variant_order,
       list_backups_for_variant,
       delete_backup_by_id,
       restore_backup_by_id,
+      list_backup_worlds_by_id,
+      restore_backup_selection_by_id,
+      set_backup_pinned,
+      get_last_restore,
+      undo_last_restore,
+      verify_backup,
+      verify_all_backups,
+      get_backup_scopes,
+      set_backup_scopes,
+      get_backup_retention_policy,
+      set_backup_retention_policy,
+      get_backup_trigger_settings,
+      set_backup_trigger_settings,
       list_manual_backups_for_variant,
       create_manual_backup_for_variant,
       delete_manual_backup_by_id,
       restore_manual_backup_by_id,
+      list_manual_backup_worlds_by_id,
+      restore_manual_backup_selection_by_id,
+      export_manual_backup,
+      import_backup,
+      get_backup_target,
+      set_backup_target,
+      list_backup_uploads,
+      retry_backup_uploads,
+      list_remote_backups,
+      restore_remote_backup,
+      get_save_sync_remote,
+      set_save_sync_remote,
+      sync_saves,
+      resolve_save_sync_conflict,
+      list_worlds,
+      get_world_details,
+      duplicate_world,
+      rename_world,
+      delete_world,
+      list_deleted_worlds,
+      restore_deleted_world,
+      purge_deleted_world,
+      get_world_version,
       list_all_mods_command,
       install_third_party_mod_command,
       uninstall_third_party_mod_command,
+      check_mod_updates_command,
+      update_third_party_mod_command,
       get_third_party_mod_installation_status_command,
       get_last_activity_on_third_party_mod_command,
       list_all_tilesets_command,
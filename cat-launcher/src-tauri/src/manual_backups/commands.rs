@@ -1,17 +1,32 @@
 use serde::ser::SerializeStruct;
 use serde::Serialize;
+use std::path::PathBuf;
 use std::time::SystemTimeError;
 use strum::IntoStaticStr;
 use tauri::{Manager, State};
 
+use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::backup_settings::repository::sqlite_backup_settings_repository::SqliteBackupSettingsRepository;
+use crate::backup_targets::mirror::BackupMirror;
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::backups::contents::{BackupWorld, RestoreSelection};
 use crate::manual_backups::manual_backups::{
-    create_manual_backup, delete_manual_backup, list_manual_backups, restore_manual_backup,
-    CreateManualBackupError, DeleteManualBackupError, ListManualBackupsError,
+    create_manual_backup, delete_manual_backup, list_manual_backup_worlds, list_manual_backups,
+    restore_manual_backup, restore_manual_backup_selection, CreateManualBackupError,
+    DeleteManualBackupError, ListManualBackupWorldsError, ListManualBackupsError,
     RestoreManualBackupError,
 };
+use crate::manual_backups::portable::{
+    export_manual_backup as export_manual_backup_impl, import_backup as import_backup_impl,
+    ExportManualBackupError, ImportBackupError,
+};
 use crate::manual_backups::repository::manual_backup_repository::ManualBackupEntry;
 use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
 use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug, IntoStaticStr)]
@@ -54,6 +69,8 @@ pub enum CreateManualBackupCommandError {
   UnsupportedOS(#[from] OSNotSupportedError),
   #[error("failed to get system time: {0}")]
   SystemTime(#[from] SystemTimeError),
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 impl Serialize for CreateManualBackupCommandError {
@@ -77,20 +94,34 @@ pub async fn create_manual_backup_for_variant(
   notes: Option<String>,
   app_handle: tauri::AppHandle,
   backup_repository: State<'_, SqliteManualBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  backup_settings_repository: State<
+    '_,
+    SqliteBackupSettingsRepository,
+  >,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  backup_mirror: State<'_, BackupMirror>,
 ) -> Result<i64, CreateManualBackupCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let timestamp = std::time::SystemTime::now()
     .duration_since(std::time::UNIX_EPOCH)?
     .as_secs();
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
   let id = create_manual_backup(
     &name,
     &variant,
+    data_profile.as_deref(),
     notes,
     &data_dir,
     timestamp,
     backup_repository.inner(),
+    &*backup_settings_repository,
+    &*active_release_repository,
   )
   .await?;
+  backup_mirror.request_sync();
   Ok(id)
 }
 
@@ -136,6 +167,8 @@ pub enum RestoreManualBackupCommandError {
   DataDir(#[from] tauri::Error),
   #[error("unsupported OS: {0}")]
   UnsupportedOS(#[from] OSNotSupportedError),
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
 }
 
 impl Serialize for RestoreManualBackupCommandError {
@@ -157,15 +190,151 @@ pub async fn restore_manual_backup_by_id(
   id: i64,
   app_handle: tauri::AppHandle,
   backup_repository: State<'_, SqliteManualBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
 ) -> Result<(), RestoreManualBackupCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
   let os = get_os_enum(std::env::consts::OS)?;
+  let timestamp = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)?
+    .as_secs();
   restore_manual_backup(
     id,
+    timestamp,
     &data_dir,
     backup_repository.inner(),
+    &*data_profiles_repository,
+    &running_games,
     &os,
   )
   .await?;
   Ok(())
 }
+
+#[tauri::command]
+pub async fn restore_manual_backup_selection_by_id(
+  id: i64,
+  selection: RestoreSelection,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteManualBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), RestoreManualBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+  let timestamp = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)?
+    .as_secs();
+  restore_manual_backup_selection(
+    id,
+    &selection,
+    timestamp,
+    &data_dir,
+    backup_repository.inner(),
+    &*data_profiles_repository,
+    &running_games,
+    &os,
+  )
+  .await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug, IntoStaticStr)]
+pub enum ListManualBackupWorldsCommandError {
+  #[error("failed to list backup worlds: {0}")]
+  List(#[from] ListManualBackupWorldsError),
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+}
+
+impl Serialize for ListManualBackupWorldsCommandError {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut state = serializer
+      .serialize_struct("ListManualBackupWorldsCommandError", 2)?;
+    let error_type: &str = self.into();
+    state.serialize_field("type", error_type)?;
+    state.serialize_field("message", &self.to_string())?;
+    state.end()
+  }
+}
+
+#[tauri::command]
+pub async fn list_manual_backup_worlds_by_id(
+  id: i64,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteManualBackupRepository>,
+) -> Result<Vec<BackupWorld>, ListManualBackupWorldsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let worlds = list_manual_backup_worlds(
+    id,
+    &data_dir,
+    backup_repository.inner(),
+  )
+  .await?;
+  Ok(worlds)
+}
+
+#[derive(thiserror::Error, Debug, IntoStaticStr)]
+pub enum PortableBackupCommandError {
+  #[error("failed to export backup: {0}")]
+  Export(#[from] ExportManualBackupError),
+  #[error("failed to import backup: {0}")]
+  Import(#[from] ImportBackupError),
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+}
+
+impl Serialize for PortableBackupCommandError {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let mut state =
+      serializer.serialize_struct("PortableBackupCommandError", 2)?;
+    let error_type: &str = self.into();
+    state.serialize_field("type", error_type)?;
+    state.serialize_field("message", &self.to_string())?;
+    state.end()
+  }
+}
+
+#[tauri::command]
+pub async fn export_manual_backup(
+  id: i64,
+  destination: PathBuf,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteManualBackupRepository>,
+) -> Result<(), PortableBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  export_manual_backup_impl(
+    id,
+    &destination,
+    &data_dir,
+    backup_repository.inner(),
+  )
+  .await?;
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn import_backup(
+  source: PathBuf,
+  app_handle: tauri::AppHandle,
+  backup_repository: State<'_, SqliteManualBackupRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  backup_mirror: State<'_, BackupMirror>,
+) -> Result<i64, PortableBackupCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let id = import_backup_impl(
+    &source,
+    &data_dir,
+    backup_repository.inner(),
+    &*data_profiles_repository,
+  )
+  .await?;
+  backup_mirror.request_sync();
+  Ok(id)
+}
@@ -1,19 +1,43 @@
 use std::path::{Path, PathBuf};
 
+use crate::active_release::repository::{
+  ActiveReleaseRepository, ActiveReleaseRepositoryError,
+};
+use crate::backup_settings::repository::{
+  BackupKind, BackupSettingsRepository, BackupSettingsRepositoryError,
+};
+use crate::backups::contents::{
+  list_worlds, prepare_selection_staging, BackupWorld,
+  RestoreSelection, RestoreSelectionError,
+};
+use crate::backups::integrity::{
+  compute_archive_integrity, verify_archive_files,
+  BackupIntegrityError, BackupVerification,
+};
+use crate::backups::restore::{
+  commit_restore, create_restore_staging_dir,
+  discard_restore_staging_dir, RestoreSaveError,
+};
+use crate::backups::scopes::{get_scope_dirs, BackupScope};
+use crate::data_profiles::data_profiles::{
+  data_profile_exists, DataProfileError,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::filesystem::paths::{
   get_or_create_manual_backup_archive_filepath,
   get_or_create_user_game_data_dir, GetManualBackupArchivePathError,
   GetUserGameDataDirError,
 };
 use crate::infra::archive::{
-  create_zip_archive, extract_archive, ArchiveCreationError,
-  ExtractionError,
+  create_zip_archive, extract_archive, extract_zip_entries,
+  list_zip_entries, ArchiveCreationError, ExtractionError,
 };
 use crate::infra::utils::OS;
 use crate::manual_backups::repository::manual_backup_repository::{
   ManualBackupEntry, ManualBackupRepository,
   ManualBackupRepositoryError,
 };
+use crate::running_games::running_games::RunningGamesRegistry;
 use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug)]
@@ -37,42 +61,118 @@ pub enum CreateManualBackupError {
   #[error("failed to add backup entry: {0}")]
   Add(#[from] ManualBackupRepositoryError),
 
+  #[error("failed to get backup scopes: {0}")]
+  BackupSettings(#[from] BackupSettingsRepositoryError),
+
+  #[error("failed to get active release: {0}")]
+  ActiveRelease(#[from] ActiveReleaseRepositoryError),
+
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetManualBackupArchivePathError),
 
   #[error("failed to create archive: {0}")]
   ArchiveCreation(#[from] ArchiveCreationError),
 
+  #[error("failed to record backup integrity: {0}")]
+  Integrity(#[from] BackupIntegrityError),
+
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn create_manual_backup(
   name: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   notes: Option<String>,
   data_dir: &Path,
   timestamp: u64,
   backup_repository: &impl ManualBackupRepository,
+  backup_settings_repository: &dyn BackupSettingsRepository,
+  active_release_repository: &dyn ActiveReleaseRepository,
 ) -> Result<i64, CreateManualBackupError> {
+  let scopes = backup_settings_repository
+    .get_backup_scopes(game_variant, &BackupKind::Manual)
+    .await?;
+
+  create_manual_backup_with_scopes(
+    name,
+    game_variant,
+    data_profile,
+    notes,
+    data_dir,
+    timestamp,
+    &scopes,
+    backup_repository,
+    active_release_repository,
+  )
+  .await
+}
+
+/// Creates a manual backup of the given scopes, regardless of the scopes
+/// configured for manual backups. The active release is recorded as the
+/// release the backup was taken with.
+#[allow(clippy::too_many_arguments)]
+pub async fn create_manual_backup_with_scopes(
+  name: &str,
+  game_variant: &GameVariant,
+  data_profile: Option<&str>,
+  notes: Option<String>,
+  data_dir: &Path,
+  timestamp: u64,
+  scopes: &[BackupScope],
+  backup_repository: &impl ManualBackupRepository,
+  active_release_repository: &dyn ActiveReleaseRepository,
+) -> Result<i64, CreateManualBackupError> {
+  let release_version = active_release_repository
+    .get_active_release(game_variant)
+    .await?;
+
   let id = backup_repository
-    .add_manual_backup_entry(name, game_variant, timestamp, notes)
+    .add_manual_backup_entry(
+      name,
+      game_variant,
+      timestamp,
+      notes,
+      data_profile,
+      scopes,
+      release_version.as_deref(),
+    )
     .await?;
 
-  let user_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
 
-  let dirs_to_backup = vec![user_data_dir.join("save")];
+  let dirs_to_backup = get_scope_dirs(&user_data_dir, scopes);
   let archive_path: PathBuf =
     get_or_create_manual_backup_archive_filepath(id, name, data_dir)
       .await?;
 
-  if let Err(e) =
-    create_zip_archive(&user_data_dir, &dirs_to_backup, &archive_path)
-      .await
-  {
+  let result = async {
+    create_zip_archive(
+      &user_data_dir,
+      &dirs_to_backup,
+      &archive_path,
+    )
+    .await?;
+
+    let integrity = compute_archive_integrity(&archive_path).await?;
+    backup_repository
+      .set_manual_backup_integrity(id, &integrity)
+      .await?;
+    Ok::<(), CreateManualBackupError>(())
+  }
+  .await;
+
+  if let Err(e) = result {
     let _ = backup_repository.delete_manual_backup_entry(id).await;
-    return Err(e.into());
+    let _ = tokio::fs::remove_file(&archive_path).await;
+    return Err(e);
   }
 
   Ok(id)
@@ -114,6 +214,9 @@ pub async fn delete_manual_backup(
         &backup.game_variant,
         backup.timestamp,
         backup.notes,
+        backup.data_profile.as_deref(),
+        &backup.scopes,
+        backup.release_version.as_deref(),
       )
       .await;
     return Err(DeleteManualBackupError::RemoveBackupFile(e));
@@ -130,20 +233,160 @@ pub enum RestoreManualBackupError {
   #[error("failed to get backup archive path: {0}")]
   BackupArchivePath(#[from] GetManualBackupArchivePathError),
 
+  #[error("failed to extract archive: {0}")]
+  Extract(#[from] ExtractionError),
+
+  #[error("failed to replace save directory: {0}")]
+  Swap(#[from] RestoreSaveError),
+
+  #[error("failed to restore selection: {0}")]
+  Selection(#[from] RestoreSelectionError),
+
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
-  #[error("failed to extract archive: {0}")]
-  Extract(#[from] ExtractionError),
+  #[error("failed to get data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error(
+    "backup was taken from data profile {0}, which was deleted"
+  )]
+  DeletedDataProfile(String),
+
+  #[error("backups cannot be restored while {0} is running")]
+  GameRunning(GameVariant),
+}
+
+// Like automatic backups, manual backups are never restored into another
+// profile than the one they were taken from.
+async fn ensure_data_profile_exists(
+  backup: &ManualBackupEntry,
+  data_profiles_repository: &dyn DataProfilesRepository,
+) -> Result<(), RestoreManualBackupError> {
+  let data_profile = backup.data_profile.as_deref();
+  if !data_profile_exists(
+    &backup.game_variant,
+    data_profile,
+    data_profiles_repository,
+  )
+  .await?
+  {
+    return Err(RestoreManualBackupError::DeletedDataProfile(
+      data_profile.unwrap_or_default().to_string(),
+    ));
+  }
+
+  Ok(())
 }
 
+/// Replaces the directories of the backup's scopes with the backup, the
+/// same way automatic backups are restored.
 pub async fn restore_manual_backup(
+  id: i64,
+  timestamp: u64,
+  data_dir: &Path,
+  backup_repository: &impl ManualBackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  running_games: &RunningGamesRegistry,
+  os: &OS,
+) -> Result<(), RestoreManualBackupError> {
+  let backup = backup_repository.get_manual_backup_entry(id).await?;
+  ensure_data_profile_exists(&backup, data_profiles_repository)
+    .await?;
+  if running_games
+    .is_running(&backup.game_variant, backup.data_profile.as_deref())
+  {
+    return Err(RestoreManualBackupError::GameRunning(
+      backup.game_variant,
+    ));
+  }
+  let archive_path: PathBuf =
+    get_or_create_manual_backup_archive_filepath(
+      backup.id,
+      &backup.name,
+      data_dir,
+    )
+    .await?;
+
+  let staging_dir = create_restore_staging_dir(data_dir).await?;
+  let result = async {
+    extract_archive(&archive_path, &staging_dir, os).await?;
+    commit_restore(
+      &backup.game_variant,
+      backup.data_profile.as_deref(),
+      &staging_dir,
+      None,
+      &backup.scopes,
+      timestamp,
+      data_dir,
+    )
+    .await?;
+    Ok(())
+  }
+  .await;
+
+  if result.is_err() {
+    discard_restore_staging_dir(&staging_dir).await;
+  }
+
+  result
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListManualBackupWorldsError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetManualBackupArchivePathError),
+
+  #[error("failed to read archive: {0}")]
+  Archive(#[from] ExtractionError),
+}
+
+/// Lists the worlds and characters in a backup without extracting it.
+pub async fn list_manual_backup_worlds(
   id: i64,
   data_dir: &Path,
   backup_repository: &impl ManualBackupRepository,
+) -> Result<Vec<BackupWorld>, ListManualBackupWorldsError> {
+  let backup = backup_repository.get_manual_backup_entry(id).await?;
+  let archive_path: PathBuf =
+    get_or_create_manual_backup_archive_filepath(
+      backup.id,
+      &backup.name,
+      data_dir,
+    )
+    .await?;
+
+  let paths = list_zip_entries(&archive_path).await?;
+
+  Ok(list_worlds(&paths))
+}
+
+/// Restores a single world, or a single character within a world, from a
+/// backup. The other worlds are left untouched.
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_manual_backup_selection(
+  id: i64,
+  selection: &RestoreSelection,
+  timestamp: u64,
+  data_dir: &Path,
+  backup_repository: &impl ManualBackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  running_games: &RunningGamesRegistry,
   os: &OS,
 ) -> Result<(), RestoreManualBackupError> {
   let backup = backup_repository.get_manual_backup_entry(id).await?;
+  ensure_data_profile_exists(&backup, data_profiles_repository)
+    .await?;
+  if running_games
+    .is_running(&backup.game_variant, backup.data_profile.as_deref())
+  {
+    return Err(RestoreManualBackupError::GameRunning(
+      backup.game_variant,
+    ));
+  }
   let archive_path: PathBuf =
     get_or_create_manual_backup_archive_filepath(
       backup.id,
@@ -151,12 +394,97 @@ pub async fn restore_manual_backup(
       data_dir,
     )
     .await?;
+  let user_data_dir = get_or_create_user_game_data_dir(
+    &backup.game_variant,
+    backup.data_profile.as_deref(),
+    data_dir,
+  )
+  .await?;
 
-  let user_data_dir =
-    get_or_create_user_game_data_dir(&backup.game_variant, data_dir)
+  let staging_dir = create_restore_staging_dir(data_dir).await?;
+  let result = async {
+    prepare_selection_staging(
+      selection,
+      &user_data_dir,
+      &staging_dir,
+      os,
+    )
+    .await?;
+
+    let filter_selection = selection.clone();
+    let extracted =
+      extract_zip_entries(&archive_path, &staging_dir, move |path| {
+        filter_selection.matches(path)
+      })
       .await?;
+    if extracted == 0 {
+      return Err(RestoreSelectionError::NotInBackup.into());
+    }
 
-  extract_archive(&archive_path, &user_data_dir, os).await?;
+    commit_restore(
+      &backup.game_variant,
+      backup.data_profile.as_deref(),
+      &staging_dir,
+      Some(selection.world()),
+      &[],
+      timestamp,
+      data_dir,
+    )
+    .await?;
+    Ok(())
+  }
+  .await;
 
-  Ok(())
+  if result.is_err() {
+    discard_restore_staging_dir(&staging_dir).await;
+  }
+
+  result
+}
+
+pub async fn get_manual_backup_archive_path(
+  backup: &ManualBackupEntry,
+  data_dir: &Path,
+) -> Result<PathBuf, GetManualBackupArchivePathError> {
+  get_or_create_manual_backup_archive_filepath(
+    backup.id,
+    &backup.name,
+    data_dir,
+  )
+  .await
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VerifyManualBackupError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetManualBackupArchivePathError),
+
+  #[error("failed to verify backup: {0}")]
+  Integrity(#[from] BackupIntegrityError),
+}
+
+/// Checks a backup against the checksum recorded when it was created and
+/// reads every entry of its archive.
+pub async fn verify_manual_backup(
+  id: i64,
+  data_dir: &Path,
+  backup_repository: &impl ManualBackupRepository,
+) -> Result<BackupVerification, VerifyManualBackupError> {
+  let backup = backup_repository.get_manual_backup_entry(id).await?;
+  verify_manual_backup_entry(&backup, data_dir).await
+}
+
+pub async fn verify_manual_backup_entry(
+  backup: &ManualBackupEntry,
+  data_dir: &Path,
+) -> Result<BackupVerification, VerifyManualBackupError> {
+  let archive_path =
+    get_manual_backup_archive_path(backup, data_dir).await?;
+  let verification =
+    verify_archive_files(&archive_path, backup.integrity.as_ref())
+      .await?;
+  Ok(verification)
 }
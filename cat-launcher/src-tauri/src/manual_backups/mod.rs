@@ -1,3 +1,4 @@
 pub mod commands;
 pub mod manual_backups;
+pub mod portable;
 pub mod repository;
@@ -0,0 +1,318 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinError;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::CompressionMethod::Stored;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::backups::integrity::{
+  compute_archive_integrity, BackupIntegrityError,
+};
+use crate::backups::scopes::{default_backup_scopes, BackupScope};
+use crate::data_profiles::repository::{
+  DataProfilesRepository, DataProfilesRepositoryError,
+};
+use crate::filesystem::paths::GetManualBackupArchivePathError;
+use crate::infra::archive::{test_zip_archive, ExtractionError};
+use crate::infra::checksum::{
+  compute_file_sha256, ComputeSha256Error,
+};
+use crate::manual_backups::manual_backups::get_manual_backup_archive_path;
+use crate::manual_backups::repository::manual_backup_repository::{
+  ManualBackupRepository, ManualBackupRepositoryError,
+};
+use crate::variants::GameVariant;
+
+/// The version of the exported backup format. Archives with a newer
+/// version are rejected, as they may contain data this launcher ignores.
+pub const PORTABLE_BACKUP_FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const BACKUP_ENTRY: &str = "backup.zip";
+
+/// Describes an exported backup, so that it can be imported on another
+/// machine or launcher installation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortableBackupManifest {
+  pub format_version: u32,
+  pub game_variant: GameVariant,
+  pub name: String,
+  pub notes: Option<String>,
+  /// The release of the variant that was active when the backup was
+  /// taken. None if it was not recorded.
+  pub release_version: Option<String>,
+  pub timestamp: u64,
+  #[serde(default = "default_backup_scopes")]
+  pub scopes: Vec<BackupScope>,
+  /// The SHA-256 of the backup archive.
+  pub sha256: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WritePortableBackupError {
+  #[error("failed to write manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  #[error("failed to write archive: {0}")]
+  Zip(#[from] ZipError),
+
+  #[error("failed to write archive: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+/// Writes `destination` as a zip archive containing the backup archive and
+/// the manifest describing it. The destination is removed if writing it
+/// fails.
+pub async fn write_portable_backup(
+  manifest: &PortableBackupManifest,
+  archive_path: &Path,
+  destination: &Path,
+) -> Result<(), WritePortableBackupError> {
+  let manifest = serde_json::to_vec_pretty(manifest)?;
+  let archive_path = archive_path.to_owned();
+  let destination = destination.to_owned();
+
+  let result = tokio::task::spawn_blocking({
+    let destination = destination.clone();
+    move || {
+      let mut zip = ZipWriter::new(File::create(&destination)?);
+      // The backup archive is already compressed.
+      let options: FileOptions<'_, ()> =
+        FileOptions::default().compression_method(Stored);
+
+      zip.start_file(MANIFEST_ENTRY, options)?;
+      zip.write_all(&manifest)?;
+
+      zip.start_file(BACKUP_ENTRY, options)?;
+      io::copy(&mut File::open(&archive_path)?, &mut zip)?;
+
+      zip.finish()?.sync_all()?;
+      Ok::<(), WritePortableBackupError>(())
+    }
+  })
+  .await?;
+
+  if result.is_err() {
+    let _ = tokio::fs::remove_file(&destination).await;
+  }
+
+  result
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportManualBackupError {
+  #[error("failed to get backup entry: {0}")]
+  Get(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetManualBackupArchivePathError),
+
+  #[error("failed to compute checksum: {0}")]
+  Checksum(#[from] ComputeSha256Error),
+
+  #[error("failed to write exported backup: {0}")]
+  Write(#[from] WritePortableBackupError),
+}
+
+/// Writes a manual backup to `destination` as a portable backup.
+pub async fn export_manual_backup(
+  id: i64,
+  destination: &Path,
+  data_dir: &Path,
+  backup_repository: &impl ManualBackupRepository,
+) -> Result<(), ExportManualBackupError> {
+  let backup = backup_repository.get_manual_backup_entry(id).await?;
+  let archive_path =
+    get_manual_backup_archive_path(&backup, data_dir).await?;
+
+  let manifest = PortableBackupManifest {
+    format_version: PORTABLE_BACKUP_FORMAT_VERSION,
+    game_variant: backup.game_variant,
+    name: backup.name,
+    notes: backup.notes,
+    release_version: backup.release_version,
+    timestamp: backup.timestamp,
+    scopes: backup.scopes,
+    sha256: compute_file_sha256(&archive_path).await?,
+  };
+
+  write_portable_backup(&manifest, &archive_path, destination)
+    .await?;
+  Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ImportBackupError {
+  #[error("failed to read archive: {0}")]
+  Zip(#[from] ZipError),
+
+  #[error("failed to read archive: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("invalid backup manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  #[error(
+    "unsupported backup format version {0}, update the launcher to import it"
+  )]
+  UnsupportedFormatVersion(u32),
+
+  #[error("backup name cannot be empty")]
+  EmptyName,
+
+  #[error("backup contains no directories")]
+  NoScopes,
+
+  #[error(
+    "backup is corrupted: expected SHA-256 {expected}, got {actual}"
+  )]
+  ChecksumMismatch { expected: String, actual: String },
+
+  #[error("backup archive is corrupted: {0}")]
+  Archive(#[from] ExtractionError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfilesRepositoryError),
+
+  #[error("failed to add backup entry: {0}")]
+  Add(#[from] ManualBackupRepositoryError),
+
+  #[error("failed to get backup archive path: {0}")]
+  BackupArchivePath(#[from] GetManualBackupArchivePathError),
+
+  #[error("failed to compute checksum: {0}")]
+  Checksum(#[from] ComputeSha256Error),
+
+  #[error("failed to record backup integrity: {0}")]
+  Integrity(#[from] BackupIntegrityError),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+fn validate_manifest(
+  manifest: &PortableBackupManifest,
+) -> Result<(), ImportBackupError> {
+  if manifest.format_version > PORTABLE_BACKUP_FORMAT_VERSION {
+    return Err(ImportBackupError::UnsupportedFormatVersion(
+      manifest.format_version,
+    ));
+  }
+
+  if manifest.name.trim().is_empty() {
+    return Err(ImportBackupError::EmptyName);
+  }
+
+  if manifest.scopes.is_empty() {
+    return Err(ImportBackupError::NoScopes);
+  }
+
+  Ok(())
+}
+
+/// Reads the manifest of a backup written by `write_portable_backup`.
+pub async fn read_portable_manifest(
+  source: &Path,
+) -> Result<PortableBackupManifest, ImportBackupError> {
+  let source = source.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut archive = ZipArchive::new(File::open(&source)?)?;
+    let mut manifest = String::new();
+    archive
+      .by_name(MANIFEST_ENTRY)?
+      .read_to_string(&mut manifest)?;
+    Ok(serde_json::from_str(&manifest)?)
+  })
+  .await?
+}
+
+async fn extract_backup_archive(
+  source: &Path,
+  archive_path: &Path,
+) -> Result<(), ImportBackupError> {
+  let source = source.to_owned();
+  let archive_path = archive_path.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut archive = ZipArchive::new(File::open(&source)?)?;
+    let mut entry = archive.by_name(BACKUP_ENTRY)?;
+    let mut file = File::create(&archive_path)?;
+    io::copy(&mut entry, &mut file)?;
+    file.sync_all()?;
+    Ok(())
+  })
+  .await?
+}
+
+/// Registers a backup exported by `export_manual_backup` as a manual
+/// backup of the active data profile of its variant. Returns the id of the
+/// new backup.
+pub async fn import_backup(
+  source: &Path,
+  data_dir: &Path,
+  backup_repository: &impl ManualBackupRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+) -> Result<i64, ImportBackupError> {
+  let manifest = read_portable_manifest(source).await?;
+  validate_manifest(&manifest)?;
+
+  let data_profile = data_profiles_repository
+    .get_active_data_profile(&manifest.game_variant)
+    .await?;
+
+  let id = backup_repository
+    .add_manual_backup_entry(
+      manifest.name.trim(),
+      &manifest.game_variant,
+      manifest.timestamp,
+      manifest.notes.clone(),
+      data_profile.as_deref(),
+      &manifest.scopes,
+      manifest.release_version.as_deref(),
+    )
+    .await?;
+
+  let mut archive_path = None;
+  let result = async {
+    let backup =
+      backup_repository.get_manual_backup_entry(id).await?;
+    let path = archive_path.insert(
+      get_manual_backup_archive_path(&backup, data_dir).await?,
+    );
+
+    extract_backup_archive(source, path).await?;
+
+    let actual = compute_file_sha256(path).await?;
+    if actual != manifest.sha256 {
+      return Err(ImportBackupError::ChecksumMismatch {
+        expected: manifest.sha256.clone(),
+        actual,
+      });
+    }
+    test_zip_archive(path).await?;
+
+    let integrity = compute_archive_integrity(path).await?;
+    backup_repository
+      .set_manual_backup_integrity(id, &integrity)
+      .await?;
+    Ok(())
+  }
+  .await;
+
+  if let Err(e) = result {
+    let _ = backup_repository.delete_manual_backup_entry(id).await;
+    if let Some(archive_path) = archive_path {
+      let _ = tokio::fs::remove_file(&archive_path).await;
+    }
+    return Err(e);
+  }
+
+  Ok(id)
+}
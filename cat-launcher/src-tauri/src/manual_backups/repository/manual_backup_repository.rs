@@ -2,6 +2,8 @@ use async_trait::async_trait;
 use serde::Serialize;
 use ts_rs::TS;
 
+use crate::backups::integrity::BackupIntegrity;
+use crate::backups::scopes::BackupScope;
 use crate::variants::GameVariant;
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -12,6 +14,15 @@ pub struct ManualBackupEntry {
   pub game_variant: GameVariant,
   pub timestamp: u64,
   pub notes: Option<String>,
+  pub data_profile: Option<String>,
+  /// The directories of the user data directory the backup contains.
+  pub scopes: Vec<BackupScope>,
+  /// What the backup contained when it was created, or None if it was
+  /// created before this was recorded.
+  pub integrity: Option<BackupIntegrity>,
+  /// The release that was active when the backup was created, or None if
+  /// it is unknown.
+  pub release_version: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +33,9 @@ pub enum ManualBackupRepositoryError {
   #[error("failed to get backup entries: {0}")]
   Get(Box<dyn std::error::Error + Send + Sync>),
 
+  #[error("failed to update backup entry: {0}")]
+  Update(Box<dyn std::error::Error + Send + Sync>),
+
   #[error("failed to delete backup entry: {0}")]
   Delete(Box<dyn std::error::Error + Send + Sync>),
 
@@ -37,6 +51,9 @@ pub trait ManualBackupRepository: Send + Sync {
     game_variant: &GameVariant,
     timestamp: u64,
     notes: Option<String>,
+    data_profile: Option<&str>,
+    scopes: &[BackupScope],
+    release_version: Option<&str>,
   ) -> Result<i64, ManualBackupRepositoryError>;
 
   async fn get_manual_backups_sorted_by_timestamp(
@@ -44,11 +61,22 @@ pub trait ManualBackupRepository: Send + Sync {
     game_variant: &GameVariant,
   ) -> Result<Vec<ManualBackupEntry>, ManualBackupRepositoryError>;
 
+  /// Returns the manual backups of all variants.
+  async fn get_all_manual_backups(
+    &self,
+  ) -> Result<Vec<ManualBackupEntry>, ManualBackupRepositoryError>;
+
   async fn get_manual_backup_entry(
     &self,
     id: i64,
   ) -> Result<ManualBackupEntry, ManualBackupRepositoryError>;
 
+  async fn set_manual_backup_integrity(
+    &self,
+    id: i64,
+    integrity: &BackupIntegrity,
+  ) -> Result<(), ManualBackupRepositoryError>;
+
   async fn delete_manual_backup_entry(
     &self,
     id: i64,
@@ -4,6 +4,8 @@ use async_trait::async_trait;
 use r2d2_sqlite::SqliteConnectionManager;
 use tokio::task;
 
+use crate::backups::integrity::BackupIntegrity;
+use crate::backups::scopes::{default_backup_scopes, BackupScope};
 use crate::manual_backups::repository::manual_backup_repository::{
   ManualBackupEntry, ManualBackupRepository,
   ManualBackupRepositoryError,
@@ -12,6 +14,8 @@ use crate::variants::GameVariant;
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
+const SELECT_MANUAL_BACKUP: &str = "SELECT b.id, b.name, b.game_variant, b.timestamp, b.notes, p.profile_name, s.scopes, i.size_in_bytes, i.sha256, i.file_count, i.worlds, r.release_version FROM manual_backups b LEFT JOIN manual_backup_data_profiles p ON p.backup_id = b.id LEFT JOIN manual_backup_scopes s ON s.backup_id = b.id LEFT JOIN manual_backup_integrity i ON i.backup_id = b.id LEFT JOIN manual_backup_releases r ON r.backup_id = b.id";
+
 #[derive(Clone)]
 pub struct SqliteManualBackupRepository {
   pool: Pool,
@@ -23,6 +27,52 @@ impl SqliteManualBackupRepository {
   }
 }
 
+// Backups taken before scopes were recorded only contain the save
+// directory.
+fn get_scopes_column(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<Vec<BackupScope>> {
+  let scopes: Option<String> = row.get(index)?;
+  match scopes {
+    Some(scopes) => serde_json::from_str(&scopes).map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        index,
+        rusqlite::types::Type::Text,
+        Box::new(e),
+      )
+    }),
+    None => Ok(default_backup_scopes()),
+  }
+}
+
+// Backups created before their integrity was recorded have no row.
+fn get_integrity_columns(
+  row: &rusqlite::Row,
+  index: usize,
+) -> rusqlite::Result<Option<BackupIntegrity>> {
+  let sha256: Option<String> = row.get(index + 1)?;
+  let Some(sha256) = sha256 else {
+    return Ok(None);
+  };
+
+  let worlds: String = row.get(index + 3)?;
+  let worlds = serde_json::from_str(&worlds).map_err(|e| {
+    rusqlite::Error::FromSqlConversionFailure(
+      index + 3,
+      rusqlite::types::Type::Text,
+      Box::new(e),
+    )
+  })?;
+
+  Ok(Some(BackupIntegrity {
+    size_in_bytes: row.get(index)?,
+    sha256,
+    file_count: row.get(index + 2)?,
+    worlds,
+  }))
+}
+
 #[async_trait]
 impl ManualBackupRepository for SqliteManualBackupRepository {
   async fn add_manual_backup_entry(
@@ -31,18 +81,43 @@ impl ManualBackupRepository for SqliteManualBackupRepository {
     game_variant: &GameVariant,
     timestamp: u64,
     notes: Option<String>,
+    data_profile: Option<&str>,
+    scopes: &[BackupScope],
+    release_version: Option<&str>,
   ) -> Result<i64, ManualBackupRepositoryError> {
     let pool = self.pool.clone();
     let name = name.to_string();
     let game_variant = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
+    let release_version = release_version.map(str::to_string);
+    let scopes = serde_json::to_string(scopes)
+      .map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
-            let id = conn.query_row(
+            let mut conn = pool.get().map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            let tx = conn.transaction().map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            let id: i64 = tx.query_row(
                 "INSERT INTO manual_backups (name, game_variant, timestamp, notes) VALUES (?1, ?2, ?3, ?4) RETURNING id",
                 rusqlite::params![name, game_variant, timestamp, notes],
                 |row| row.get(0),
             ).map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            if let Some(data_profile) = data_profile {
+                tx.execute(
+                    "INSERT INTO manual_backup_data_profiles (backup_id, profile_name) VALUES (?1, ?2)",
+                    rusqlite::params![id, data_profile],
+                ).map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            }
+            tx.execute(
+                "INSERT INTO manual_backup_scopes (backup_id, scopes) VALUES (?1, ?2)",
+                rusqlite::params![id, scopes],
+            ).map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            if let Some(release_version) = release_version {
+                tx.execute(
+                    "INSERT INTO manual_backup_releases (backup_id, release_version) VALUES (?1, ?2)",
+                    rusqlite::params![id, release_version],
+                ).map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
+            }
+            tx.commit().map_err(|e| ManualBackupRepositoryError::Add(Box::new(e)))?;
             Ok(id)
         })
         .await
@@ -57,32 +132,96 @@ impl ManualBackupRepository for SqliteManualBackupRepository {
     let game_variant = game_variant.to_string();
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
-            let mut stmt = conn.prepare(
-                "SELECT id, name, game_variant, timestamp, notes FROM manual_backups WHERE game_variant = ?1 ORDER BY timestamp ASC",
-            ).map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
-            let backups = stmt
-                .query_map(rusqlite::params![game_variant], |row| {
-                    let id = row.get(0)?;
-                    let name: String = row.get(1)?;
-                    let game_variant_str: String = row.get(2)?;
-                    let game_variant = GameVariant::from_str(&game_variant_str)
-                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
-                    Ok(ManualBackupEntry {
-                        id,
-                        name,
-                        game_variant,
-                        timestamp: row.get(3)?,
-                        notes: row.get(4)?,
-                    })
-                })
-                .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
-                .collect::<Result<Vec<ManualBackupEntry>, _>>()
-                .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
-            Ok(backups)
+      let conn = pool
+        .get()
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!(
+          "{} WHERE b.game_variant = ?1 ORDER BY b.timestamp ASC",
+          SELECT_MANUAL_BACKUP
+        ))
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let backups = stmt
+        .query_map(rusqlite::params![game_variant], |row| {
+          let id = row.get(0)?;
+          let name: String = row.get(1)?;
+          let game_variant_str: String = row.get(2)?;
+          let game_variant = GameVariant::from_str(&game_variant_str)
+            .map_err(|e| {
+              rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+              )
+            })?;
+          Ok(ManualBackupEntry {
+            id,
+            name,
+            game_variant,
+            timestamp: row.get(3)?,
+            notes: row.get(4)?,
+            data_profile: row.get(5)?,
+            scopes: get_scopes_column(row, 6)?,
+            integrity: get_integrity_columns(row, 7)?,
+            release_version: row.get(11)?,
+          })
         })
-        .await
         .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
+        .collect::<Result<Vec<ManualBackupEntry>, _>>()
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      Ok(backups)
+    })
+    .await
+    .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn get_all_manual_backups(
+    &self,
+  ) -> Result<Vec<ManualBackupEntry>, ManualBackupRepositoryError> {
+    let pool = self.pool.clone();
+
+    task::spawn_blocking(move || {
+      let conn = pool
+        .get()
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!(
+          "{} ORDER BY b.timestamp ASC",
+          SELECT_MANUAL_BACKUP
+        ))
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let backups = stmt
+        .query_map([], |row| {
+          let id = row.get(0)?;
+          let name: String = row.get(1)?;
+          let game_variant_str: String = row.get(2)?;
+          let game_variant = GameVariant::from_str(&game_variant_str)
+            .map_err(|e| {
+              rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(e),
+              )
+            })?;
+          Ok(ManualBackupEntry {
+            id,
+            name,
+            game_variant,
+            timestamp: row.get(3)?,
+            notes: row.get(4)?,
+            data_profile: row.get(5)?,
+            scopes: get_scopes_column(row, 6)?,
+            integrity: get_integrity_columns(row, 7)?,
+            release_version: row.get(11)?,
+          })
+        })
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
+        .collect::<Result<Vec<ManualBackupEntry>, _>>()
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      Ok(backups)
+    })
+    .await
+    .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
   }
 
   async fn get_manual_backup_entry(
@@ -92,34 +231,81 @@ impl ManualBackupRepository for SqliteManualBackupRepository {
     let pool = self.pool.clone();
 
     task::spawn_blocking(move || {
-            let conn = pool.get().map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
-            let mut stmt = conn.prepare(
-                "SELECT id, name, game_variant, timestamp, notes FROM manual_backups WHERE id = ?1",
-            ).map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
-            let backup = stmt
-                .query_row(rusqlite::params![id], |row| {
-                    let id = row.get(0)?;
-                    let name: String = row.get(1)?;
-                    let game_variant_str: String = row.get(2)?;
-                    let game_variant = GameVariant::from_str(&game_variant_str)
-                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e)))?;
-                    Ok(ManualBackupEntry {
-                        id,
-                        name,
-                        game_variant,
-                        timestamp: row.get(3)?,
-                        notes: row.get(4)?,
-                    })
-                });
-
-            match backup {
-                Ok(backup) => Ok(backup),
-                Err(rusqlite::Error::QueryReturnedNoRows) => Err(ManualBackupRepositoryError::NotFound(id)),
-                Err(e) => Err(ManualBackupRepositoryError::Get(Box::new(e))),
-            }
+      let conn = pool
+        .get()
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let mut stmt = conn
+        .prepare(&format!("{} WHERE b.id = ?1", SELECT_MANUAL_BACKUP))
+        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?;
+      let backup = stmt.query_row(rusqlite::params![id], |row| {
+        let id = row.get(0)?;
+        let name: String = row.get(1)?;
+        let game_variant_str: String = row.get(2)?;
+        let game_variant = GameVariant::from_str(&game_variant_str)
+          .map_err(|e| {
+          rusqlite::Error::FromSqlConversionFailure(
+            0,
+            rusqlite::types::Type::Text,
+            Box::new(e),
+          )
+        })?;
+        Ok(ManualBackupEntry {
+          id,
+          name,
+          game_variant,
+          timestamp: row.get(3)?,
+          notes: row.get(4)?,
+          data_profile: row.get(5)?,
+          scopes: get_scopes_column(row, 6)?,
+          integrity: get_integrity_columns(row, 7)?,
+          release_version: row.get(11)?,
         })
-        .await
-        .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
+      });
+
+      match backup {
+        Ok(backup) => Ok(backup),
+        Err(rusqlite::Error::QueryReturnedNoRows) => {
+          Err(ManualBackupRepositoryError::NotFound(id))
+        }
+        Err(e) => Err(ManualBackupRepositoryError::Get(Box::new(e))),
+      }
+    })
+    .await
+    .map_err(|e| ManualBackupRepositoryError::Get(Box::new(e)))?
+  }
+
+  async fn set_manual_backup_integrity(
+    &self,
+    id: i64,
+    integrity: &BackupIntegrity,
+  ) -> Result<(), ManualBackupRepositoryError> {
+    let pool = self.pool.clone();
+    let integrity = integrity.clone();
+    let worlds =
+      serde_json::to_string(&integrity.worlds).map_err(|e| {
+        ManualBackupRepositoryError::Update(Box::new(e))
+      })?;
+
+    task::spawn_blocking(move || {
+      let conn = pool
+        .get()
+        .map_err(|e| ManualBackupRepositoryError::Update(Box::new(e)))?;
+      conn
+        .execute(
+          "INSERT OR REPLACE INTO manual_backup_integrity (backup_id, size_in_bytes, sha256, file_count, worlds) VALUES (?1, ?2, ?3, ?4, ?5)",
+          rusqlite::params![
+            id,
+            integrity.size_in_bytes,
+            integrity.sha256,
+            integrity.file_count,
+            worlds
+          ],
+        )
+        .map_err(|e| ManualBackupRepositoryError::Update(Box::new(e)))?;
+      Ok(())
+    })
+    .await
+    .map_err(|e| ManualBackupRepositoryError::Update(Box::new(e)))?
   }
 
   async fn delete_manual_backup_entry(
@@ -3,6 +3,10 @@ use tauri::{command, AppHandle, Manager, State};
 
 use cat_macros::CommandErrorSerialize;
 
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::master_reset::reset::{
   master_reset as master_reset_fn, MasterResetError,
 };
@@ -21,6 +25,9 @@ pub enum MasterResetCommandError {
 
   #[error("failed to get system directory: {0}")]
   SystemDir(#[from] tauri::Error),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[command]
@@ -37,11 +44,19 @@ pub async fn master_reset(
     SqliteInstalledTilesetsRepository,
   >,
   settings_repository: State<'_, SqliteSettingsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), MasterResetCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
 
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   master_reset_fn(
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     installed_mods_repository.inner(),
     installed_soundpacks_repository.inner(),
@@ -61,6 +61,7 @@ async fn should_skip(
 
 pub async fn master_reset(
   variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
   installed_mods_repository: &dyn InstalledModsRepository,
   installed_soundpacks_repository: &dyn InstalledSoundpacksRepository,
@@ -68,7 +69,8 @@ pub async fn master_reset(
   settings_repository: &dyn SettingsRepository,
 ) -> Result<(), MasterResetError> {
   let user_data_dir =
-    get_or_create_user_game_data_dir(variant, data_dir).await?;
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
 
   let mut entries = fs::read_dir(&user_data_dir).await?;
 
@@ -91,15 +93,15 @@ pub async fn master_reset(
 
   // Update DB sequentially to avoid deadlocks
   installed_mods_repository
-    .delete_all_installed_mods(variant)
+    .delete_all_installed_mods(variant, data_profile)
     .await?;
 
   installed_soundpacks_repository
-    .delete_all_installed_soundpacks(variant)
+    .delete_all_installed_soundpacks(variant, data_profile)
     .await?;
 
   installed_tilesets_repository
-    .delete_all_installed_tilesets(variant)
+    .delete_all_installed_tilesets(variant, data_profile)
     .await?;
 
   settings_repository
@@ -0,0 +1,108 @@
+use reqwest::Client;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::mods::mod_revision::{
+  get_latest_mod_revision, GetModRevisionError, ModRevision,
+};
+use crate::mods::repository::installed_mods_repository::{
+  InstalledModSource, InstalledModsRepository,
+  InstalledModsRepositoryError,
+};
+use crate::mods::repository::mods_repository::{
+  GetThirdPartyModByIdError, ModsRepository,
+};
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct ModUpdate {
+  pub installed: InstalledModSource,
+  pub update_available: bool,
+  /// Why the mod could not be checked. The other mods are still checked.
+  pub error: Option<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CheckModUpdatesError {
+  #[error("failed to get installed mods: {0}")]
+  InstalledMods(#[from] InstalledModsRepositoryError),
+}
+
+#[derive(thiserror::Error, Debug)]
+enum CheckModUpdateError {
+  #[error("failed to get mod: {0}")]
+  GetMod(#[from] GetThirdPartyModByIdError),
+
+  #[error("failed to get latest revision: {0}")]
+  Revision(#[from] GetModRevisionError),
+}
+
+async fn is_update_available(
+  installed: &InstalledModSource,
+  game_variant: &GameVariant,
+  client: &Client,
+  mods_repository: &impl ModsRepository,
+) -> Result<bool, CheckModUpdateError> {
+  let mod_details = mods_repository
+    .get_third_party_mod_by_id(&installed.mod_id, game_variant)
+    .await?;
+  if mod_details.installation.download_url != installed.source_url {
+    return Ok(true);
+  }
+
+  let latest = get_latest_mod_revision(&mod_details, client).await?;
+  Ok(match (latest, &installed.commit_sha, &installed.etag) {
+    (None, _, _) | (Some(_), None, None) => false,
+    (Some(ModRevision::Commit(sha)), Some(installed_sha), _) => {
+      sha != *installed_sha
+    }
+    (Some(ModRevision::ETag(etag)), _, Some(installed_etag)) => {
+      etag != *installed_etag
+    }
+    // The mod is tracked differently than when it was installed, for
+    // example because its GitHub repository was added to the catalog.
+    _ => true,
+  })
+}
+
+/// Compares the installed third-party mods with their latest upstream
+/// revisions. Mods installed before their source was recorded are not
+/// checked.
+pub async fn check_mod_updates(
+  game_variant: &GameVariant,
+  data_profile: Option<&str>,
+  client: &Client,
+  installed_mods_repository: &impl InstalledModsRepository,
+  mods_repository: &impl ModsRepository,
+) -> Result<Vec<ModUpdate>, CheckModUpdatesError> {
+  let sources = installed_mods_repository
+    .get_installed_mod_sources(game_variant, data_profile)
+    .await?;
+
+  let mut updates = Vec::new();
+  for installed in sources {
+    let result = is_update_available(
+      &installed,
+      game_variant,
+      client,
+      mods_repository,
+    )
+    .await;
+
+    updates.push(match result {
+      Ok(update_available) => ModUpdate {
+        installed,
+        update_available,
+        error: None,
+      },
+      Err(e) => ModUpdate {
+        installed,
+        update_available: false,
+        error: Some(e.to_string()),
+      },
+    });
+  }
+
+  Ok(updates)
+}
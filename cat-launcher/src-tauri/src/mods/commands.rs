@@ -1,7 +1,10 @@
 use std::env::consts::OS;
 use std::sync::Arc;
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
 
 use reqwest::Client;
+use serde::ser::SerializeStruct;
+use serde::Serialize;
 use strum::IntoStaticStr;
 use tauri::ipc::Channel;
 use tauri::{Emitter, Manager, State};
@@ -9,9 +12,18 @@ use tauri::{Emitter, Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::backup_targets::mirror::BackupMirror;
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::download::Downloader;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
+use crate::mods::check_mod_updates::{
+  check_mod_updates, CheckModUpdatesError, ModUpdate,
+};
 use crate::mods::get_last_activity_for_third_party_mod::{
   get_last_activity_for_third_party_mod,
   GetLastActivityForThirdPartyModError, LastModActivity,
@@ -22,6 +34,7 @@ use crate::mods::get_third_party_mod_installation_status::{
 };
 use crate::mods::install_third_party_mod::{
   install_third_party_mod, InstallThirdPartyModError,
+  ModDependencyError,
 };
 use crate::mods::lib::OnlineModRepositoryRegistry;
 use crate::mods::list_all_mods::{list_all_mods, ListAllModsError};
@@ -33,6 +46,9 @@ use crate::mods::types::{
 use crate::mods::uninstall_third_party_mod::{
   uninstall_third_party_mod, UninstallThirdPartyModError,
 };
+use crate::mods::update_third_party_mod::{
+  update_third_party_mod, UpdateThirdPartyModError,
+};
 use crate::variants::GameVariant;
 
 #[derive(
@@ -100,69 +116,179 @@ pub enum InstallThirdPartyModCommandError {
 
   #[error("failed to install mod: {0}")]
   Install(#[from] InstallThirdPartyModError),
+
+  // Distinct from Install, so that the mods involved can be shown.
+  #[error("{0}")]
+  DependencyCycle(ModDependencyError),
+
+  #[error("{0}")]
+  UnavailableDependency(ModDependencyError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn install_third_party_mod_command(
   id: String,
   variant: GameVariant,
   channel: Channel,
   app: tauri::AppHandle,
+  client: State<'_, Client>,
   downloader: State<'_, Downloader>,
   installed_mods_repository: State<'_, SqliteInstalledModsRepository>,
   mods_repository: State<'_, SqliteModsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
 ) -> Result<(), InstallThirdPartyModCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
   let temp_dir = app.path().app_cache_dir()?;
 
   let os = get_os_enum(OS)?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
   let reporter = Arc::new(ChannelReporter::new(channel));
 
-  install_third_party_mod(
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
+  let result = install_third_party_mod(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     &temp_dir,
     &os,
+    timestamp,
+    client.inner(),
     downloader.inner(),
     installed_mods_repository.inner(),
     mods_repository.inner(),
+    active_release_repository.inner(),
     reporter,
   )
-  .await?;
+  .await;
 
-  Ok(())
+  match result {
+    Err(InstallThirdPartyModError::Dependency(
+      e @ ModDependencyError::Cycle(_),
+    )) => Err(InstallThirdPartyModCommandError::DependencyCycle(e)),
+    Err(InstallThirdPartyModError::Dependency(
+      e @ ModDependencyError::Unavailable { .. },
+    )) => {
+      Err(InstallThirdPartyModCommandError::UnavailableDependency(e))
+    }
+    result => result.map_err(Into::into),
+  }
 }
 
-#[derive(
-  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
-)]
+#[derive(thiserror::Error, Debug, IntoStaticStr)]
 pub enum UninstallThirdPartyModCommandError {
   #[error("failed to get app data directory: {0}")]
   AppDataDir(#[from] tauri::Error),
 
   #[error("failed to uninstall mod: {0}")]
   Uninstall(#[from] UninstallThirdPartyModError),
+
+  // Distinct from Uninstall, so that the worlds can be shown and the
+  // uninstall retried with force.
+  #[error("mod {mod_id} is used by worlds: {}", .worlds.join(", "))]
+  ModInUse { mod_id: String, worlds: Vec<String> },
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+impl Serialize for UninstallThirdPartyModCommandError {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let worlds = match self {
+      UninstallThirdPartyModCommandError::ModInUse {
+        worlds, ..
+      } => Some(worlds),
+      _ => None,
+    };
+
+    let mut state = serializer.serialize_struct(
+      "UninstallThirdPartyModCommandError",
+      if worlds.is_some() { 3 } else { 2 },
+    )?;
+    let error_type: &str = self.into();
+    state.serialize_field("type", error_type)?;
+    state.serialize_field("message", &self.to_string())?;
+    if let Some(worlds) = worlds {
+      state.serialize_field("worlds", worlds)?;
+    }
+    state.end()
+  }
 }
 
+/// Returns the id of the manual backup taken before a forced uninstall.
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn uninstall_third_party_mod_command(
   id: String,
   variant: GameVariant,
+  force: Option<bool>,
   app: tauri::AppHandle,
   repository: State<'_, SqliteInstalledModsRepository>,
-) -> Result<(), UninstallThirdPartyModCommandError> {
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  backup_repository: State<'_, SqliteManualBackupRepository>,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+  backup_mirror: State<'_, BackupMirror>,
+) -> Result<Option<i64>, UninstallThirdPartyModCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
-  uninstall_third_party_mod(
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
+  let backup_id = match uninstall_third_party_mod(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
+    force.unwrap_or(false),
+    timestamp,
     repository.inner(),
+    backup_repository.inner(),
+    &*active_release_repository,
   )
-  .await?;
-  Ok(())
+  .await
+  {
+    Err(UninstallThirdPartyModError::UsedByWorlds {
+      mod_id,
+      worlds,
+    }) => {
+      return Err(UninstallThirdPartyModCommandError::ModInUse {
+        mod_id,
+        worlds,
+      });
+    }
+    result => result?,
+  };
+
+  if backup_id.is_some() {
+    backup_mirror.request_sync();
+  }
+  Ok(backup_id)
 }
 
 #[derive(
@@ -171,6 +297,9 @@ pub async fn uninstall_third_party_mod_command(
 pub enum GetThirdPartyModInstallationStatusCommandError {
   #[error("failed to get mod installation status: {0}")]
   GetStatus(#[from] GetThirdPartyModInstallationStatusError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -178,13 +307,21 @@ pub async fn get_third_party_mod_installation_status_command(
   id: String,
   variant: GameVariant,
   repository: State<'_, SqliteInstalledModsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<
   ModInstallationStatus,
   GetThirdPartyModInstallationStatusCommandError,
 > {
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   let status = get_third_party_mod_installation_status(
     &id,
     &variant,
+    data_profile.as_deref(),
     repository.inner(),
   )
   .await?;
@@ -222,3 +359,109 @@ pub async fn get_last_activity_on_third_party_mod_command(
 
   Ok(last_activity)
 }
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum CheckModUpdatesCommandError {
+  #[error("failed to check mod updates: {0}")]
+  Check(#[from] CheckModUpdatesError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+}
+
+#[tauri::command]
+pub async fn check_mod_updates_command(
+  variant: GameVariant,
+  client: State<'_, Client>,
+  installed_mods_repository: State<'_, SqliteInstalledModsRepository>,
+  mods_repository: State<'_, SqliteModsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Vec<ModUpdate>, CheckModUpdatesCommandError> {
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
+  let updates = check_mod_updates(
+    &variant,
+    data_profile.as_deref(),
+    client.inner(),
+    installed_mods_repository.inner(),
+    mods_repository.inner(),
+  )
+  .await?;
+
+  Ok(updates)
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum UpdateThirdPartyModCommandError {
+  #[error("failed to get app data directory")]
+  AppDataDir(#[from] tauri::Error),
+
+  #[error("failed to get OS information")]
+  OSInfo(#[from] OSNotSupportedError),
+
+  #[error("failed to update mod: {0}")]
+  Update(#[from] UpdateThirdPartyModError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to get system time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn update_third_party_mod_command(
+  id: String,
+  variant: GameVariant,
+  channel: Channel,
+  app: tauri::AppHandle,
+  client: State<'_, Client>,
+  downloader: State<'_, Downloader>,
+  installed_mods_repository: State<'_, SqliteInstalledModsRepository>,
+  mods_repository: State<'_, SqliteModsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  active_release_repository: State<'_, SqliteActiveReleaseRepository>,
+) -> Result<(), UpdateThirdPartyModCommandError> {
+  let data_dir = app.path().app_local_data_dir()?;
+  let temp_dir = app.path().app_cache_dir()?;
+
+  let os = get_os_enum(OS)?;
+  let timestamp =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+  let reporter = Arc::new(ChannelReporter::new(channel));
+
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
+  update_third_party_mod(
+    &id,
+    &variant,
+    data_profile.as_deref(),
+    &data_dir,
+    &temp_dir,
+    &os,
+    timestamp,
+    client.inner(),
+    downloader.inner(),
+    installed_mods_repository.inner(),
+    mods_repository.inner(),
+    active_release_repository.inner(),
+    reporter,
+  )
+  .await?;
+
+  Ok(())
+}
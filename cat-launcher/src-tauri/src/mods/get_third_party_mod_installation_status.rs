@@ -13,13 +13,15 @@ pub enum GetThirdPartyModInstallationStatusError {
 pub async fn get_third_party_mod_installation_status(
   mod_id: &str,
   variant: &GameVariant,
+  data_profile: Option<&str>,
   repository: &dyn InstalledModsRepository,
 ) -> Result<
   ModInstallationStatus,
   GetThirdPartyModInstallationStatusError,
 > {
-  let is_installed =
-    repository.is_mod_installed(mod_id, variant).await?;
+  let is_installed = repository
+    .is_mod_installed(mod_id, variant, data_profile)
+    .await?;
 
   Ok(if is_installed {
     ModInstallationStatus::Installed
@@ -1,24 +1,38 @@
+use std::collections::HashSet;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use downloader::progress::Reporter;
-use tokio::fs::create_dir_all;
+use reqwest::Client;
+use tokio::fs::{self, create_dir_all};
+use uuid::Uuid;
 
+use crate::active_release::repository::ActiveReleaseRepository;
 use crate::filesystem::paths::{
-  get_or_create_directory, get_or_create_user_game_data_dir,
-  GetOrCreateDirectoryError, GetUserGameDataDirError,
+  get_mod_install_staging_dir, get_or_create_directory,
+  get_or_create_user_game_data_dir, GetOrCreateDirectoryError,
+  GetUserGameDataDirError,
 };
 use crate::filesystem::utils::{copy_dir_all, CopyDirError};
 use crate::infra::archive::{extract_archive, ExtractionError};
 use crate::infra::download::{DownloadFileError, Downloader};
 use crate::infra::utils::OS;
+use crate::mods::list_all_mods::{
+  get_all_stock_mods, GetAllStockModsError,
+};
+use crate::mods::mod_revision::{
+  get_latest_mod_revision, ModRevision,
+};
+use crate::mods::modinfo::{read_modinfo, ReadModInfoError};
 use crate::mods::repository::installed_mods_repository::{
-  InstalledModsRepository, InstalledModsRepositoryError,
+  InstalledModSource, InstalledModsRepository,
+  InstalledModsRepositoryError,
 };
 use crate::mods::repository::mods_repository::{
   GetThirdPartyModByIdError, ModsRepository,
 };
+use crate::mods::types::ThirdPartyMod;
 use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug)]
@@ -49,37 +63,87 @@ pub enum InstallThirdPartyModError {
 
   #[error("failed to update repository: {0}")]
   UpdateRepository(#[from] InstalledModsRepositoryError),
+
+  #[error("failed to get stock mods: {0}")]
+  GetStockMods(#[from] GetAllStockModsError),
+
+  #[error("failed to resolve mod dependencies: {0}")]
+  Dependency(#[from] ModDependencyError),
+
+  #[error("failed to read modinfo of mod {0}: {1}")]
+  ReadModInfo(String, #[source] ReadModInfoError),
 }
 
-#[allow(clippy::too_many_arguments)]
-pub async fn install_third_party_mod(
-  mod_id: &str,
-  game_variant: &GameVariant,
-  data_dir: &Path,
+#[derive(thiserror::Error, Debug)]
+pub enum ModDependencyError {
+  #[error("mod dependencies form a cycle: {}", .0.join(" -> "))]
+  Cycle(Vec<String>),
+
+  #[error(
+    "mod {mod_id} depends on {dependency}, which is not available"
+  )]
+  Unavailable { mod_id: String, dependency: String },
+}
+
+/// A temporary directory that is removed when dropped, so that it is
+/// cleaned up whether or not the installation succeeds.
+struct ModTempDir(PathBuf);
+
+impl Drop for ModTempDir {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
+
+/// A mod that has been downloaded and extracted, but not yet installed.
+struct DownloadedMod {
+  mod_details: ThirdPartyMod,
+  revision: Option<ModRevision>,
+  // Held until the mod is placed.
+  _mod_temp_dir: ModTempDir,
+  mod_parent_dir: PathBuf,
+  modinfo_version: Option<String>,
+  dependencies: Vec<String>,
+  // The number of dependencies that have been resolved so far.
+  resolved: usize,
+}
+
+async fn download_mod(
+  mod_details: ThirdPartyMod,
   temp_dir: &Path,
   os: &OS,
+  client: &Client,
   downloader: &Downloader,
-  installed_mods_repository: &impl InstalledModsRepository,
-  mods_repository: &impl ModsRepository,
   reporter: Arc<dyn Reporter + Send + Sync>,
-) -> Result<(), InstallThirdPartyModError> {
-  let mod_details = mods_repository
-    .get_third_party_mod_by_id(mod_id, game_variant)
-    .await?;
+) -> Result<DownloadedMod, InstallThirdPartyModError> {
+  let mod_id = &mod_details.id;
+
+  // Looked up before downloading, so that a change published during the
+  // download is offered as an update later. The mod is still installed
+  // if the lookup fails, but updates to it cannot be detected.
+  let revision =
+    match get_latest_mod_revision(&mod_details, client).await {
+      Ok(revision) => revision,
+      Err(e) => {
+        eprintln!("Error getting revision of mod {}: {}", mod_id, e);
+        None
+      }
+    };
 
   let mod_temp_dir =
     temp_dir.join("cat-launcher-mod-install-dir").join(mod_id);
   create_dir_all(&mod_temp_dir).await?;
+  let mod_temp_dir = ModTempDir(mod_temp_dir);
 
   let downloaded_file = downloader
     .download_file(
       &mod_details.installation.download_url,
-      &mod_temp_dir,
+      &mod_temp_dir.0,
       reporter,
     )
     .await?;
 
-  let extraction_dir = mod_temp_dir.join("extracted");
+  let extraction_dir = mod_temp_dir.0.join("extracted");
   create_dir_all(&extraction_dir).await?;
   extract_archive(&downloaded_file, &extraction_dir, os).await?;
 
@@ -88,18 +152,261 @@ pub async fn install_third_party_mod(
     &mod_details.installation.modinfo,
   )?;
 
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let modinfo = read_modinfo(
+    &extraction_dir.join(&mod_details.installation.modinfo),
+  )
+  .await
+  .map_err(|e| {
+    InstallThirdPartyModError::ReadModInfo(mod_id.clone(), e)
+  })?;
+  let (modinfo_version, dependencies) = match modinfo {
+    Some(modinfo) => (modinfo.version, modinfo.dependencies),
+    None => (None, Vec::new()),
+  };
+
+  Ok(DownloadedMod {
+    mod_details,
+    revision,
+    _mod_temp_dir: mod_temp_dir,
+    mod_parent_dir,
+    modinfo_version,
+    dependencies,
+    resolved: 0,
+  })
+}
+
+/// Installs a third-party mod along with the dependencies listed in its
+/// modinfo.json that are not already available.
+///
+/// Dependencies are satisfied by the stock mods of the active release
+/// and by installed third-party mods. Any others are installed from the
+/// cached mod catalogue, before the mods that depend on them. Nothing is
+/// installed unless every dependency can be resolved.
+#[allow(clippy::too_many_arguments)]
+pub async fn install_third_party_mod(
+  mod_id: &str,
+  game_variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+  temp_dir: &Path,
+  os: &OS,
+  timestamp: u64,
+  client: &Client,
+  downloader: &Downloader,
+  installed_mods_repository: &impl InstalledModsRepository,
+  mods_repository: &impl ModsRepository,
+  active_release_repository: &impl ActiveReleaseRepository,
+  reporter: Arc<dyn Reporter + Send + Sync>,
+) -> Result<(), InstallThirdPartyModError> {
+  let mod_details = mods_repository
+    .get_third_party_mod_by_id(mod_id, game_variant)
+    .await?;
+
+  let stock_mod_ids: HashSet<String> = get_all_stock_mods(
+    game_variant,
+    data_dir,
+    os,
+    active_release_repository,
+  )
+  .await?
+  .into_iter()
+  .map(|stock_mod| stock_mod.id)
+  .collect();
+
+  // Mods whose dependencies are still being resolved, each one a
+  // dependency of the mod before it.
+  let mut path = vec![
+    download_mod(
+      mod_details,
+      temp_dir,
+      os,
+      client,
+      downloader,
+      reporter.clone(),
+    )
+    .await?,
+  ];
+  // Mods in the order they are installed, dependencies first.
+  let mut resolved_mods: Vec<DownloadedMod> = Vec::new();
+
+  while let Some(current) = path.last_mut() {
+    let Some(dependency) =
+      current.dependencies.get(current.resolved).cloned()
+    else {
+      let downloaded_mod = path.pop().expect("path is not empty");
+      resolved_mods.push(downloaded_mod);
+      continue;
+    };
+    current.resolved += 1;
+
+    if let Some(position) = path
+      .iter()
+      .position(|downloaded| downloaded.mod_details.id == dependency)
+    {
+      let mut cycle: Vec<String> = path[position..]
+        .iter()
+        .map(|downloaded| downloaded.mod_details.id.clone())
+        .collect();
+      cycle.push(dependency);
+      return Err(ModDependencyError::Cycle(cycle).into());
+    }
+
+    if stock_mod_ids.contains(&dependency)
+      || resolved_mods
+        .iter()
+        .any(|resolved| resolved.mod_details.id == dependency)
+      || installed_mods_repository
+        .is_mod_installed(&dependency, game_variant, data_profile)
+        .await?
+    {
+      continue;
+    }
+
+    let dependency_details = match mods_repository
+      .get_third_party_mod_by_id(&dependency, game_variant)
+      .await
+    {
+      Ok(dependency_details) => dependency_details,
+      // Without an active release the stock mods are unknown, so the
+      // dependency is assumed to be one of them.
+      Err(GetThirdPartyModByIdError::NotFound(..))
+        if stock_mod_ids.is_empty() =>
+      {
+        continue
+      }
+      Err(GetThirdPartyModByIdError::NotFound(..)) => {
+        let current = path.last().expect("path is not empty");
+        return Err(
+          ModDependencyError::Unavailable {
+            mod_id: current.mod_details.id.clone(),
+            dependency,
+          }
+          .into(),
+        );
+      }
+      Err(e) => return Err(e.into()),
+    };
+
+    path.push(
+      download_mod(
+        dependency_details,
+        temp_dir,
+        os,
+        client,
+        downloader,
+        reporter.clone(),
+      )
+      .await?,
+    );
+  }
+
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let mods_dir =
     get_or_create_directory(&user_game_data_dir, "mods").await?;
 
-  let mod_install_dir = mods_dir.join(mod_id);
-  copy_dir_all(&mod_parent_dir, &mod_install_dir, os).await?;
+  for downloaded_mod in resolved_mods {
+    place_mod(
+      downloaded_mod,
+      &mods_dir,
+      game_variant,
+      data_profile,
+      data_dir,
+      os,
+      timestamp,
+      installed_mods_repository,
+    )
+    .await?;
+  }
 
-  let _ = tokio::fs::remove_dir_all(&mod_temp_dir).await;
+  Ok(())
+}
+
+// Moves `target` to `displaced` and `replacement` into its place. The
+// staging directory is on the same file system as the mods directory,
+// so both moves are renames. If the second one fails, the first is
+// reverted.
+async fn replace_mod_dir(
+  target: &Path,
+  replacement: &Path,
+  displaced: &Path,
+) -> io::Result<()> {
+  let existed = fs::try_exists(target).await?;
+  if existed {
+    fs::rename(target, displaced).await?;
+  }
+
+  if let Err(e) = fs::rename(replacement, target).await {
+    if existed {
+      let _ = fs::rename(displaced, target).await;
+    }
+    return Err(e);
+  }
+
+  Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn place_mod(
+  downloaded_mod: DownloadedMod,
+  mods_dir: &Path,
+  game_variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+  os: &OS,
+  timestamp: u64,
+  installed_mods_repository: &impl InstalledModsRepository,
+) -> Result<(), InstallThirdPartyModError> {
+  let mod_id = &downloaded_mod.mod_details.id;
+
+  // The new copy is staged outside the mods directory, so that the game
+  // never sees a partial mod, and files removed upstream are not left
+  // behind. The replaced copy is removed along with the staging
+  // directory.
+  let staging_dir = ModTempDir(
+    get_mod_install_staging_dir(data_dir)
+      .join(Uuid::new_v4().to_string()),
+  );
+  let mod_staging_dir = staging_dir.0.join("mod");
+  copy_dir_all(&downloaded_mod.mod_parent_dir, &mod_staging_dir, os)
+    .await?;
+  replace_mod_dir(
+    &mods_dir.join(mod_id),
+    &mod_staging_dir,
+    &staging_dir.0.join("displaced"),
+  )
+  .await?;
 
   installed_mods_repository
-    .add_installed_mod(mod_id, game_variant)
+    .add_installed_mod(mod_id, game_variant, data_profile)
+    .await?;
+
+  let (commit_sha, etag) = match downloaded_mod.revision {
+    Some(ModRevision::Commit(sha)) => (Some(sha), None),
+    Some(ModRevision::ETag(etag)) => (None, Some(etag)),
+    None => (None, None),
+  };
+  installed_mods_repository
+    .set_installed_mod_source(
+      game_variant,
+      data_profile,
+      &InstalledModSource {
+        mod_id: mod_id.clone(),
+        source_url: downloaded_mod
+          .mod_details
+          .installation
+          .download_url
+          .clone(),
+        commit_sha,
+        etag,
+        installed_at: timestamp,
+        modinfo_version: downloaded_mod.modinfo_version,
+      },
+    )
     .await?;
 
   Ok(())
@@ -1,3 +1,4 @@
+pub mod check_mod_updates;
 pub mod commands;
 pub mod get_last_activity_for_third_party_mod;
 pub mod get_third_party_mod_by_id;
@@ -5,7 +6,10 @@ pub mod get_third_party_mod_installation_status;
 pub mod install_third_party_mod;
 pub mod lib;
 pub mod list_all_mods;
+pub mod mod_revision;
+pub mod modinfo;
 pub mod online;
 pub mod repository;
 pub mod types;
 pub mod uninstall_third_party_mod;
+pub mod update_third_party_mod;
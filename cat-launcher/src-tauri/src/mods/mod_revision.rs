@@ -0,0 +1,139 @@
+use reqwest::header::ETAG;
+use reqwest::{Client, StatusCode};
+use url::Url;
+
+use crate::infra::github::get_last_commit::{
+  get_commit, GetLastCommitError,
+};
+use crate::mods::types::ThirdPartyMod;
+
+/// Identifies the upstream state of a third-party mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModRevision {
+  Commit(String),
+  ETag(String),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetModRevisionError {
+  #[error("failed to get last commit: {0}")]
+  GetLastCommit(#[from] GetLastCommitError),
+
+  #[error("failed to request mod archive: {0}")]
+  Request(#[from] reqwest::Error),
+
+  #[error("mod archive request returned status: {0}")]
+  Status(StatusCode),
+}
+
+/// Returns the repository and ref of a GitHub source archive, such as
+/// `https://github.com/owner/repo/archive/refs/heads/main.zip`.
+fn get_archive_ref(download_url: &str) -> Option<(String, String)> {
+  let url = Url::parse(download_url).ok()?;
+  let parts: Vec<&str> =
+    url.path().trim_start_matches('/').split('/').collect();
+
+  let (owner, repo, reference) =
+    match (url.host_str()?, parts.as_slice()) {
+      ("github.com", [owner, repo, "archive", reference @ ..]) => {
+        let reference = reference.join("/");
+        let reference = reference
+          .strip_suffix(".zip")
+          .or_else(|| reference.strip_suffix(".tar.gz"))?
+          .to_string();
+        (owner, repo, reference)
+      }
+      (
+        "codeload.github.com",
+        [owner, repo, "zip" | "tar.gz", reference @ ..],
+      ) => (owner, repo, reference.join("/")),
+      _ => return None,
+    };
+
+  // The commits API takes branches and tags without the refs/ prefix.
+  let reference = reference
+    .strip_prefix("refs/")
+    .map(str::to_string)
+    .unwrap_or(reference);
+  if owner.is_empty() || repo.is_empty() || reference.is_empty() {
+    return None;
+  }
+
+  Some((format!("{}/{}", owner, repo), reference))
+}
+
+/// Returns the commit that a mod's GitHub source archive currently
+/// points to, or else the ETag of its archive. The commit is looked up
+/// for the ref that is downloaded, not the repository's default branch.
+/// Returns None if the server sends no ETag.
+pub async fn get_latest_mod_revision(
+  mod_details: &ThirdPartyMod,
+  client: &Client,
+) -> Result<Option<ModRevision>, GetModRevisionError> {
+  if let Some((repo, reference)) =
+    get_archive_ref(&mod_details.installation.download_url)
+  {
+    let commit = get_commit(&repo, &reference, client).await?;
+    return Ok(Some(ModRevision::Commit(commit.sha)));
+  }
+
+  let response = client
+    .head(&mod_details.installation.download_url)
+    .send()
+    .await?;
+  if !response.status().is_success() {
+    return Err(GetModRevisionError::Status(response.status()));
+  }
+
+  Ok(
+    response
+      .headers()
+      .get(ETAG)
+      .and_then(|etag| etag.to_str().ok())
+      .map(|etag| ModRevision::ETag(etag.to_string())),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn archive_ref_is_read_from_github_archive_urls() {
+    assert_eq!(
+      get_archive_ref(
+        "https://github.com/owner/repo/archive/refs/heads/main.zip"
+      ),
+      Some(("owner/repo".to_string(), "heads/main".to_string()))
+    );
+    assert_eq!(
+      get_archive_ref(
+        "https://github.com/owner/repo/archive/refs/tags/v1.2.tar.gz"
+      ),
+      Some(("owner/repo".to_string(), "tags/v1.2".to_string()))
+    );
+    assert_eq!(
+      get_archive_ref(
+        "https://github.com/owner/repo/archive/master.zip"
+      ),
+      Some(("owner/repo".to_string(), "master".to_string()))
+    );
+    assert_eq!(
+      get_archive_ref(
+        "https://codeload.github.com/owner/repo/zip/refs/heads/main"
+      ),
+      Some(("owner/repo".to_string(), "heads/main".to_string()))
+    );
+  }
+
+  #[test]
+  fn other_urls_have_no_archive_ref() {
+    assert_eq!(
+      get_archive_ref(
+        "https://github.com/owner/repo/releases/download/v1/mod.zip"
+      ),
+      None
+    );
+    assert_eq!(get_archive_ref("https://example.com/mod.zip"), None);
+  }
+}
@@ -0,0 +1,47 @@
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The `MOD_INFO` entry of a mod's modinfo.json.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModInfo {
+  pub id: String,
+  #[serde(default)]
+  pub version: Option<String>,
+  #[serde(default)]
+  pub dependencies: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReadModInfoError {
+  #[error("failed to read modinfo.json: {0}")]
+  Read(#[from] io::Error),
+
+  #[error("failed to parse modinfo.json: {0}")]
+  Parse(#[from] serde_json::Error),
+}
+
+/// Reads the `MOD_INFO` entry of a modinfo.json, which holds either a
+/// single entry or a list of entries. Returns None if it has none.
+pub async fn read_modinfo(
+  path: &Path,
+) -> Result<Option<ModInfo>, ReadModInfoError> {
+  let content = tokio::fs::read_to_string(path).await?;
+  let entries = match serde_json::from_str(&content)? {
+    Value::Array(entries) => entries,
+    entry => vec![entry],
+  };
+
+  let Some(entry) = entries.into_iter().find(|entry| {
+    entry
+      .get("type")
+      .and_then(Value::as_str)
+      .is_some_and(|ty| ty.eq_ignore_ascii_case("MOD_INFO"))
+  }) else {
+    return Ok(None);
+  };
+
+  Ok(Some(serde_json::from_value(entry)?))
+}
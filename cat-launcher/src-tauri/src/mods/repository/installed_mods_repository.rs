@@ -1,9 +1,27 @@
 use std::error::Error;
 
 use async_trait::async_trait;
+use serde::Serialize;
+use ts_rs::TS;
 
 use crate::variants::GameVariant;
 
+/// Where an installed third-party mod was installed from.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct InstalledModSource {
+  pub mod_id: String,
+  pub source_url: String,
+  /// The latest commit of the mod's GitHub repository at install time.
+  pub commit_sha: Option<String>,
+  /// The ETag of the downloaded archive, for mods not tracked through
+  /// GitHub commits.
+  pub etag: Option<String>,
+  pub installed_at: u64,
+  /// The version from the mod's modinfo.json, if it has one.
+  pub modinfo_version: Option<String>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum InstalledModsRepositoryError {
   #[error("failed to add installed mod: {0}")]
@@ -18,6 +36,12 @@ pub enum InstalledModsRepositoryError {
   #[error("failed to check if mod is installed: {0}")]
   IsInstalled(#[source] Box<dyn Error + Send + Sync>),
 
+  #[error("failed to get installed mod sources: {0}")]
+  GetSources(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to record installed mod source: {0}")]
+  SetSource(#[source] Box<dyn Error + Send + Sync>),
+
   #[error("installed mod with id {0} not found for variant {1}")]
   NotFound(String, String),
 }
@@ -28,22 +52,40 @@ pub trait InstalledModsRepository: Send + Sync {
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError>;
 
   async fn delete_installed_mod(
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError>;
 
   async fn delete_all_installed_mods(
     &self,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError>;
 
   async fn is_mod_installed(
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<bool, InstalledModsRepositoryError>;
+
+  /// Returns the sources of the installed mods that have one.
+  async fn get_installed_mod_sources(
+    &self,
+    game_variant: &GameVariant,
+    data_profile: Option<&str>,
+  ) -> Result<Vec<InstalledModSource>, InstalledModsRepositoryError>;
+
+  async fn set_installed_mod_source(
+    &self,
+    game_variant: &GameVariant,
+    data_profile: Option<&str>,
+    source: &InstalledModSource,
+  ) -> Result<(), InstalledModsRepositoryError>;
 }
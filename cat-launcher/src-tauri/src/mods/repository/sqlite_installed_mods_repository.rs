@@ -3,7 +3,8 @@ use r2d2_sqlite::SqliteConnectionManager;
 
 use crate::infra::repository::db_helper::run_db;
 use crate::mods::repository::installed_mods_repository::{
-  InstalledModsRepository, InstalledModsRepositoryError,
+  InstalledModSource, InstalledModsRepository,
+  InstalledModsRepositoryError,
 };
 use crate::variants::GameVariant;
 
@@ -23,16 +24,24 @@ impl InstalledModsRepository for SqliteInstalledModsRepository {
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError> {
     let pool = self.pool.clone();
     let mod_id = mod_id.to_string();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
     run_db(pool, move |conn| {
-      conn.execute(
-        "INSERT OR IGNORE INTO installed_mods (mod_id, game_variant) VALUES (?1, ?2)",
-        [&mod_id, &variant_name],
-      )?;
+      match data_profile {
+        None => conn.execute(
+          "INSERT OR IGNORE INTO installed_mods (mod_id, game_variant) VALUES (?1, ?2)",
+          [&mod_id, &variant_name],
+        ),
+        Some(profile_name) => conn.execute(
+          "INSERT OR IGNORE INTO data_profile_installed_mods (mod_id, game_variant, profile_name) VALUES (?1, ?2, ?3)",
+          [&mod_id, &variant_name, &profile_name],
+        ),
+      }?;
       Ok::<(), rusqlite::Error>(())
     })
     .await
@@ -43,16 +52,34 @@ impl InstalledModsRepository for SqliteInstalledModsRepository {
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError> {
     let pool = self.pool.clone();
     let mod_id_clone = mod_id.to_string();
     let variant_name_clone = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
-    let rows_affected = run_db(pool, move |conn| {
-      let count = conn.execute(
-        "DELETE FROM installed_mods WHERE mod_id = ?1 AND game_variant = ?2",
-        [&mod_id_clone, &variant_name_clone],
+    let rows_affected = run_db(pool, move |mut conn| {
+      let tx = conn.transaction()?;
+      let count = match &data_profile {
+        None => tx.execute(
+          "DELETE FROM installed_mods WHERE mod_id = ?1 AND game_variant = ?2",
+          [&mod_id_clone, &variant_name_clone],
+        ),
+        Some(profile_name) => tx.execute(
+          "DELETE FROM data_profile_installed_mods WHERE mod_id = ?1 AND game_variant = ?2 AND profile_name = ?3",
+          [&mod_id_clone, &variant_name_clone, profile_name],
+        ),
+      }?;
+      tx.execute(
+        "DELETE FROM installed_mod_sources WHERE mod_id = ?1 AND game_variant = ?2 AND data_profile = ?3",
+        [
+          &mod_id_clone,
+          &variant_name_clone,
+          &data_profile.unwrap_or_default(),
+        ],
       )?;
+      tx.commit()?;
       Ok::<usize, rusqlite::Error>(count)
     })
     .await
@@ -71,16 +98,29 @@ impl InstalledModsRepository for SqliteInstalledModsRepository {
   async fn delete_all_installed_mods(
     &self,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledModsRepositoryError> {
     let pool = self.pool.clone();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
-    run_db(pool, move |conn| {
-      conn.execute(
-        "DELETE FROM installed_mods WHERE game_variant = ?1",
-        [&variant_name],
+    run_db(pool, move |mut conn| {
+      let tx = conn.transaction()?;
+      match &data_profile {
+        None => tx.execute(
+          "DELETE FROM installed_mods WHERE game_variant = ?1",
+          [&variant_name],
+        ),
+        Some(profile_name) => tx.execute(
+          "DELETE FROM data_profile_installed_mods WHERE game_variant = ?1 AND profile_name = ?2",
+          [&variant_name, profile_name],
+        ),
+      }?;
+      tx.execute(
+        "DELETE FROM installed_mod_sources WHERE game_variant = ?1 AND data_profile = ?2",
+        [&variant_name, &data_profile.unwrap_or_default()],
       )?;
-      Ok::<(), rusqlite::Error>(())
+      tx.commit()
     })
     .await
     .map_err(InstalledModsRepositoryError::DeleteAll)
@@ -90,18 +130,102 @@ impl InstalledModsRepository for SqliteInstalledModsRepository {
     &self,
     mod_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<bool, InstalledModsRepositoryError> {
     let pool = self.pool.clone();
     let mod_id = mod_id.to_string();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
+
+    run_db(pool, move |conn| match data_profile {
+      None => {
+        let mut stmt = conn.prepare(
+          "SELECT 1 FROM installed_mods WHERE mod_id = ?1 AND game_variant = ?2",
+        )?;
+        stmt.exists([&mod_id, &variant_name])
+      }
+      Some(profile_name) => {
+        let mut stmt = conn.prepare(
+          "SELECT 1 FROM data_profile_installed_mods WHERE mod_id = ?1 AND game_variant = ?2 AND profile_name = ?3",
+        )?;
+        stmt.exists([&mod_id, &variant_name, &profile_name])
+      }
+    })
+    .await
+    .map_err(InstalledModsRepositoryError::IsInstalled)
+  }
+
+  async fn get_installed_mod_sources(
+    &self,
+    game_variant: &GameVariant,
+    data_profile: Option<&str>,
+  ) -> Result<Vec<InstalledModSource>, InstalledModsRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
     run_db(pool, move |conn| {
-      let mut stmt = conn.prepare(
-        "SELECT 1 FROM installed_mods WHERE mod_id = ?1 AND game_variant = ?2",
+      // Sources are kept when a mod is removed by deleting its data
+      // profile, so only those of installed mods are returned.
+      let (query, params) = match data_profile {
+        None => (
+          "SELECT s.mod_id, s.source_url, s.commit_sha, s.etag, s.installed_at, s.modinfo_version FROM installed_mod_sources s JOIN installed_mods m ON m.mod_id = s.mod_id AND m.game_variant = s.game_variant WHERE s.game_variant = ?1 AND s.data_profile = ?2 ORDER BY s.mod_id",
+          [variant_name, String::new()],
+        ),
+        Some(profile_name) => (
+          "SELECT s.mod_id, s.source_url, s.commit_sha, s.etag, s.installed_at, s.modinfo_version FROM installed_mod_sources s JOIN data_profile_installed_mods m ON m.mod_id = s.mod_id AND m.game_variant = s.game_variant AND m.profile_name = s.data_profile WHERE s.game_variant = ?1 AND s.data_profile = ?2 ORDER BY s.mod_id",
+          [variant_name, profile_name],
+        ),
+      };
+
+      let mut stmt = conn.prepare(query)?;
+      let sources = stmt
+        .query_map(params, |row| {
+          Ok(InstalledModSource {
+            mod_id: row.get(0)?,
+            source_url: row.get(1)?,
+            commit_sha: row.get(2)?,
+            etag: row.get(3)?,
+            installed_at: row.get(4)?,
+            modinfo_version: row.get(5)?,
+          })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<InstalledModSource>, rusqlite::Error>(sources)
+    })
+    .await
+    .map_err(InstalledModsRepositoryError::GetSources)
+  }
+
+  async fn set_installed_mod_source(
+    &self,
+    game_variant: &GameVariant,
+    data_profile: Option<&str>,
+    source: &InstalledModSource,
+  ) -> Result<(), InstalledModsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let source = source.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO installed_mod_sources (mod_id, game_variant, data_profile, source_url, commit_sha, etag, installed_at, modinfo_version) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+          source.mod_id,
+          variant_name,
+          data_profile,
+          source.source_url,
+          source.commit_sha,
+          source.etag,
+          source.installed_at,
+          source.modinfo_version
+        ],
       )?;
-      stmt.exists([&mod_id, &variant_name])
+      Ok::<(), rusqlite::Error>(())
     })
     .await
-    .map_err(InstalledModsRepositoryError::IsInstalled)
+    .map_err(InstalledModsRepositoryError::SetSource)
   }
 }
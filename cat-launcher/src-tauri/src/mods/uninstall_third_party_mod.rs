@@ -1,16 +1,34 @@
 use std::io;
 use std::path::Path;
 
+use tokio::task::JoinError;
+
+use crate::active_release::repository::ActiveReleaseRepository;
+use crate::backups::scopes::BackupScope;
 use crate::filesystem::paths::{
   get_or_create_user_game_data_dir, GetUserGameDataDirError,
 };
+use crate::manual_backups::manual_backups::{
+  create_manual_backup_with_scopes, CreateManualBackupError,
+};
+use crate::manual_backups::repository::manual_backup_repository::ManualBackupRepository;
 use crate::mods::repository::installed_mods_repository::{
   InstalledModsRepository, InstalledModsRepositoryError,
 };
+use crate::save_sync::world_files::list_world_names;
 use crate::variants::GameVariant;
+use crate::worlds::worlds::{get_save_dir, read_mods};
 
 #[derive(thiserror::Error, Debug)]
 pub enum UninstallThirdPartyModError {
+  #[error("mod {mod_id} is used by worlds: {}", .worlds.join(", "))]
+  UsedByWorlds { mod_id: String, worlds: Vec<String> },
+  #[error("failed to read worlds: {0}")]
+  Worlds(#[source] io::Error),
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+  #[error("failed to back up worlds: {0}")]
+  Backup(#[from] CreateManualBackupError),
   #[error("failed to remove installed mod from repository: {0}")]
   Repository(#[from] InstalledModsRepositoryError),
   #[error("failed to get user game data directory: {0}")]
@@ -19,22 +37,87 @@ pub enum UninstallThirdPartyModError {
   DeleteModDirectory(#[from] io::Error),
 }
 
+/// Uninstalls a third-party mod. Worlds whose mod list includes the mod
+/// would fail to load without it, so unless `force` is set the mod is
+/// kept and the worlds are returned in an error. Otherwise the saves are
+/// backed up before the mod is removed.
+///
+/// Returns the id of the manual backup, if one was taken.
+#[allow(clippy::too_many_arguments)]
 pub async fn uninstall_third_party_mod(
   mod_id: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
+  force: bool,
+  timestamp: u64,
   repository: &impl InstalledModsRepository,
-) -> Result<(), UninstallThirdPartyModError> {
+  backup_repository: &impl ManualBackupRepository,
+  active_release_repository: &dyn ActiveReleaseRepository,
+) -> Result<Option<i64>, UninstallThirdPartyModError> {
+  // Only the mod lists are read, so that worlds with unreadable saves do
+  // not keep the mod from being uninstalled.
+  let save_dir = get_save_dir(game_variant, data_profile, data_dir);
+  let world_names = list_world_names(&save_dir)
+    .await
+    .map_err(UninstallThirdPartyModError::Worlds)?;
+  let used_mod_id = mod_id.to_string();
+  let affected_worlds: Vec<String> =
+    tokio::task::spawn_blocking(move || {
+      world_names
+        .into_iter()
+        .filter(|world| {
+          read_mods(&save_dir.join(world)).contains(&used_mod_id)
+        })
+        .collect()
+    })
+    .await?;
+
+  let mut backup_id = None;
+  if !affected_worlds.is_empty() {
+    if !force {
+      return Err(UninstallThirdPartyModError::UsedByWorlds {
+        mod_id: mod_id.to_string(),
+        worlds: affected_worlds,
+      });
+    }
+
+    // Manual backups cannot be limited to some worlds, so the whole save
+    // directory is backed up.
+    backup_id = Some(
+      create_manual_backup_with_scopes(
+        &format!("before-uninstalling-{}", mod_id),
+        game_variant,
+        data_profile,
+        Some(format!(
+          "Taken before uninstalling {}, which is used by: {}",
+          mod_id,
+          affected_worlds.join(", ")
+        )),
+        data_dir,
+        timestamp,
+        &[BackupScope::Save],
+        backup_repository,
+        active_release_repository,
+      )
+      .await?,
+    );
+  }
+
   // Remove from repository
   repository
-    .delete_installed_mod(mod_id, game_variant)
+    .delete_installed_mod(mod_id, game_variant, data_profile)
     .await?;
 
   // Delete mod directory
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let mod_dir = user_game_data_dir.join("mods").join(mod_id);
   tokio::fs::remove_dir_all(&mod_dir).await?;
 
-  Ok(())
+  Ok(backup_id)
 }
@@ -0,0 +1,76 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use downloader::progress::Reporter;
+use reqwest::Client;
+
+use crate::active_release::repository::ActiveReleaseRepository;
+use crate::infra::download::Downloader;
+use crate::infra::utils::OS;
+use crate::mods::install_third_party_mod::{
+  install_third_party_mod, InstallThirdPartyModError,
+};
+use crate::mods::repository::installed_mods_repository::{
+  InstalledModsRepository, InstalledModsRepositoryError,
+};
+use crate::mods::repository::mods_repository::ModsRepository;
+use crate::variants::GameVariant;
+
+#[derive(thiserror::Error, Debug)]
+pub enum UpdateThirdPartyModError {
+  #[error("mod {0} is not installed")]
+  NotInstalled(String),
+
+  #[error("failed to check if mod is installed: {0}")]
+  Repository(#[from] InstalledModsRepositoryError),
+
+  #[error("failed to install mod: {0}")]
+  Install(#[from] InstallThirdPartyModError),
+}
+
+/// Reinstalls an installed mod from its latest upstream revision,
+/// replacing the installed files.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_third_party_mod(
+  mod_id: &str,
+  game_variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+  temp_dir: &Path,
+  os: &OS,
+  timestamp: u64,
+  client: &Client,
+  downloader: &Downloader,
+  installed_mods_repository: &impl InstalledModsRepository,
+  mods_repository: &impl ModsRepository,
+  active_release_repository: &impl ActiveReleaseRepository,
+  reporter: Arc<dyn Reporter + Send + Sync>,
+) -> Result<(), UpdateThirdPartyModError> {
+  if !installed_mods_repository
+    .is_mod_installed(mod_id, game_variant, data_profile)
+    .await?
+  {
+    return Err(UpdateThirdPartyModError::NotInstalled(
+      mod_id.to_string(),
+    ));
+  }
+
+  install_third_party_mod(
+    mod_id,
+    game_variant,
+    data_profile,
+    data_dir,
+    temp_dir,
+    os,
+    timestamp,
+    client,
+    downloader,
+    installed_mods_repository,
+    mods_repository,
+    active_release_repository,
+    reporter,
+  )
+  .await?;
+
+  Ok(())
+}
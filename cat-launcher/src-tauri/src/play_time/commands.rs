@@ -6,9 +6,12 @@ use cat_macros::CommandErrorSerialize;
 use crate::play_time::play_time::{
   get_play_time_for_variant as get_play_time_for_variant_feature,
   get_play_time_for_version as get_play_time_for_version_feature,
+  list_play_sessions as list_play_sessions_feature,
   log_play_time as log_play_time_feature,
 };
-use crate::play_time::repository::PlayTimeRepositoryError;
+use crate::play_time::repository::{
+  PlaySession, PlayTimeRepositoryError,
+};
 use crate::play_time::sqlite_play_time_repository::SqlitePlayTimeRepository;
 use crate::variants::game_variant::GameVariant;
 
@@ -69,3 +72,16 @@ pub async fn log_play_time(
   .await?;
   Ok(())
 }
+
+#[tauri::command]
+pub async fn list_play_sessions(
+  variant: GameVariant,
+  from: Option<u64>,
+  to: Option<u64>,
+  repository: State<'_, SqlitePlayTimeRepository>,
+) -> Result<Vec<PlaySession>, GetPlayTimeCommandError> {
+  let result =
+    list_play_sessions_feature(&variant, from, to, &*repository)
+      .await?;
+  Ok(result)
+}
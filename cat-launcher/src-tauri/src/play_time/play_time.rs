@@ -1,5 +1,5 @@
 use crate::play_time::repository::{
-  PlayTimeRepository, PlayTimeRepositoryError,
+  PlaySession, PlayTimeRepository, PlayTimeRepositoryError,
 };
 use crate::variants::game_variant::GameVariant;
 
@@ -32,3 +32,35 @@ pub async fn log_play_time(
     .log_play_time(game_variant, version, duration_in_seconds)
     .await
 }
+
+pub async fn record_play_session(
+  game_variant: &GameVariant,
+  version: &str,
+  world: Option<&str>,
+  started_at: u64,
+  ended_at: u64,
+  exit_code: Option<i32>,
+  play_time_repository: &impl PlayTimeRepository,
+) -> Result<i64, PlayTimeRepositoryError> {
+  play_time_repository
+    .record_play_session(
+      game_variant,
+      version,
+      world,
+      started_at,
+      ended_at,
+      exit_code,
+    )
+    .await
+}
+
+pub async fn list_play_sessions(
+  game_variant: &GameVariant,
+  from: Option<u64>,
+  to: Option<u64>,
+  play_time_repository: &impl PlayTimeRepository,
+) -> Result<Vec<PlaySession>, PlayTimeRepositoryError> {
+  play_time_repository
+    .get_play_sessions(game_variant, from, to)
+    .await
+}
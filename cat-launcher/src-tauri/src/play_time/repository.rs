@@ -1,7 +1,21 @@
 use async_trait::async_trait;
+use serde::Serialize;
+use ts_rs::TS;
 
 use crate::variants::GameVariant;
 
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct PlaySession {
+  pub id: i64,
+  pub game_variant: GameVariant,
+  pub version: String,
+  pub world: Option<String>,
+  pub started_at: u64,
+  pub ended_at: u64,
+  pub exit_code: Option<i32>,
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum PlayTimeRepositoryError {
   #[error("Failed to log play time: {0}")]
@@ -16,6 +30,12 @@ pub enum PlayTimeRepositoryError {
   #[error("Failed to get total play time: {0}")]
   GetTotalPlayTime(Box<dyn std::error::Error + Send + Sync>),
 
+  #[error("Failed to record play session: {0}")]
+  RecordPlaySession(Box<dyn std::error::Error + Send + Sync>),
+
+  #[error("Failed to get play sessions: {0}")]
+  GetPlaySessions(Box<dyn std::error::Error + Send + Sync>),
+
   #[error("Task join error: {0}")]
   JoinError(Box<dyn std::error::Error + Send + Sync>),
 
@@ -42,4 +62,25 @@ pub trait PlayTimeRepository: Send + Sync {
     &self,
     game_variant: &GameVariant,
   ) -> Result<i64, PlayTimeRepositoryError>;
+
+  /// Stores a finished session and adds its duration to the play time
+  /// of its version.
+  async fn record_play_session(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+    world: Option<&str>,
+    started_at: u64,
+    ended_at: u64,
+    exit_code: Option<i32>,
+  ) -> Result<i64, PlayTimeRepositoryError>;
+
+  /// Returns the sessions that started within `[from, to)`, newest first.
+  /// A missing bound leaves that side of the range open.
+  async fn get_play_sessions(
+    &self,
+    game_variant: &GameVariant,
+    from: Option<u64>,
+    to: Option<u64>,
+  ) -> Result<Vec<PlaySession>, PlayTimeRepositoryError>;
 }
@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
 use r2d2::Pool;
 use r2d2_sqlite::SqliteConnectionManager;
@@ -5,7 +7,7 @@ use rusqlite::OptionalExtension;
 use tokio::task;
 
 use crate::play_time::repository::{
-  PlayTimeRepository, PlayTimeRepositoryError,
+  PlaySession, PlayTimeRepository, PlayTimeRepositoryError,
 };
 use crate::variants::GameVariant;
 
@@ -101,4 +103,94 @@ impl PlayTimeRepository for SqlitePlayTimeRepository {
         .await
         .map_err(|e| PlayTimeRepositoryError::JoinError(Box::new(e)))?
   }
+
+  async fn record_play_session(
+    &self,
+    game_variant: &GameVariant,
+    version: &str,
+    world: Option<&str>,
+    started_at: u64,
+    ended_at: u64,
+    exit_code: Option<i32>,
+  ) -> Result<i64, PlayTimeRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant_id = game_variant.id();
+    let version = version.to_owned();
+    let world = world.map(str::to_owned);
+    let duration_in_seconds = ended_at.saturating_sub(started_at);
+    task::spawn_blocking(move || {
+            let mut conn = pool
+                .get()
+                .map_err(|e| PlayTimeRepositoryError::RecordPlaySession(Box::new(e)))?;
+            let tx = conn
+                .transaction()
+                .map_err(|e| PlayTimeRepositoryError::RecordPlaySession(Box::new(e)))?;
+            let id: i64 = tx
+                .query_row(
+                    "INSERT INTO play_sessions (game_variant, version, world, started_at, ended_at, exit_code)
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+                    rusqlite::params![game_variant_id, version, world, started_at, ended_at, exit_code],
+                    |row| row.get(0),
+                )
+                .map_err(|e| PlayTimeRepositoryError::RecordPlaySession(Box::new(e)))?;
+            tx.execute(
+                "INSERT INTO play_time (game_variant, version, duration_in_seconds)
+                    VALUES (?1, ?2, ?3)
+                    ON CONFLICT(game_variant, version)
+                    DO UPDATE SET duration_in_seconds = duration_in_seconds + excluded.duration_in_seconds",
+                rusqlite::params![game_variant_id, version, duration_in_seconds],
+            )
+            .map_err(|e| PlayTimeRepositoryError::RecordPlaySession(Box::new(e)))?;
+            tx.commit()
+                .map_err(|e| PlayTimeRepositoryError::RecordPlaySession(Box::new(e)))?;
+            Ok(id)
+        })
+        .await
+        .map_err(|e| PlayTimeRepositoryError::JoinError(Box::new(e)))?
+  }
+
+  async fn get_play_sessions(
+    &self,
+    game_variant: &GameVariant,
+    from: Option<u64>,
+    to: Option<u64>,
+  ) -> Result<Vec<PlaySession>, PlayTimeRepositoryError> {
+    let pool = self.pool.clone();
+    let game_variant_id = game_variant.id();
+    task::spawn_blocking(move || {
+            let conn = pool
+                .get()
+                .map_err(|e| PlayTimeRepositoryError::GetPlaySessions(Box::new(e)))?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, game_variant, version, world, started_at, ended_at, exit_code FROM play_sessions
+                        WHERE game_variant = ?1
+                        AND (?2 IS NULL OR started_at >= ?2)
+                        AND (?3 IS NULL OR started_at < ?3)
+                        ORDER BY started_at DESC, id DESC",
+                )
+                .map_err(|e| PlayTimeRepositoryError::GetPlaySessions(Box::new(e)))?;
+            let sessions = stmt
+                .query_map(rusqlite::params![game_variant_id, from, to], |row| {
+                    let game_variant_str: String = row.get(1)?;
+                    let game_variant = GameVariant::from_str(&game_variant_str)
+                        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, Box::new(e)))?;
+                    Ok(PlaySession {
+                        id: row.get(0)?,
+                        game_variant,
+                        version: row.get(2)?,
+                        world: row.get(3)?,
+                        started_at: row.get(4)?,
+                        ended_at: row.get(5)?,
+                        exit_code: row.get(6)?,
+                    })
+                })
+                .map_err(|e| PlayTimeRepositoryError::GetPlaySessions(Box::new(e)))?
+                .collect::<Result<Vec<PlaySession>, _>>()
+                .map_err(|e| PlayTimeRepositoryError::GetPlaySessions(Box::new(e)))?;
+            Ok(sessions)
+        })
+        .await
+        .map_err(|e| PlayTimeRepositoryError::JoinError(Box::new(e)))?
+  }
 }
@@ -0,0 +1,34 @@
+use strum::IntoStaticStr;
+use tauri::{command, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::running_games::running_games::{
+  RunningGame, RunningGamesError, RunningGamesRegistry,
+};
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum RunningGamesCommandError {
+  #[error("running game operation failed: {0}")]
+  RunningGames(#[from] RunningGamesError),
+}
+
+#[command]
+pub async fn list_running_games(
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<Vec<RunningGame>, RunningGamesCommandError> {
+  Ok(running_games.list())
+}
+
+/// Asks the game to exit. It is killed if it has not exited after a grace
+/// period, and its exit is reported through the usual game events.
+#[command]
+pub async fn terminate_game(
+  id: u64,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), RunningGamesCommandError> {
+  running_games.request_termination(id)?;
+  Ok(())
+}
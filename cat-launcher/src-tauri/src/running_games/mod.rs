@@ -0,0 +1,4 @@
+#[allow(clippy::module_inception)]
+pub mod running_games;
+
+pub mod commands;
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use serde::Serialize;
+use tokio::process::{Child, Command};
+use tokio::sync::oneshot;
+use ts_rs::TS;
+
+use crate::constants::GRACEFUL_EXIT_TIMEOUT;
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct RunningGame {
+  pub id: u64,
+  pub game_variant: GameVariant,
+  pub data_profile: Option<String>,
+  pub release_version: String,
+  pub world: Option<String>,
+  pub pid: Option<u32>,
+  pub started_at: u64,
+}
+
+struct RunningGameEntry {
+  game: RunningGame,
+  terminate: Option<oneshot::Sender<()>>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RunningGamesError {
+  #[error(
+    "{0} is already running against the same user data directory"
+  )]
+  AlreadyRunning(GameVariant),
+
+  #[error("no running game with id {0}")]
+  NotRunning(u64),
+
+  #[error("game with id {0} is already being terminated")]
+  AlreadyTerminating(u64),
+}
+
+/// Tracks the game processes spawned by the launcher.
+#[derive(Clone, Default)]
+pub struct RunningGamesRegistry {
+  next_id: Arc<AtomicU64>,
+  games: Arc<Mutex<HashMap<u64, RunningGameEntry>>>,
+}
+
+/// Keeps a game registered until it is dropped.
+pub struct RunningGameGuard {
+  id: u64,
+  registry: RunningGamesRegistry,
+}
+
+impl RunningGameGuard {
+  pub fn set_pid(&self, pid: Option<u32>) {
+    if let Some(entry) = self.registry.lock_games().get_mut(&self.id)
+    {
+      entry.game.pid = pid;
+    }
+  }
+}
+
+impl Drop for RunningGameGuard {
+  fn drop(&mut self) {
+    self.registry.lock_games().remove(&self.id);
+  }
+}
+
+pub struct RunningGameRegistration {
+  pub guard: RunningGameGuard,
+  /// Whether another game was already running against the same user
+  /// data directory.
+  pub duplicate: bool,
+  /// Resolves when the game was asked to terminate.
+  pub terminate: oneshot::Receiver<()>,
+}
+
+impl RunningGamesRegistry {
+  // The registry only holds plain data, so it is still consistent if a
+  // thread panicked while holding the lock.
+  fn lock_games(
+    &self,
+  ) -> MutexGuard<'_, HashMap<u64, RunningGameEntry>> {
+    self.games.lock().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// Registers a game before it is spawned, so that concurrent launches
+  /// of the same variant and data profile see each other.
+  pub fn register(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    release_version: &str,
+    world: Option<&str>,
+    started_at: u64,
+    allow_duplicate: bool,
+  ) -> Result<RunningGameRegistration, RunningGamesError> {
+    let mut games = self.lock_games();
+
+    let duplicate = games.values().any(|entry| {
+      entry.game.game_variant == *variant
+        && entry.game.data_profile.as_deref() == data_profile
+    });
+    if duplicate && !allow_duplicate {
+      return Err(RunningGamesError::AlreadyRunning(*variant));
+    }
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let (terminate_tx, terminate_rx) = oneshot::channel();
+
+    let entry = RunningGameEntry {
+      game: RunningGame {
+        id,
+        game_variant: *variant,
+        data_profile: data_profile.map(str::to_string),
+        release_version: release_version.to_string(),
+        world: world.map(str::to_string),
+        pid: None,
+        started_at,
+      },
+      terminate: Some(terminate_tx),
+    };
+    games.insert(id, entry);
+
+    Ok(RunningGameRegistration {
+      guard: RunningGameGuard {
+        id,
+        registry: self.clone(),
+      },
+      duplicate,
+      terminate: terminate_rx,
+    })
+  }
+
+  pub fn list(&self) -> Vec<RunningGame> {
+    let games = self.lock_games();
+    let mut running_games: Vec<RunningGame> =
+      games.values().map(|entry| entry.game.clone()).collect();
+    running_games.sort_by_key(|game| game.id);
+    running_games
+  }
+
+  /// Whether a game is running against the user data directory of a
+  /// variant and data profile.
+  pub fn is_running(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+  ) -> bool {
+    self.list().iter().any(|game| {
+      game.game_variant == *variant
+        && game.data_profile.as_deref() == data_profile
+    })
+  }
+
+  pub fn request_termination(
+    &self,
+    id: u64,
+  ) -> Result<(), RunningGamesError> {
+    let mut games = self.lock_games();
+    let entry = games
+      .get_mut(&id)
+      .ok_or(RunningGamesError::NotRunning(id))?;
+    let terminate = entry
+      .terminate
+      .take()
+      .ok_or(RunningGamesError::AlreadyTerminating(id))?;
+
+    // The game may have exited in the meantime, which is fine.
+    let _ = terminate.send(());
+    Ok(())
+  }
+}
+
+/// Makes the game the leader of its own process group on Unix, so that
+/// it can be terminated along with the processes that a wrapper or
+/// launcher script starts.
+pub fn isolate_process_tree(command: &mut Command) {
+  #[cfg(unix)]
+  command.process_group(0);
+
+  #[cfg(not(unix))]
+  let _ = command;
+}
+
+// Signals the game and the processes it started. Without `force`, they
+// are asked to exit.
+async fn signal_process_tree(
+  pid: u32,
+  force: bool,
+) -> io::Result<()> {
+  // The game leads its own process group, whose id is its pid.
+  #[cfg(unix)]
+  let mut command = {
+    let mut command = Command::new("kill");
+    command
+      .arg(if force { "-KILL" } else { "-TERM" })
+      .arg("--")
+      .arg(format!("-{}", pid));
+    command
+  };
+
+  // Without /F, taskkill asks the windows of the processes to close.
+  #[cfg(windows)]
+  let mut command = {
+    let mut command = Command::new("taskkill");
+    if force {
+      command.arg("/F");
+    }
+    command.arg("/T").arg("/PID").arg(pid.to_string());
+    command
+  };
+
+  command
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .await?;
+
+  Ok(())
+}
+
+/// Asks the game and the processes it started to exit, and kills them
+/// if the game is still running after `GRACEFUL_EXIT_TIMEOUT`.
+pub async fn terminate_child(
+  child: &mut Child,
+) -> io::Result<ExitStatus> {
+  let pid = child.id();
+  if let Some(pid) = pid {
+    if let Err(e) = signal_process_tree(pid, false).await {
+      eprintln!("Error asking game to exit: {}", e);
+    }
+  }
+
+  match tokio::time::timeout(GRACEFUL_EXIT_TIMEOUT, child.wait())
+    .await
+  {
+    Ok(status) => status,
+    Err(_) => {
+      if let Some(pid) = pid {
+        if let Err(e) = signal_process_tree(pid, true).await {
+          eprintln!("Error killing game: {}", e);
+        }
+      }
+      child.kill().await?;
+      child.wait().await
+    }
+  }
+}
+
+/// Terminates the processes that the game with `pid` started, once the
+/// game itself has exited. A wrapper or launcher script can exit before
+/// the game it started. `exited` resolves once those processes have
+/// exited. They are killed if it has not resolved after
+/// `GRACEFUL_EXIT_TIMEOUT`.
+pub async fn terminate_process_tree(
+  pid: u32,
+  exited: impl Future<Output = ()>,
+) {
+  if let Err(e) = signal_process_tree(pid, false).await {
+    eprintln!("Error asking game to exit: {}", e);
+  }
+
+  tokio::pin!(exited);
+  if tokio::time::timeout(GRACEFUL_EXIT_TIMEOUT, &mut exited)
+    .await
+    .is_err()
+  {
+    if let Err(e) = signal_process_tree(pid, true).await {
+      eprintln!("Error killing game: {}", e);
+    }
+    exited.await;
+  }
+}
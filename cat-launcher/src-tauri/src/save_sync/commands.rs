@@ -0,0 +1,121 @@
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::backup_targets::backup_targets::redact_backup_target_config;
+use crate::backup_targets::repository::BackupTargetConfig;
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::save_sync::repository::sqlite_save_sync_repository::SqliteSaveSyncRepository;
+use crate::save_sync::repository::{
+  SaveSyncRepository, SaveSyncRepositoryError,
+};
+use crate::save_sync::save_sync::{
+  resolve_save_sync_conflict as resolve_save_sync_conflict_impl,
+  set_save_sync_remote as set_save_sync_remote_impl,
+  sync_saves as sync_saves_impl, SaveSyncError, SaveSyncReport,
+  SaveSyncResolution, SetSaveSyncRemoteError,
+};
+use crate::users::repository::sqlite_users_repository::SqliteUsersRepository;
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum SaveSyncRemoteCommandError {
+  #[error("failed to access save sync remote: {0}")]
+  Repository(#[from] SaveSyncRepositoryError),
+
+  #[error("failed to set save sync remote: {0}")]
+  Set(#[from] SetSaveSyncRemoteError),
+}
+
+#[command]
+pub async fn get_save_sync_remote(
+  repository: State<'_, SqliteSaveSyncRepository>,
+) -> Result<Option<BackupTargetConfig>, SaveSyncRemoteCommandError> {
+  let config = repository.get_remote().await?;
+  Ok(config.map(redact_backup_target_config))
+}
+
+#[command]
+pub async fn set_save_sync_remote(
+  config: Option<BackupTargetConfig>,
+  repository: State<'_, SqliteSaveSyncRepository>,
+) -> Result<(), SaveSyncRemoteCommandError> {
+  set_save_sync_remote_impl(config, &*repository).await?;
+  Ok(())
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum SaveSyncCommandError {
+  #[error("failed to get data directory: {0}")]
+  DataDir(#[from] tauri::Error),
+
+  #[error("failed to sync saves: {0}")]
+  Sync(#[from] SaveSyncError),
+
+  #[error("unsupported OS: {0}")]
+  UnsupportedOS(#[from] OSNotSupportedError),
+}
+
+#[command]
+pub async fn sync_saves(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  repository: State<'_, SqliteSaveSyncRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  users_repository: State<'_, SqliteUsersRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<SaveSyncReport, SaveSyncCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+
+  let report = sync_saves_impl(
+    &variant,
+    &data_dir,
+    &os,
+    &*repository,
+    &*data_profiles_repository,
+    &*users_repository,
+    &running_games,
+  )
+  .await?;
+
+  Ok(report)
+}
+
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn resolve_save_sync_conflict(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  world: String,
+  resolution: SaveSyncResolution,
+  repository: State<'_, SqliteSaveSyncRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  users_repository: State<'_, SqliteUsersRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), SaveSyncCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+
+  resolve_save_sync_conflict_impl(
+    &variant,
+    &world,
+    resolution,
+    &data_dir,
+    &os,
+    &*repository,
+    &*data_profiles_repository,
+    &*users_repository,
+    &running_games,
+  )
+  .await?;
+
+  Ok(())
+}
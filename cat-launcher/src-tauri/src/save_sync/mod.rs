@@ -0,0 +1,7 @@
+#[allow(clippy::module_inception)]
+pub mod save_sync;
+
+pub mod commands;
+pub mod repository;
+pub mod version_vector;
+pub mod world_files;
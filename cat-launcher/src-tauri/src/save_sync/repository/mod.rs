@@ -0,0 +1,6 @@
+pub mod save_sync_repository;
+pub mod sqlite_save_sync_repository;
+
+pub use save_sync_repository::{
+  SaveSyncRepository, SaveSyncRepositoryError, WorldSyncState,
+};
@@ -0,0 +1,69 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use crate::backup_targets::repository::BackupTargetConfig;
+use crate::save_sync::version_vector::VersionVector;
+use crate::variants::GameVariant;
+
+/// A world as it was on both sides after it was last synced.
+#[derive(Debug, Clone)]
+pub struct WorldSyncState {
+  pub world: String,
+  pub version_vector: VersionVector,
+  /// The SHA-256 of the world's files.
+  pub sha256: String,
+  pub synced_at: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveSyncRepositoryError {
+  #[error("failed to get save sync remote: {0}")]
+  GetRemote(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set save sync remote: {0}")]
+  SetRemote(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get world sync states: {0}")]
+  GetWorldStates(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to record world sync state: {0}")]
+  SetWorldState(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete world sync state: {0}")]
+  DeleteWorldState(#[source] Box<dyn Error + Send + Sync>),
+}
+
+#[async_trait]
+pub trait SaveSyncRepository: Send + Sync {
+  async fn get_remote(
+    &self,
+  ) -> Result<Option<BackupTargetConfig>, SaveSyncRepositoryError>;
+
+  /// Replaces the remote, or removes it if `config` is None. The sync
+  /// states of every world are forgotten.
+  async fn set_remote(
+    &self,
+    config: Option<&BackupTargetConfig>,
+  ) -> Result<(), SaveSyncRepositoryError>;
+
+  async fn get_world_states(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+  ) -> Result<Vec<WorldSyncState>, SaveSyncRepositoryError>;
+
+  async fn set_world_state(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    state: &WorldSyncState,
+  ) -> Result<(), SaveSyncRepositoryError>;
+
+  async fn delete_world_state(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+  ) -> Result<(), SaveSyncRepositoryError>;
+}
@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::OptionalExtension;
+
+use crate::backup_targets::repository::BackupTargetConfig;
+use crate::infra::repository::db_helper::run_db;
+use crate::save_sync::repository::{
+  SaveSyncRepository, SaveSyncRepositoryError, WorldSyncState,
+};
+use crate::variants::GameVariant;
+
+#[derive(Clone)]
+pub struct SqliteSaveSyncRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteSaveSyncRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+fn to_json_error(
+  index: usize,
+  e: serde_json::Error,
+) -> rusqlite::Error {
+  rusqlite::Error::FromSqlConversionFailure(
+    index,
+    Type::Text,
+    Box::new(e),
+  )
+}
+
+#[async_trait]
+impl SaveSyncRepository for SqliteSaveSyncRepository {
+  async fn get_remote(
+    &self,
+  ) -> Result<Option<BackupTargetConfig>, SaveSyncRepositoryError> {
+    let pool = self.pool.clone();
+
+    run_db(pool, move |conn| {
+      let config: Option<String> = conn
+        .query_row(
+          "SELECT config FROM save_sync_remote WHERE id = 1",
+          [],
+          |row| row.get(0),
+        )
+        .optional()?;
+
+      config
+        .map(|config| {
+          serde_json::from_str(&config)
+            .map_err(|e| to_json_error(0, e))
+        })
+        .transpose()
+    })
+    .await
+    .map_err(SaveSyncRepositoryError::GetRemote)
+  }
+
+  async fn set_remote(
+    &self,
+    config: Option<&BackupTargetConfig>,
+  ) -> Result<(), SaveSyncRepositoryError> {
+    let pool = self.pool.clone();
+    let config = config
+      .map(serde_json::to_string)
+      .transpose()
+      .map_err(|e| SaveSyncRepositoryError::SetRemote(Box::new(e)))?;
+
+    run_db(pool, move |mut conn| {
+      let tx = conn.transaction()?;
+      match config {
+        Some(config) => tx.execute(
+          "INSERT OR REPLACE INTO save_sync_remote (id, config) VALUES (1, ?1)",
+          [config],
+        )?,
+        None => tx.execute("DELETE FROM save_sync_remote", [])?,
+      };
+      tx.execute("DELETE FROM save_sync_worlds", [])?;
+      tx.commit()
+    })
+    .await
+    .map_err(SaveSyncRepositoryError::SetRemote)
+  }
+
+  async fn get_world_states(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+  ) -> Result<Vec<WorldSyncState>, SaveSyncRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(
+        "SELECT world, version_vector, sha256, synced_at FROM save_sync_worlds WHERE game_variant = ?1 AND data_profile = ?2 ORDER BY world",
+      )?;
+      let states = stmt
+        .query_map([variant, data_profile], |row| {
+          let version_vector: String = row.get(1)?;
+          Ok(WorldSyncState {
+            world: row.get(0)?,
+            version_vector: serde_json::from_str(&version_vector)
+              .map_err(|e| to_json_error(1, e))?,
+            sha256: row.get(2)?,
+            synced_at: row.get(3)?,
+          })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<WorldSyncState>, rusqlite::Error>(states)
+    })
+    .await
+    .map_err(SaveSyncRepositoryError::GetWorldStates)
+  }
+
+  async fn set_world_state(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    state: &WorldSyncState,
+  ) -> Result<(), SaveSyncRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let state = state.clone();
+    let version_vector = serde_json::to_string(&state.version_vector)
+      .map_err(|e| {
+        SaveSyncRepositoryError::SetWorldState(Box::new(e))
+      })?;
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO save_sync_worlds (game_variant, data_profile, world, version_vector, sha256, synced_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+          variant,
+          data_profile,
+          state.world,
+          version_vector,
+          state.sha256,
+          state.synced_at
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(SaveSyncRepositoryError::SetWorldState)
+  }
+
+  async fn delete_world_state(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+  ) -> Result<(), SaveSyncRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let world = world.to_string();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "DELETE FROM save_sync_worlds WHERE game_variant = ?1 AND data_profile = ?2 AND world = ?3",
+        [variant, data_profile, world],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(SaveSyncRepositoryError::DeleteWorldState)
+  }
+}
@@ -0,0 +1,821 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tokio::sync::{Mutex, MutexGuard};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::backup_targets::backup_targets::{
+  keep_stored_password, normalize_backup_target_config,
+  InvalidBackupTargetError,
+};
+use crate::backup_targets::repository::BackupTargetConfig;
+use crate::backup_targets::targets::{
+  create_backup_target, BackupTarget, BackupTargetError,
+};
+use crate::data_profiles::repository::{
+  DataProfilesRepository, DataProfilesRepositoryError,
+};
+use crate::filesystem::paths::{
+  get_save_sync_staging_dir, get_user_game_data_dir,
+};
+use crate::infra::utils::OS;
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::save_sync::repository::{
+  SaveSyncRepository, SaveSyncRepositoryError, WorldSyncState,
+};
+use crate::save_sync::version_vector::VersionVector;
+use crate::save_sync::world_files::{
+  compute_world_sha256, extract_world_archive, list_world_names,
+  replace_world, write_world_archive, WorldFilesError,
+};
+use crate::users::repository::users_repository::UsersRepository;
+use crate::users::service::{
+  get_or_create_user_id, GetOrCreateUserIdError,
+};
+use crate::variants::GameVariant;
+use crate::worlds::management::{delete_world, ManageWorldError};
+
+// Syncing the same save directory twice at once could interleave uploads
+// and downloads of a world. Launching a game also takes it, so that a
+// game never starts while a world is being replaced.
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(thiserror::Error, Debug)]
+#[error(
+  "saves are being synced, try again once the sync has finished"
+)]
+pub struct SaveSyncInProgressError;
+
+/// Keeps saves from being synced until the guard is dropped, or fails if
+/// a sync is in progress. A game is registered as running before the
+/// guard is dropped, after which syncs leave its saves alone.
+pub fn hold_save_sync(
+) -> Result<MutexGuard<'static, ()>, SaveSyncInProgressError> {
+  SYNC_LOCK.try_lock().map_err(|_| SaveSyncInProgressError)
+}
+
+const WORLD_ARCHIVE_SUFFIX: &str = ".world.zip";
+// Each world archive has a manifest next to it, so that the remote worlds
+// can be compared without downloading them.
+const WORLD_MANIFEST_SUFFIX: &str = ".world.json";
+
+/// Describes the copy of a world on the remote.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RemoteWorld {
+  world: String,
+  version_vector: VersionVector,
+  /// The SHA-256 of the world's files.
+  sha256: String,
+  /// The launcher installation that uploaded the world.
+  device_id: String,
+  timestamp: u64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, TS)]
+#[ts(export)]
+pub enum SaveSyncResolution {
+  KeepLocal,
+  KeepRemote,
+  /// Keeps the local world and downloads the remote world as a copy.
+  KeepBoth,
+}
+
+/// A world that was changed both locally and on the remote since it was
+/// last synced.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SaveSyncConflict {
+  pub world: String,
+  pub remote_device_id: String,
+  pub remote_timestamp: u64,
+  /// The world was deleted locally, but changed on the remote. Keeping
+  /// the local world deletes it from the remote.
+  pub deleted_locally: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct FailedWorldSync {
+  pub world: String,
+  pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export)]
+pub struct SaveSyncReport {
+  pub uploaded: Vec<String>,
+  pub downloaded: Vec<String>,
+  /// Worlds deleted or renamed on one side, which are removed from the
+  /// other. Local worlds are moved to the trash.
+  pub deleted: Vec<String>,
+  /// Worlds that are left as they are until the conflict is resolved.
+  pub conflicts: Vec<SaveSyncConflict>,
+  pub failed: Vec<FailedWorldSync>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveSyncError {
+  #[error("no save sync remote is set")]
+  NoRemote,
+
+  #[error("saves cannot be synced while {0} is running")]
+  GameRunning(GameVariant),
+
+  #[error("world {0} is not on the remote")]
+  NotOnRemote(String),
+
+  #[error("downloaded world {0} does not match its checksum")]
+  ChecksumMismatch(String),
+
+  #[error("failed to access save sync state: {0}")]
+  Repository(#[from] SaveSyncRepositoryError),
+
+  #[error("failed to access remote: {0}")]
+  Target(#[from] BackupTargetError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfilesRepositoryError),
+
+  #[error("failed to get device id: {0}")]
+  DeviceId(#[from] GetOrCreateUserIdError),
+
+  #[error("failed to read or write world: {0}")]
+  WorldFiles(#[from] WorldFilesError),
+
+  #[error("failed to delete world: {0}")]
+  DeleteWorld(#[from] ManageWorldError),
+
+  #[error("failed to read or write world manifest: {0}")]
+  Manifest(#[from] serde_json::Error),
+
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to get current time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SetSaveSyncRemoteError {
+  #[error("invalid save sync remote: {0}")]
+  Invalid(#[from] InvalidBackupTargetError),
+
+  #[error("failed to save save sync remote: {0}")]
+  Repository(#[from] SaveSyncRepositoryError),
+}
+
+/// Sets the remote saves are synced with, or stops syncing if `config` is
+/// None. Every world is compared from scratch after the remote changes.
+pub async fn set_save_sync_remote(
+  config: Option<BackupTargetConfig>,
+  repository: &dyn SaveSyncRepository,
+) -> Result<(), SetSaveSyncRemoteError> {
+  let stored = repository.get_remote().await?;
+  let config = config
+    .map(normalize_backup_target_config)
+    .transpose()?
+    .map(|config| keep_stored_password(config, stored.as_ref()));
+
+  if stored == config {
+    return Ok(());
+  }
+
+  repository.set_remote(config.as_ref()).await?;
+  Ok(())
+}
+
+fn get_remote_name(
+  variant: &GameVariant,
+  world: &str,
+  suffix: &str,
+) -> String {
+  format!("{}_{}{}", variant.id(), urlencoding::encode(world), suffix)
+}
+
+// World names from the remote become directory names, so they must not
+// leave the save directory.
+fn is_valid_world_name(world: &str) -> bool {
+  let mut components = Path::new(world).components();
+  matches!(
+    (components.next(), components.next()),
+    (Some(Component::Normal(_)), None)
+  ) && !world.contains(['/', '\\'])
+}
+
+fn now() -> Result<u64, SystemTimeError> {
+  Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+enum WorldSyncOutcome {
+  Unchanged,
+  Uploaded,
+  Downloaded,
+  Deleted,
+  Conflict(SaveSyncConflict),
+}
+
+struct SaveSync<'a> {
+  variant: GameVariant,
+  data_profile: Option<String>,
+  device_id: String,
+  data_dir: PathBuf,
+  save_dir: PathBuf,
+  staging_dir: PathBuf,
+  target: Box<dyn BackupTarget>,
+  repository: &'a dyn SaveSyncRepository,
+  running_games: &'a RunningGamesRegistry,
+  os: &'a OS,
+}
+
+impl<'a> SaveSync<'a> {
+  async fn open(
+    variant: &GameVariant,
+    data_dir: &Path,
+    os: &'a OS,
+    repository: &'a dyn SaveSyncRepository,
+    data_profiles_repository: &dyn DataProfilesRepository,
+    users_repository: &impl UsersRepository,
+    running_games: &'a RunningGamesRegistry,
+  ) -> Result<SaveSync<'a>, SaveSyncError> {
+    let config = repository
+      .get_remote()
+      .await?
+      .ok_or(SaveSyncError::NoRemote)?;
+    let data_profile = data_profiles_repository
+      .get_active_data_profile(variant)
+      .await?;
+
+    let staging_dir = get_save_sync_staging_dir(data_dir)
+      .join(Uuid::new_v4().to_string());
+    fs::create_dir_all(&staging_dir).await?;
+
+    let sync = SaveSync {
+      variant: *variant,
+      save_dir: get_user_game_data_dir(
+        variant,
+        data_profile.as_deref(),
+        data_dir,
+      )
+      .join("save"),
+      data_profile,
+      device_id: get_or_create_user_id(users_repository).await?,
+      data_dir: data_dir.to_path_buf(),
+      staging_dir,
+      target: create_backup_target(&config)?,
+      repository,
+      running_games,
+      os,
+    };
+    sync.ensure_not_running()?;
+
+    Ok(sync)
+  }
+
+  async fn close(self) {
+    // A leftover staging directory is harmless.
+    let _ = fs::remove_dir_all(&self.staging_dir).await;
+  }
+
+  // Checked before every change, as the game may be started while saves
+  // are being synced.
+  fn ensure_not_running(&self) -> Result<(), SaveSyncError> {
+    if self
+      .running_games
+      .is_running(&self.variant, self.data_profile.as_deref())
+    {
+      return Err(SaveSyncError::GameRunning(self.variant));
+    }
+
+    Ok(())
+  }
+
+  fn create_staging_path(&self) -> PathBuf {
+    self.staging_dir.join(Uuid::new_v4().to_string())
+  }
+
+  async fn read_remote_worlds(
+    &self,
+  ) -> Result<HashMap<String, RemoteWorld>, SaveSyncError> {
+    let prefix = format!("{}_", self.variant.id());
+    let mut worlds = HashMap::new();
+
+    for name in self.target.list().await? {
+      if !name.starts_with(&prefix)
+        || !name.ends_with(WORLD_MANIFEST_SUFFIX)
+      {
+        continue;
+      }
+
+      let manifest_path = self.create_staging_path();
+      self.target.download(&name, &manifest_path).await?;
+      let remote: RemoteWorld = match serde_json::from_slice(
+        &fs::read(&manifest_path).await?,
+      ) {
+        Ok(remote) => remote,
+        Err(e) => {
+          eprintln!(
+            "Skipping invalid world manifest {}: {}",
+            name, e
+          );
+          continue;
+        }
+      };
+
+      let expected_name = get_remote_name(
+        &self.variant,
+        &remote.world,
+        WORLD_MANIFEST_SUFFIX,
+      );
+      if is_valid_world_name(&remote.world) && name == expected_name {
+        worlds.insert(remote.world.clone(), remote);
+      }
+    }
+
+    Ok(worlds)
+  }
+
+  async fn get_local_sha256(
+    &self,
+    world: &str,
+  ) -> Result<Option<String>, SaveSyncError> {
+    let world_dir = self.save_dir.join(world);
+    if !fs::try_exists(&world_dir).await? {
+      return Ok(None);
+    }
+
+    Ok(Some(compute_world_sha256(&world_dir).await?))
+  }
+
+  async fn record(
+    &self,
+    world: &str,
+    version_vector: VersionVector,
+    sha256: String,
+  ) -> Result<(), SaveSyncError> {
+    self
+      .repository
+      .set_world_state(
+        &self.variant,
+        self.data_profile.as_deref(),
+        &WorldSyncState {
+          world: world.to_string(),
+          version_vector,
+          sha256,
+          synced_at: now()?,
+        },
+      )
+      .await?;
+    Ok(())
+  }
+
+  async fn forget(&self, world: &str) -> Result<(), SaveSyncError> {
+    self
+      .repository
+      .delete_world_state(
+        &self.variant,
+        self.data_profile.as_deref(),
+        world,
+      )
+      .await?;
+    Ok(())
+  }
+
+  // The manifest is removed first, so that other devices no longer see
+  // the world even if removing the archive fails.
+  async fn delete_remote(
+    &self,
+    world: &str,
+  ) -> Result<(), SaveSyncError> {
+    self
+      .target
+      .delete(&get_remote_name(
+        &self.variant,
+        world,
+        WORLD_MANIFEST_SUFFIX,
+      ))
+      .await?;
+    self
+      .target
+      .delete(&get_remote_name(
+        &self.variant,
+        world,
+        WORLD_ARCHIVE_SUFFIX,
+      ))
+      .await?;
+
+    self.forget(world).await
+  }
+
+  // Moves the local world to the trash, from which it can be restored.
+  async fn delete_local(
+    &self,
+    world: &str,
+  ) -> Result<(), SaveSyncError> {
+    self.ensure_not_running()?;
+    delete_world(
+      &self.variant,
+      self.data_profile.as_deref(),
+      world,
+      &self.data_dir,
+      self.running_games,
+    )
+    .await?;
+
+    self.forget(world).await
+  }
+
+  // The archive is uploaded before the manifest. A device that reads the
+  // new manifest before the archive is replaced fails the checksum and
+  // tries again on its next sync.
+  async fn upload(
+    &self,
+    world: &str,
+    version_vector: &VersionVector,
+    sha256: &str,
+  ) -> Result<(), SaveSyncError> {
+    self.ensure_not_running()?;
+
+    let archive_path = self.create_staging_path();
+    write_world_archive(&self.save_dir, world, &archive_path).await?;
+
+    let remote = RemoteWorld {
+      world: world.to_string(),
+      version_vector: version_vector.clone(),
+      sha256: sha256.to_string(),
+      device_id: self.device_id.clone(),
+      timestamp: now()?,
+    };
+    let manifest_path = self.create_staging_path();
+    fs::write(&manifest_path, serde_json::to_vec_pretty(&remote)?)
+      .await?;
+
+    self
+      .target
+      .upload(
+        &get_remote_name(&self.variant, world, WORLD_ARCHIVE_SUFFIX),
+        &archive_path,
+      )
+      .await?;
+    self
+      .target
+      .upload(
+        &get_remote_name(&self.variant, world, WORLD_MANIFEST_SUFFIX),
+        &manifest_path,
+      )
+      .await?;
+
+    self
+      .record(world, version_vector.clone(), sha256.to_string())
+      .await
+  }
+
+  // Downloads the remote world into the local world `world`, which is
+  // the same world unless a copy is kept. Returns the SHA-256 of the
+  // downloaded files.
+  async fn download(
+    &self,
+    remote: &RemoteWorld,
+    world: &str,
+  ) -> Result<String, SaveSyncError> {
+    self.ensure_not_running()?;
+
+    let dir = self.create_staging_path();
+    fs::create_dir_all(&dir).await?;
+
+    let archive_path = dir.join("world.zip");
+    self
+      .target
+      .download(
+        &get_remote_name(
+          &self.variant,
+          &remote.world,
+          WORLD_ARCHIVE_SUFFIX,
+        ),
+        &archive_path,
+      )
+      .await?;
+
+    let world_dir = extract_world_archive(
+      &archive_path,
+      &remote.world,
+      &dir.join("extracted"),
+      self.os,
+    )
+    .await?;
+    let sha256 = compute_world_sha256(&world_dir).await?;
+    if sha256 != remote.sha256 {
+      return Err(SaveSyncError::ChecksumMismatch(
+        remote.world.clone(),
+      ));
+    }
+
+    self.ensure_not_running()?;
+    replace_world(
+      &self.save_dir,
+      world,
+      &world_dir,
+      &dir.join("displaced"),
+    )
+    .await?;
+
+    Ok(sha256)
+  }
+
+  async fn sync_world(
+    &self,
+    world: &str,
+    state: Option<&WorldSyncState>,
+    remote: Option<&RemoteWorld>,
+  ) -> Result<WorldSyncOutcome, SaveSyncError> {
+    let local_sha256 = self.get_local_sha256(world).await?;
+    let local_changed = match (&local_sha256, state) {
+      (Some(sha256), Some(state)) => *sha256 != state.sha256,
+      (Some(_), None) => true,
+      (None, _) => false,
+    };
+    let mut local_version = state
+      .map(|state| state.version_vector.clone())
+      .unwrap_or_default();
+    if local_changed {
+      local_version.increment(&self.device_id);
+    }
+
+    // A world that was synced before and is now missing on one side was
+    // deleted or renamed there. The delete is carried over, unless the
+    // other side changed the world since.
+    let (local_sha256, remote) = match (local_sha256, remote) {
+      (None, None) => {
+        if state.is_some() {
+          self.forget(world).await?;
+        }
+        return Ok(WorldSyncOutcome::Unchanged);
+      }
+      (Some(_), None) if state.is_some() && !local_changed => {
+        self.delete_local(world).await?;
+        return Ok(WorldSyncOutcome::Deleted);
+      }
+      (Some(sha256), None) => {
+        self.upload(world, &local_version, &sha256).await?;
+        return Ok(WorldSyncOutcome::Uploaded);
+      }
+      (None, Some(remote)) => match state {
+        None => {
+          let sha256 = self.download(remote, world).await?;
+          self
+            .record(world, remote.version_vector.clone(), sha256)
+            .await?;
+          return Ok(WorldSyncOutcome::Downloaded);
+        }
+        Some(state)
+          if matches!(
+            state.version_vector.compare(&remote.version_vector),
+            Some(Ordering::Equal | Ordering::Greater)
+          ) =>
+        {
+          self.delete_remote(world).await?;
+          return Ok(WorldSyncOutcome::Deleted);
+        }
+        Some(_) => {
+          return Ok(WorldSyncOutcome::Conflict(SaveSyncConflict {
+            world: world.to_string(),
+            remote_device_id: remote.device_id.clone(),
+            remote_timestamp: remote.timestamp,
+            deleted_locally: true,
+          }));
+        }
+      },
+      (Some(sha256), Some(remote)) => (sha256, remote),
+    };
+
+    if local_sha256 == remote.sha256 {
+      let version = local_version.merge(&remote.version_vector);
+      let is_recorded = state.is_some_and(|state| {
+        state.sha256 == local_sha256
+          && state.version_vector == version
+      });
+      if !is_recorded {
+        self.record(world, version, local_sha256).await?;
+      }
+      return Ok(WorldSyncOutcome::Unchanged);
+    }
+
+    match local_version.compare(&remote.version_vector) {
+      Some(Ordering::Greater) => {
+        self.upload(world, &local_version, &local_sha256).await?;
+        Ok(WorldSyncOutcome::Uploaded)
+      }
+      Some(Ordering::Less) if !local_changed => {
+        let sha256 = self.download(remote, world).await?;
+        self
+          .record(world, remote.version_vector.clone(), sha256)
+          .await?;
+        Ok(WorldSyncOutcome::Downloaded)
+      }
+      _ => Ok(WorldSyncOutcome::Conflict(SaveSyncConflict {
+        world: world.to_string(),
+        remote_device_id: remote.device_id.clone(),
+        remote_timestamp: remote.timestamp,
+        deleted_locally: false,
+      })),
+    }
+  }
+
+  async fn get_world_states(
+    &self,
+  ) -> Result<HashMap<String, WorldSyncState>, SaveSyncError> {
+    Ok(
+      self
+        .repository
+        .get_world_states(&self.variant, self.data_profile.as_deref())
+        .await?
+        .into_iter()
+        .map(|state| (state.world.clone(), state))
+        .collect(),
+    )
+  }
+
+  async fn sync(&self) -> Result<SaveSyncReport, SaveSyncError> {
+    let states = self.get_world_states().await?;
+    let remote_worlds = self.read_remote_worlds().await?;
+
+    let mut worlds: Vec<String> = list_world_names(&self.save_dir)
+      .await?
+      .into_iter()
+      .chain(remote_worlds.keys().cloned())
+      .collect::<HashSet<_>>()
+      .into_iter()
+      .collect();
+    worlds.sort();
+
+    let mut report = SaveSyncReport::default();
+    for world in worlds {
+      match self
+        .sync_world(
+          &world,
+          states.get(&world),
+          remote_worlds.get(&world),
+        )
+        .await
+      {
+        Ok(WorldSyncOutcome::Unchanged) => {}
+        Ok(WorldSyncOutcome::Uploaded) => report.uploaded.push(world),
+        Ok(WorldSyncOutcome::Downloaded) => {
+          report.downloaded.push(world)
+        }
+        Ok(WorldSyncOutcome::Deleted) => report.deleted.push(world),
+        Ok(WorldSyncOutcome::Conflict(conflict)) => {
+          report.conflicts.push(conflict)
+        }
+        // Every world is synced on its own, so only a game that started
+        // in the meantime stops the others.
+        Err(e @ SaveSyncError::GameRunning(_)) => return Err(e),
+        Err(e) => report.failed.push(FailedWorldSync {
+          world,
+          message: e.to_string(),
+        }),
+      }
+    }
+
+    Ok(report)
+  }
+
+  fn get_copy_name(
+    &self,
+    world: &str,
+    taken: &HashSet<String>,
+  ) -> String {
+    (1..)
+      .map(|index| match index {
+        1 => format!("{} (remote)", world),
+        index => format!("{} (remote {})", world, index),
+      })
+      .find(|name| {
+        !taken.contains(name) && !self.save_dir.join(name).exists()
+      })
+      .expect("an unused name exists")
+  }
+
+  async fn resolve(
+    &self,
+    world: &str,
+    resolution: SaveSyncResolution,
+  ) -> Result<(), SaveSyncError> {
+    let remote_worlds = self.read_remote_worlds().await?;
+    let remote = remote_worlds
+      .get(world)
+      .ok_or_else(|| SaveSyncError::NotOnRemote(world.to_string()))?;
+
+    if let SaveSyncResolution::KeepRemote = resolution {
+      let sha256 = self.download(remote, world).await?;
+      return self
+        .record(world, remote.version_vector.clone(), sha256)
+        .await;
+    }
+
+    let Some(local_sha256) = self.get_local_sha256(world).await?
+    else {
+      // The world was deleted locally, so keeping the local side carries
+      // the delete over, and keeping both only restores the remote world.
+      return match resolution {
+        SaveSyncResolution::KeepLocal => {
+          self.delete_remote(world).await
+        }
+        _ => {
+          let sha256 = self.download(remote, world).await?;
+          self
+            .record(world, remote.version_vector.clone(), sha256)
+            .await
+        }
+      };
+    };
+
+    if let SaveSyncResolution::KeepBoth = resolution {
+      let taken = remote_worlds.keys().cloned().collect();
+      let copy = self.get_copy_name(world, &taken);
+      let sha256 = self.download(remote, &copy).await?;
+
+      let mut version = VersionVector::default();
+      version.increment(&self.device_id);
+      self.upload(&copy, &version, &sha256).await?;
+    }
+
+    // The local world replaces the remote one, so its version must include
+    // the remote changes it overrides.
+    let states = self.get_world_states().await?;
+    let mut version = states
+      .get(world)
+      .map(|state| state.version_vector.clone())
+      .unwrap_or_default()
+      .merge(&remote.version_vector);
+    version.increment(&self.device_id);
+
+    self.upload(world, &version, &local_sha256).await
+  }
+}
+
+/// Syncs the worlds of the active data profile of a variant with the
+/// remote in both directions. Worlds changed on both sides since their
+/// last sync are reported as conflicts and left alone.
+pub async fn sync_saves(
+  variant: &GameVariant,
+  data_dir: &Path,
+  os: &OS,
+  repository: &dyn SaveSyncRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  users_repository: &impl UsersRepository,
+  running_games: &RunningGamesRegistry,
+) -> Result<SaveSyncReport, SaveSyncError> {
+  let _lock = SYNC_LOCK.lock().await;
+
+  let sync = SaveSync::open(
+    variant,
+    data_dir,
+    os,
+    repository,
+    data_profiles_repository,
+    users_repository,
+    running_games,
+  )
+  .await?;
+  let report = sync.sync().await;
+  sync.close().await;
+
+  report
+}
+
+/// Resolves a conflict reported by `sync_saves`.
+#[allow(clippy::too_many_arguments)]
+pub async fn resolve_save_sync_conflict(
+  variant: &GameVariant,
+  world: &str,
+  resolution: SaveSyncResolution,
+  data_dir: &Path,
+  os: &OS,
+  repository: &dyn SaveSyncRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
+  users_repository: &impl UsersRepository,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), SaveSyncError> {
+  let _lock = SYNC_LOCK.lock().await;
+
+  let sync = SaveSync::open(
+    variant,
+    data_dir,
+    os,
+    repository,
+    data_profiles_repository,
+    users_repository,
+    running_games,
+  )
+  .await?;
+  let result = sync.resolve(world, resolution).await;
+  sync.close().await;
+
+  result
+}
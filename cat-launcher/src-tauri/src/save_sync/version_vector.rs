@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Counts the changes each device made to a world, so that two copies of
+/// a world can be told apart as older, newer or changed independently.
+#[derive(
+  Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct VersionVector(BTreeMap<String, u64>);
+
+impl VersionVector {
+  /// Records a change made on `device_id`.
+  pub fn increment(&mut self, device_id: &str) {
+    *self.0.entry(device_id.to_string()).or_default() += 1;
+  }
+
+  /// Returns a vector that includes the changes of both vectors.
+  pub fn merge(&self, other: &VersionVector) -> VersionVector {
+    let mut merged = self.0.clone();
+    for (device_id, count) in &other.0 {
+      let entry = merged.entry(device_id.clone()).or_default();
+      *entry = (*entry).max(*count);
+    }
+    VersionVector(merged)
+  }
+
+  /// Returns Less if `other` includes every change of this vector and
+  /// more, Greater if the reverse is true, or None if both vectors have
+  /// changes the other does not have.
+  pub fn compare(&self, other: &VersionVector) -> Option<Ordering> {
+    let mut ordering = Ordering::Equal;
+
+    for device_id in self.0.keys().chain(other.0.keys()) {
+      let ours = self.0.get(device_id).copied().unwrap_or(0);
+      let theirs = other.0.get(device_id).copied().unwrap_or(0);
+
+      match (ordering, ours.cmp(&theirs)) {
+        (_, Ordering::Equal) => {}
+        (Ordering::Equal, device_ordering) => {
+          ordering = device_ordering
+        }
+        (ordering, device_ordering)
+          if ordering != device_ordering =>
+        {
+          return None;
+        }
+        _ => {}
+      }
+    }
+
+    Some(ordering)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn vector(counts: &[(&str, u64)]) -> VersionVector {
+    VersionVector(
+      counts
+        .iter()
+        .map(|(device_id, count)| (device_id.to_string(), *count))
+        .collect(),
+    )
+  }
+
+  #[test]
+  fn equal_vectors_compare_equal() {
+    let a = vector(&[("a", 2), ("b", 1)]);
+    let b = vector(&[("a", 2), ("b", 1)]);
+
+    assert_eq!(a.compare(&b), Some(Ordering::Equal));
+    assert_eq!(a.merge(&b), a);
+  }
+
+  #[test]
+  fn missing_devices_count_as_zero() {
+    let a = vector(&[("a", 1), ("b", 0)]);
+    let b = vector(&[("a", 1)]);
+
+    assert_eq!(a.compare(&b), Some(Ordering::Equal));
+  }
+
+  #[test]
+  fn newer_vector_dominates() {
+    let older = vector(&[("a", 1), ("b", 1)]);
+    let newer = vector(&[("a", 2), ("b", 1)]);
+
+    assert_eq!(newer.compare(&older), Some(Ordering::Greater));
+    assert_eq!(older.compare(&newer), Some(Ordering::Less));
+  }
+
+  #[test]
+  fn vector_with_new_device_dominates() {
+    let older = vector(&[("a", 1)]);
+    let newer = vector(&[("a", 1), ("b", 1)]);
+
+    assert_eq!(newer.compare(&older), Some(Ordering::Greater));
+    assert_eq!(older.compare(&newer), Some(Ordering::Less));
+  }
+
+  #[test]
+  fn independent_changes_are_concurrent() {
+    let a = vector(&[("a", 2), ("b", 1)]);
+    let b = vector(&[("a", 1), ("b", 2)]);
+
+    assert_eq!(a.compare(&b), None);
+    assert_eq!(b.compare(&a), None);
+  }
+
+  #[test]
+  fn merge_dominates_both_vectors() {
+    let a = vector(&[("a", 2), ("b", 1)]);
+    let b = vector(&[("a", 1), ("b", 2), ("c", 1)]);
+    let merged = a.merge(&b);
+
+    assert_eq!(merged, vector(&[("a", 2), ("b", 2), ("c", 1)]));
+    assert_eq!(merged.compare(&a), Some(Ordering::Greater));
+    assert_eq!(merged.compare(&b), Some(Ordering::Greater));
+  }
+
+  #[test]
+  fn increment_makes_vector_newer() {
+    let older = vector(&[("a", 1)]);
+    let mut newer = older.clone();
+    newer.increment("b");
+
+    assert_eq!(newer, vector(&[("a", 1), ("b", 1)]));
+    assert_eq!(newer.compare(&older), Some(Ordering::Greater));
+  }
+}
@@ -0,0 +1,152 @@
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tokio::task::JoinError;
+use walkdir::WalkDir;
+
+use crate::infra::archive::{
+  create_zip_archive, extract_archive, ArchiveCreationError,
+  ExtractionError,
+};
+use crate::infra::utils::OS;
+
+#[derive(thiserror::Error, Debug)]
+pub enum WorldFilesError {
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to walk world directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  #[error("failed to create world archive: {0}")]
+  Archive(#[from] ArchiveCreationError),
+
+  #[error("failed to extract world archive: {0}")]
+  Extract(#[from] ExtractionError),
+
+  #[error("world archive does not contain world {0}")]
+  MissingWorld(String),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+/// Returns the names of the worlds in a save directory.
+pub async fn list_world_names(
+  save_dir: &Path,
+) -> io::Result<Vec<String>> {
+  let mut worlds = Vec::new();
+  let mut entries = match fs::read_dir(save_dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(worlds)
+    }
+    Err(e) => return Err(e),
+  };
+
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_dir() {
+      worlds.push(entry.file_name().to_string_lossy().into_owned());
+    }
+  }
+
+  worlds.sort();
+  Ok(worlds)
+}
+
+/// Hashes the paths and contents of the files of a world, so that two
+/// copies of a world with the same files have the same hash.
+pub async fn compute_world_sha256(
+  world_dir: &Path,
+) -> Result<String, WorldFilesError> {
+  let world_dir = world_dir.to_owned();
+
+  tokio::task::spawn_blocking(move || {
+    let mut hasher = Sha256::new();
+
+    for entry in WalkDir::new(&world_dir).sort_by_file_name() {
+      let entry = entry?;
+      if !entry.file_type().is_file() {
+        continue;
+      }
+
+      let relative_path = entry
+        .path()
+        .strip_prefix(&world_dir)
+        .unwrap_or(entry.path())
+        .to_string_lossy()
+        .replace('\\', "/");
+      hasher.update(relative_path.as_bytes());
+      hasher.update([0]);
+      hasher.update(entry.metadata()?.len().to_le_bytes());
+      io::copy(&mut File::open(entry.path())?, &mut hasher)?;
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+  })
+  .await?
+}
+
+/// Writes a world to a zip archive whose entries start with the world's
+/// name.
+pub async fn write_world_archive(
+  save_dir: &Path,
+  world: &str,
+  archive_path: &Path,
+) -> Result<(), WorldFilesError> {
+  create_zip_archive(save_dir, &[save_dir.join(world)], archive_path)
+    .await?;
+  Ok(())
+}
+
+/// Extracts an archive written by `write_world_archive` into `target_dir`
+/// and returns the directory of the world in it.
+pub async fn extract_world_archive(
+  archive_path: &Path,
+  world: &str,
+  target_dir: &Path,
+  os: &OS,
+) -> Result<PathBuf, WorldFilesError> {
+  extract_archive(archive_path, target_dir, os).await?;
+
+  let world_dir = target_dir.join(world);
+  if !fs::try_exists(&world_dir).await? {
+    return Err(WorldFilesError::MissingWorld(world.to_string()));
+  }
+
+  Ok(world_dir)
+}
+
+/// Moves `replacement` to `save_dir/world`. An existing world is moved to
+/// `displaced` first and only removed once the replacement is in place.
+/// All directories are on the same file system, so the moves are renames.
+pub async fn replace_world(
+  save_dir: &Path,
+  world: &str,
+  replacement: &Path,
+  displaced: &Path,
+) -> io::Result<()> {
+  let target = save_dir.join(world);
+  fs::create_dir_all(save_dir).await?;
+
+  let existed = fs::try_exists(&target).await?;
+  if existed {
+    fs::rename(&target, displaced).await?;
+  }
+
+  if let Err(e) = fs::rename(replacement, &target).await {
+    if existed {
+      let _ = fs::rename(displaced, &target).await;
+    }
+    return Err(e);
+  }
+
+  if existed {
+    fs::remove_dir_all(displaced).await?;
+  }
+
+  Ok(())
+}
@@ -0,0 +1,94 @@
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::session_logs::repository::sqlite_session_logs_repository::SqliteSessionLogsRepository;
+use crate::session_logs::repository::{
+  SessionLogEntry, SessionLogRetentionPolicy,
+};
+use crate::session_logs::session_logs::{
+  delete_session_log as delete_session_log_impl,
+  get_session_log_retention_policy as get_session_log_retention_policy_impl,
+  list_session_logs as list_session_logs_impl,
+  read_session_log as read_session_log_impl,
+  set_session_log_retention_policy as set_session_log_retention_policy_impl,
+  SessionLogError, SessionLogPage,
+};
+use crate::variants::GameVariant;
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum SessionLogCommandError {
+  #[error("session log operation failed: {0}")]
+  SessionLog(#[from] SessionLogError),
+
+  #[error("failed to get system directory: {0}")]
+  SystemDir(#[from] tauri::Error),
+}
+
+#[command]
+pub async fn list_session_logs(
+  variant: GameVariant,
+  repository: State<'_, SqliteSessionLogsRepository>,
+) -> Result<Vec<SessionLogEntry>, SessionLogCommandError> {
+  let logs = list_session_logs_impl(&variant, &*repository).await?;
+  Ok(logs)
+}
+
+#[command]
+pub async fn read_session_log(
+  app_handle: AppHandle,
+  id: i64,
+  offset: u64,
+  limit: u64,
+  repository: State<'_, SqliteSessionLogsRepository>,
+) -> Result<SessionLogPage, SessionLogCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  let page =
+    read_session_log_impl(id, offset, limit, &data_dir, &*repository)
+      .await?;
+
+  Ok(page)
+}
+
+#[command]
+pub async fn delete_session_log(
+  app_handle: AppHandle,
+  id: i64,
+  repository: State<'_, SqliteSessionLogsRepository>,
+) -> Result<(), SessionLogCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+
+  delete_session_log_impl(id, &data_dir, &*repository).await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn get_session_log_retention_policy(
+  variant: GameVariant,
+  repository: State<'_, SqliteSessionLogsRepository>,
+) -> Result<SessionLogRetentionPolicy, SessionLogCommandError> {
+  let policy =
+    get_session_log_retention_policy_impl(&variant, &*repository)
+      .await?;
+  Ok(policy)
+}
+
+#[command]
+pub async fn set_session_log_retention_policy(
+  variant: GameVariant,
+  policy: SessionLogRetentionPolicy,
+  repository: State<'_, SqliteSessionLogsRepository>,
+) -> Result<(), SessionLogCommandError> {
+  set_session_log_retention_policy_impl(
+    &variant,
+    &policy,
+    &*repository,
+  )
+  .await?;
+  Ok(())
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod session_logs;
+
+pub mod commands;
+pub mod repository;
@@ -0,0 +1,7 @@
+pub mod session_logs_repository;
+pub mod sqlite_session_logs_repository;
+
+pub use session_logs_repository::{
+  SessionLogEntry, SessionLogRetentionPolicy, SessionLogsRepository,
+  SessionLogsRepositoryError,
+};
@@ -0,0 +1,102 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::variants::GameVariant;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionLogEntry {
+  pub id: i64,
+  pub game_variant: GameVariant,
+  pub release_version: String,
+  pub started_at: u64,
+  pub ended_at: Option<u64>,
+}
+
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub struct SessionLogRetentionPolicy {
+  pub max_count: Option<u32>,
+  pub max_age_days: Option<u32>,
+}
+
+impl Default for SessionLogRetentionPolicy {
+  fn default() -> Self {
+    SessionLogRetentionPolicy {
+      max_count: Some(20),
+      max_age_days: Some(30),
+    }
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SessionLogsRepositoryError {
+  #[error("failed to add session log: {0}")]
+  Add(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get session logs: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to update session log: {0}")]
+  Update(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to delete session log: {0}")]
+  Delete(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to get session log retention policy: {0}")]
+  GetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to set session log retention policy: {0}")]
+  SetRetentionPolicy(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("session log with id {0} not found")]
+  NotFound(i64),
+}
+
+#[async_trait]
+pub trait SessionLogsRepository: Send + Sync {
+  async fn add_session_log(
+    &self,
+    game_variant: &GameVariant,
+    release_version: &str,
+    started_at: u64,
+  ) -> Result<i64, SessionLogsRepositoryError>;
+
+  async fn set_session_log_ended_at(
+    &self,
+    id: i64,
+    ended_at: u64,
+  ) -> Result<(), SessionLogsRepositoryError>;
+
+  /// Returns the session logs of a variant, newest first.
+  async fn get_session_logs(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<SessionLogEntry>, SessionLogsRepositoryError>;
+
+  async fn get_session_log(
+    &self,
+    id: i64,
+  ) -> Result<SessionLogEntry, SessionLogsRepositoryError>;
+
+  async fn delete_session_log(
+    &self,
+    id: i64,
+  ) -> Result<(), SessionLogsRepositoryError>;
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<SessionLogRetentionPolicy, SessionLogsRepositoryError>;
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &SessionLogRetentionPolicy,
+  ) -> Result<(), SessionLogsRepositoryError>;
+}
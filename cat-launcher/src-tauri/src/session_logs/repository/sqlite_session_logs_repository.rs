@@ -0,0 +1,209 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::types::Type;
+use rusqlite::{OptionalExtension, Row};
+
+use crate::infra::repository::db_helper::run_db;
+use crate::session_logs::repository::{
+  SessionLogEntry, SessionLogRetentionPolicy, SessionLogsRepository,
+  SessionLogsRepositoryError,
+};
+use crate::variants::GameVariant;
+
+const SELECT_SESSION_LOG: &str = "SELECT id, game_variant, release_version, started_at, ended_at FROM session_logs";
+
+#[derive(Clone)]
+pub struct SqliteSessionLogsRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteSessionLogsRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+fn row_to_session_log(
+  row: &Row,
+) -> rusqlite::Result<SessionLogEntry> {
+  let game_variant_str: String = row.get(1)?;
+  let game_variant = GameVariant::from_str(&game_variant_str)
+    .map_err(|e| {
+      rusqlite::Error::FromSqlConversionFailure(
+        1,
+        Type::Text,
+        Box::new(e),
+      )
+    })?;
+
+  Ok(SessionLogEntry {
+    id: row.get(0)?,
+    game_variant,
+    release_version: row.get(2)?,
+    started_at: row.get(3)?,
+    ended_at: row.get(4)?,
+  })
+}
+
+#[async_trait]
+impl SessionLogsRepository for SqliteSessionLogsRepository {
+  async fn add_session_log(
+    &self,
+    game_variant: &GameVariant,
+    release_version: &str,
+    started_at: u64,
+  ) -> Result<i64, SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let release_version = release_version.to_string();
+
+    run_db(pool, move |conn| {
+      conn.query_row(
+        "INSERT INTO session_logs (game_variant, release_version, started_at) VALUES (?1, ?2, ?3) RETURNING id",
+        rusqlite::params![variant_name, release_version, started_at],
+        |row| row.get::<_, i64>(0),
+      )
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::Add)
+  }
+
+  async fn set_session_log_ended_at(
+    &self,
+    id: i64,
+    ended_at: u64,
+  ) -> Result<(), SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute(
+        "UPDATE session_logs SET ended_at = ?2 WHERE id = ?1",
+        rusqlite::params![id, ended_at],
+      )
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::Update)?;
+
+    if rows_affected == 0 {
+      return Err(SessionLogsRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+
+  async fn get_session_logs(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<Vec<SessionLogEntry>, SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let mut stmt = conn.prepare(&format!(
+        "{} WHERE game_variant = ?1 ORDER BY started_at DESC, id DESC",
+        SELECT_SESSION_LOG
+      ))?;
+      let logs = stmt
+        .query_map([&variant_name], row_to_session_log)?
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok::<Vec<SessionLogEntry>, rusqlite::Error>(logs)
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::Get)
+  }
+
+  async fn get_session_log(
+    &self,
+    id: i64,
+  ) -> Result<SessionLogEntry, SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let log = run_db(pool, move |conn| {
+      conn
+        .query_row(
+          &format!("{} WHERE id = ?1", SELECT_SESSION_LOG),
+          [id],
+          row_to_session_log,
+        )
+        .optional()
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::Get)?;
+
+    log.ok_or(SessionLogsRepositoryError::NotFound(id))
+  }
+
+  async fn delete_session_log(
+    &self,
+    id: i64,
+  ) -> Result<(), SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+
+    let rows_affected = run_db(pool, move |conn| {
+      conn.execute("DELETE FROM session_logs WHERE id = ?1", [id])
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::Delete)?;
+
+    if rows_affected == 0 {
+      return Err(SessionLogsRepositoryError::NotFound(id));
+    }
+
+    Ok(())
+  }
+
+  async fn get_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+  ) -> Result<SessionLogRetentionPolicy, SessionLogsRepositoryError>
+  {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+
+    run_db(pool, move |conn| {
+      let policy = conn
+        .query_row(
+          "SELECT max_count, max_age_days FROM session_log_retention_policies WHERE game_variant = ?1",
+          [&variant_name],
+          |row| {
+            Ok(SessionLogRetentionPolicy {
+              max_count: row.get(0)?,
+              max_age_days: row.get(1)?,
+            })
+          },
+        )
+        .optional()?;
+      Ok::<SessionLogRetentionPolicy, rusqlite::Error>(
+        policy.unwrap_or_default(),
+      )
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::GetRetentionPolicy)
+  }
+
+  async fn set_retention_policy(
+    &self,
+    game_variant: &GameVariant,
+    policy: &SessionLogRetentionPolicy,
+  ) -> Result<(), SessionLogsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant_name = game_variant.to_string();
+    let policy = *policy;
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO session_log_retention_policies (game_variant, max_count, max_age_days) VALUES (?1, ?2, ?3)",
+        rusqlite::params![
+          variant_name,
+          policy.max_count,
+          policy.max_age_days
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(SessionLogsRepositoryError::SetRetentionPolicy)
+  }
+}
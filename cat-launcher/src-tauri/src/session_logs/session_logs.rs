@@ -0,0 +1,294 @@
+use std::io;
+use std::path::Path;
+
+use serde::Serialize;
+use tokio::fs::{self, File};
+use tokio::io::{
+  AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter,
+};
+use tokio::sync::Mutex;
+use ts_rs::TS;
+
+use crate::filesystem::paths::{
+  get_session_log_filepath, get_session_logs_dir,
+};
+use crate::session_logs::repository::{
+  SessionLogEntry, SessionLogRetentionPolicy, SessionLogsRepository,
+  SessionLogsRepositoryError,
+};
+use crate::variants::GameVariant;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Copy)]
+pub enum LogStream {
+  Stdout,
+  Stderr,
+}
+
+impl LogStream {
+  fn tag(&self) -> &'static str {
+    match self {
+      LogStream::Stdout => "stdout",
+      LogStream::Stderr => "stderr",
+    }
+  }
+}
+
+/// The log file of a running game session.
+pub struct SessionLog {
+  entry: SessionLogEntry,
+  writer: Mutex<BufWriter<File>>,
+}
+
+impl SessionLog {
+  pub async fn write_line(
+    &self,
+    stream: LogStream,
+    line: &str,
+  ) -> io::Result<()> {
+    let mut writer = self.writer.lock().await;
+    writer
+      .write_all(format!("[{}] {}\n", stream.tag(), line).as_bytes())
+      .await?;
+    // Each line is flushed, so that the log survives the launcher
+    // crashing or being killed mid-session.
+    writer.flush().await
+  }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SessionLogError {
+  #[error("failed to access session logs: {0}")]
+  Repository(#[from] SessionLogsRepositoryError),
+
+  #[error("failed to access session log file: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("page size must be greater than zero")]
+  EmptyPage,
+}
+
+pub async fn start_session_log(
+  variant: &GameVariant,
+  release_version: &str,
+  started_at: u64,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<SessionLog, SessionLogError> {
+  let id = repository
+    .add_session_log(variant, release_version, started_at)
+    .await?;
+
+  let path =
+    get_session_log_filepath(variant, id, started_at, data_dir);
+  let file = async {
+    fs::create_dir_all(get_session_logs_dir(data_dir)).await?;
+    let mut writer = BufWriter::new(File::create(&path).await?);
+    writer
+      .write_all(
+        format!(
+          "# variant: {}\n# version: {}\n# started_at: {}\n",
+          variant.id(),
+          release_version,
+          started_at
+        )
+        .as_bytes(),
+      )
+      .await?;
+    writer.flush().await?;
+    Ok::<BufWriter<File>, io::Error>(writer)
+  }
+  .await;
+
+  let writer = match file {
+    Ok(writer) => writer,
+    Err(e) => {
+      let _ = repository.delete_session_log(id).await;
+      return Err(e.into());
+    }
+  };
+
+  Ok(SessionLog {
+    entry: SessionLogEntry {
+      id,
+      game_variant: *variant,
+      release_version: release_version.to_string(),
+      started_at,
+      ended_at: None,
+    },
+    writer: Mutex::new(writer),
+  })
+}
+
+pub async fn finish_session_log(
+  session_log: &SessionLog,
+  ended_at: u64,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<(), SessionLogError> {
+  {
+    let mut writer = session_log.writer.lock().await;
+    writer
+      .write_all(format!("# ended_at: {}\n", ended_at).as_bytes())
+      .await?;
+    writer.flush().await?;
+  }
+
+  let entry = &session_log.entry;
+  repository
+    .set_session_log_ended_at(entry.id, ended_at)
+    .await?;
+
+  apply_session_log_retention_policy(
+    &entry.game_variant,
+    entry.id,
+    ended_at,
+    data_dir,
+    repository,
+  )
+  .await
+}
+
+async fn remove_session_log(
+  entry: &SessionLogEntry,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<(), SessionLogError> {
+  repository.delete_session_log(entry.id).await?;
+
+  let path = get_session_log_filepath(
+    &entry.game_variant,
+    entry.id,
+    entry.started_at,
+    data_dir,
+  );
+  match fs::remove_file(&path).await {
+    Ok(()) => {}
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+    Err(e) => return Err(e.into()),
+  }
+
+  Ok(())
+}
+
+// Removes the session logs that exceed the variant's retention policy.
+// The log of the session that just ended is always kept.
+async fn apply_session_log_retention_policy(
+  variant: &GameVariant,
+  current_id: i64,
+  now: u64,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<(), SessionLogError> {
+  let policy = repository.get_retention_policy(variant).await?;
+  let logs = repository.get_session_logs(variant).await?;
+
+  let oldest_kept = policy.max_age_days.map(|days| {
+    now.saturating_sub(u64::from(days) * SECONDS_PER_DAY)
+  });
+
+  for (index, log) in logs.iter().enumerate() {
+    if log.id == current_id {
+      continue;
+    }
+
+    let exceeds_count = policy
+      .max_count
+      .is_some_and(|max_count| index >= max_count as usize);
+    let exceeds_age = oldest_kept
+      .is_some_and(|oldest_kept| log.started_at < oldest_kept);
+
+    if exceeds_count || exceeds_age {
+      remove_session_log(log, data_dir, repository).await?;
+    }
+  }
+
+  Ok(())
+}
+
+pub async fn list_session_logs(
+  variant: &GameVariant,
+  repository: &dyn SessionLogsRepository,
+) -> Result<Vec<SessionLogEntry>, SessionLogError> {
+  Ok(repository.get_session_logs(variant).await?)
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct SessionLogPage {
+  pub lines: Vec<String>,
+  pub offset: u64,
+  /// The offset of the next page, or None if this is the last page.
+  pub next_offset: Option<u64>,
+}
+
+pub async fn read_session_log(
+  id: i64,
+  offset: u64,
+  limit: u64,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<SessionLogPage, SessionLogError> {
+  if limit == 0 {
+    return Err(SessionLogError::EmptyPage);
+  }
+
+  let entry = repository.get_session_log(id).await?;
+  let path = get_session_log_filepath(
+    &entry.game_variant,
+    entry.id,
+    entry.started_at,
+    data_dir,
+  );
+
+  let mut reader = BufReader::new(File::open(&path).await?).lines();
+  let mut lines = Vec::new();
+  let mut index = 0;
+
+  // One extra line is read to know whether another page follows.
+  while let Some(line) = reader.next_line().await? {
+    if index >= offset {
+      if lines.len() as u64 == limit {
+        return Ok(SessionLogPage {
+          lines,
+          offset,
+          next_offset: Some(offset + limit),
+        });
+      }
+      lines.push(line);
+    }
+    index += 1;
+  }
+
+  Ok(SessionLogPage {
+    lines,
+    offset,
+    next_offset: None,
+  })
+}
+
+pub async fn delete_session_log(
+  id: i64,
+  data_dir: &Path,
+  repository: &dyn SessionLogsRepository,
+) -> Result<(), SessionLogError> {
+  let entry = repository.get_session_log(id).await?;
+  remove_session_log(&entry, data_dir, repository).await
+}
+
+pub async fn get_session_log_retention_policy(
+  variant: &GameVariant,
+  repository: &dyn SessionLogsRepository,
+) -> Result<SessionLogRetentionPolicy, SessionLogError> {
+  Ok(repository.get_retention_policy(variant).await?)
+}
+
+pub async fn set_session_log_retention_policy(
+  variant: &GameVariant,
+  policy: &SessionLogRetentionPolicy,
+  repository: &dyn SessionLogsRepository,
+) -> Result<(), SessionLogError> {
+  repository.set_retention_policy(variant, policy).await?;
+  Ok(())
+}
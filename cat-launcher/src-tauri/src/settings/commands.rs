@@ -5,6 +5,7 @@ use tauri::{command, AppHandle, Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
 use crate::settings::colors::{
   get_available_color_themes, GetColorThemesError,
@@ -93,6 +94,7 @@ pub async fn update_settings(
   app_handle: AppHandle,
   settings: Settings,
   repository: State<'_, SqliteSettingsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), UpdateSettingsCommandError> {
   let data_dir = app_handle.path().app_local_data_dir()?;
 
@@ -100,6 +102,7 @@ pub async fn update_settings(
     &data_dir,
     &settings,
     &*repository,
+    &*data_profiles_repository,
   )
   .await?;
   Ok(())
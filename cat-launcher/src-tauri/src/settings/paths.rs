@@ -18,10 +18,12 @@ pub enum GetOrCreateUserConfigDirError {
 
 pub async fn get_or_create_user_config_dir(
   variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
 ) -> Result<PathBuf, GetOrCreateUserConfigDirError> {
   let user_dir =
-    get_or_create_user_game_data_dir(variant, data_dir).await?;
+    get_or_create_user_game_data_dir(variant, data_profile, data_dir)
+      .await?;
   let config_dir = user_dir.join("config");
   tokio::fs::create_dir_all(&config_dir).await?;
   Ok(config_dir)
@@ -2,6 +2,10 @@ use std::path::Path;
 
 use strum::IntoEnumIterator;
 
+use crate::data_profiles::data_profiles::{
+  get_all_data_profiles, DataProfileError,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::filesystem::paths::GetUserGameDataDirError;
 use crate::settings::paths::{
   get_or_create_user_config_dir, GetOrCreateUserConfigDirError,
@@ -11,6 +15,9 @@ use crate::variants::GameVariant;
 
 #[derive(thiserror::Error, Debug)]
 pub enum UpdateColorFilesError {
+  #[error("failed to get data profiles: {0}")]
+  DataProfiles(#[from] DataProfileError),
+
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
@@ -24,22 +31,33 @@ pub enum UpdateColorFilesError {
 pub async fn update_color_files(
   data_dir: &Path,
   settings: &Settings,
+  data_profiles_repository: &dyn DataProfilesRepository,
 ) -> Result<(), UpdateColorFilesError> {
   let selected_theme = &settings.color_theme;
 
   for variant in GameVariant::iter() {
-    let config_dir =
-      get_or_create_user_config_dir(&variant, data_dir).await?;
-    let target_path = config_dir.join("base_colors.json");
-
-    if let Some(theme) = selected_theme {
-      tokio::fs::copy(&theme.path, &target_path).await?;
-    } else {
-      // Removing the file, resets the theme to the default
-      match tokio::fs::remove_file(&target_path).await {
-        Ok(_) => (),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
-        Err(e) => return Err(UpdateColorFilesError::Copy(e)),
+    let data_profiles =
+      get_all_data_profiles(&variant, data_profiles_repository)
+        .await?;
+
+    for data_profile in data_profiles {
+      let config_dir = get_or_create_user_config_dir(
+        &variant,
+        data_profile.as_deref(),
+        data_dir,
+      )
+      .await?;
+      let target_path = config_dir.join("base_colors.json");
+
+      if let Some(theme) = selected_theme {
+        tokio::fs::copy(&theme.path, &target_path).await?;
+      } else {
+        // Removing the file, resets the theme to the default
+        match tokio::fs::remove_file(&target_path).await {
+          Ok(_) => (),
+          Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+          Err(e) => return Err(UpdateColorFilesError::Copy(e)),
+        }
       }
     }
   }
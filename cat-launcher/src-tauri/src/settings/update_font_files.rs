@@ -3,6 +3,10 @@ use std::path::Path;
 
 use strum::IntoEnumIterator;
 
+use crate::data_profiles::data_profiles::{
+  get_all_data_profiles, DataProfileError,
+};
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::filesystem::paths::GetUserGameDataDirError;
 use crate::settings::consts::FALLBACK_FONTS;
 use crate::settings::paths::{
@@ -29,6 +33,9 @@ pub enum EnsureFontBlendingError {
 
 #[derive(thiserror::Error, Debug)]
 pub enum UpdateFontFilesError {
+  #[error("failed to get data profiles: {0}")]
+  DataProfiles(#[from] DataProfileError),
+
   #[error("failed to get user game data directory: {0}")]
   UserGameDataDir(#[from] GetUserGameDataDirError),
 
@@ -51,47 +58,60 @@ pub enum UpdateFontFilesError {
 pub async fn update_font_files(
   data_dir: &Path,
   settings: &Settings,
+  data_profiles_repository: &dyn DataProfilesRepository,
 ) -> Result<(), UpdateFontFilesError> {
   let selected_font = &settings.font;
 
   for variant in GameVariant::iter() {
-    let config_dir =
-      get_or_create_user_config_dir(&variant, data_dir).await?;
-
-    let fonts_json_path = config_dir.join("fonts.json");
-
-    let mut fonts_map: HashMap<String, Vec<String>> =
-      match tokio::fs::read_to_string(&fonts_json_path).await {
-        Ok(content) => {
-          serde_json::from_str(&content).unwrap_or_else(|e| {
-            eprintln!(
-              "Failed to parse fonts.json at {:?}: {}",
-              fonts_json_path, e
-            );
+    let data_profiles =
+      get_all_data_profiles(&variant, data_profiles_repository)
+        .await?;
+
+    for data_profile in data_profiles {
+      let config_dir = get_or_create_user_config_dir(
+        &variant,
+        data_profile.as_deref(),
+        data_dir,
+      )
+      .await?;
+
+      let fonts_json_path = config_dir.join("fonts.json");
+
+      let mut fonts_map: HashMap<String, Vec<String>> =
+        match tokio::fs::read_to_string(&fonts_json_path).await {
+          Ok(content) => serde_json::from_str(&content)
+            .unwrap_or_else(|e| {
+              eprintln!(
+                "Failed to parse fonts.json at {:?}: {}",
+                fonts_json_path, e
+              );
+              create_default_fonts_map(&variant)
+            }),
+          Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
             create_default_fonts_map(&variant)
-          })
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-          create_default_fonts_map(&variant)
-        }
-        Err(e) => return Err(UpdateFontFilesError::ReadFontsJson(e)),
-      };
-
-    let updated_list = get_updated_typeface_list(selected_font);
-    let supported_categories =
-      variant.supported_typeface_categories();
-
-    for category in supported_categories {
-      fonts_map.insert(category.to_string(), updated_list.clone());
-    }
-
-    let content = serde_json::to_string_pretty(&fonts_map)?;
-    if let Err(e) = tokio::fs::write(&fonts_json_path, content).await
-    {
-      return Err(UpdateFontFilesError::WriteFontsJson(e));
+          }
+          Err(e) => {
+            return Err(UpdateFontFilesError::ReadFontsJson(e))
+          }
+        };
+
+      let updated_list = get_updated_typeface_list(selected_font);
+      let supported_categories =
+        variant.supported_typeface_categories();
+
+      for category in supported_categories {
+        fonts_map.insert(category.to_string(), updated_list.clone());
+      }
+
+      let content = serde_json::to_string_pretty(&fonts_map)?;
+      if let Err(e) =
+        tokio::fs::write(&fonts_json_path, content).await
+      {
+        return Err(UpdateFontFilesError::WriteFontsJson(e));
+      }
+
+      ensure_font_blending(&config_dir).await?;
     }
-
-    ensure_font_blending(&config_dir).await?;
   }
 
   Ok(())
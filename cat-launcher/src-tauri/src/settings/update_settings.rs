@@ -1,5 +1,6 @@
 use std::path::Path;
 
+use crate::data_profiles::repository::DataProfilesRepository;
 use crate::settings::repository::settings_repository::{
   SaveSettingsError, SettingsRepository,
 };
@@ -27,9 +28,12 @@ pub async fn update_settings(
   data_dir: &Path,
   settings: &Settings,
   repository: &impl SettingsRepository,
+  data_profiles_repository: &dyn DataProfilesRepository,
 ) -> Result<(), UpdateSettingsError> {
-  update_font_files(data_dir, settings).await?;
-  update_color_files(data_dir, settings).await?;
+  update_font_files(data_dir, settings, data_profiles_repository)
+    .await?;
+  update_color_files(data_dir, settings, data_profiles_repository)
+    .await?;
   repository.save_settings(settings).await?;
   Ok(())
 }
@@ -8,6 +8,10 @@ use tauri::{Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::download::Downloader;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
@@ -74,6 +78,9 @@ pub enum InstallThirdPartySoundpackCommandError {
 
   #[error("failed to install soundpack: {0}")]
   Install(#[from] InstallThirdPartySoundpackError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -84,6 +91,7 @@ pub async fn install_third_party_soundpack_command(
   app: tauri::AppHandle,
   downloader: State<'_, Downloader>,
   repository: State<'_, SqliteInstalledSoundpacksRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), InstallThirdPartySoundpackCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
   let resource_dir = app.path().resource_dir()?;
@@ -93,9 +101,16 @@ pub async fn install_third_party_soundpack_command(
 
   let reporter = Arc::new(ChannelReporter::new(channel));
 
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   install_third_party_soundpack(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     &resource_dir,
     &temp_dir,
@@ -118,6 +133,9 @@ pub enum UninstallThirdPartySoundpackCommandError {
 
   #[error("failed to uninstall soundpack: {0}")]
   Uninstall(#[from] UninstallThirdPartySoundpackError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -126,12 +144,20 @@ pub async fn uninstall_third_party_soundpack_command(
   variant: GameVariant,
   app: tauri::AppHandle,
   repository: State<'_, SqliteInstalledSoundpacksRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), UninstallThirdPartySoundpackCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
 
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   uninstall_third_party_soundpack(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     repository.inner(),
   )
@@ -145,6 +171,9 @@ pub async fn uninstall_third_party_soundpack_command(
 pub enum GetThirdPartySoundpackInstallationStatusCommandError {
   #[error("failed to get soundpack installation status: {0}")]
   GetStatus(#[from] GetThirdPartySoundpackInstallationStatusError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -152,13 +181,21 @@ pub async fn get_third_party_soundpack_installation_status_command(
   id: String,
   variant: GameVariant,
   repository: State<'_, SqliteInstalledSoundpacksRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<
   SoundpackInstallationStatus,
   GetThirdPartySoundpackInstallationStatusCommandError,
 > {
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   let status = get_third_party_soundpack_installation_status(
     &id,
     &variant,
+    data_profile.as_deref(),
     repository.inner(),
   )
   .await?;
@@ -13,13 +13,14 @@ pub enum GetThirdPartySoundpackInstallationStatusError {
 pub async fn get_third_party_soundpack_installation_status(
   soundpack_id: &str,
   variant: &GameVariant,
+  data_profile: Option<&str>,
   repository: &dyn InstalledSoundpacksRepository,
 ) -> Result<
   SoundpackInstallationStatus,
   GetThirdPartySoundpackInstallationStatusError,
 > {
   let is_installed = repository
-    .is_soundpack_installed(soundpack_id, variant)
+    .is_soundpack_installed(soundpack_id, variant, data_profile)
     .await?;
 
   Ok(if is_installed {
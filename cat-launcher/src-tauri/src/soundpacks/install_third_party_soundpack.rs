@@ -57,6 +57,7 @@ pub enum InstallThirdPartySoundpackError {
 pub async fn install_third_party_soundpack(
   soundpack_id: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
   resource_dir: &Path,
   temp_dir: &Path,
@@ -97,8 +98,12 @@ pub async fn install_third_party_soundpack(
   )?;
 
   // Get the sounds directory in user game data
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let sounds_dir =
     get_or_create_directory(&user_game_data_dir, "sound").await?;
 
@@ -109,7 +114,7 @@ pub async fn install_third_party_soundpack(
 
   // Mark the soundpack as installed in the repository
   repository
-    .add_installed_soundpack(soundpack_id, game_variant)
+    .add_installed_soundpack(soundpack_id, game_variant, data_profile)
     .await?;
 
   // Clean up temp files, ignore any errors
@@ -30,22 +30,26 @@ pub trait InstalledSoundpacksRepository: Send + Sync {
     &self,
     soundpack_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledSoundpacksRepositoryError>;
 
   async fn delete_installed_soundpack(
     &self,
     soundpack_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledSoundpacksRepositoryError>;
 
   async fn delete_all_installed_soundpacks(
     &self,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledSoundpacksRepositoryError>;
 
   async fn is_soundpack_installed(
     &self,
     soundpack_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<bool, InstalledSoundpacksRepositoryError>;
 }
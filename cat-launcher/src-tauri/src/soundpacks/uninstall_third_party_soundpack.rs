@@ -22,17 +22,26 @@ pub enum UninstallThirdPartySoundpackError {
 pub async fn uninstall_third_party_soundpack(
   soundpack_id: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
   repository: &impl InstalledSoundpacksRepository,
 ) -> Result<(), UninstallThirdPartySoundpackError> {
   // Remove from repository
   repository
-    .delete_installed_soundpack(soundpack_id, game_variant)
+    .delete_installed_soundpack(
+      soundpack_id,
+      game_variant,
+      data_profile,
+    )
     .await?;
 
   // Delete soundpack directory
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let soundpack_dir =
     user_game_data_dir.join("sound").join(soundpack_id);
   tokio::fs::remove_dir_all(&soundpack_dir).await?;
@@ -8,6 +8,10 @@ use tauri::{Manager, State};
 use cat_macros::CommandErrorSerialize;
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::infra::download::Downloader;
 use crate::infra::installation_progress_monitor::channel_reporter::ChannelReporter;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
@@ -74,6 +78,9 @@ pub enum InstallThirdPartyTilesetCommandError {
 
   #[error("failed to install tileset: {0}")]
   Install(#[from] InstallThirdPartyTilesetError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -84,6 +91,7 @@ pub async fn install_third_party_tileset_command(
   app: tauri::AppHandle,
   downloader: State<'_, Downloader>,
   repository: State<'_, SqliteInstalledTilesetsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), InstallThirdPartyTilesetCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
   let resource_dir = app.path().resource_dir()?;
@@ -93,9 +101,16 @@ pub async fn install_third_party_tileset_command(
 
   let reporter = Arc::new(ChannelReporter::new(channel));
 
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   install_third_party_tileset(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     &resource_dir,
     &temp_dir,
@@ -118,6 +133,9 @@ pub enum UninstallThirdPartyTilesetCommandError {
 
   #[error("failed to uninstall tileset: {0}")]
   Uninstall(#[from] UninstallThirdPartyTilesetError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -126,12 +144,20 @@ pub async fn uninstall_third_party_tileset_command(
   variant: GameVariant,
   app: tauri::AppHandle,
   repository: State<'_, SqliteInstalledTilesetsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<(), UninstallThirdPartyTilesetCommandError> {
   let data_dir = app.path().app_local_data_dir()?;
 
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   uninstall_third_party_tileset(
     &id,
     &variant,
+    data_profile.as_deref(),
     &data_dir,
     repository.inner(),
   )
@@ -145,6 +171,9 @@ pub async fn uninstall_third_party_tileset_command(
 pub enum GetThirdPartyTilesetInstallationStatusCommandError {
   #[error("failed to get tileset installation status: {0}")]
   GetStatus(#[from] GetThirdPartyTilesetInstallationStatusError),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
 }
 
 #[tauri::command]
@@ -152,13 +181,21 @@ pub async fn get_third_party_tileset_installation_status_command(
   id: String,
   variant: GameVariant,
   repository: State<'_, SqliteInstalledTilesetsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
 ) -> Result<
   TilesetInstallationStatus,
   GetThirdPartyTilesetInstallationStatusCommandError,
 > {
+  let data_profile = get_active_data_profile(
+    &variant,
+    data_profiles_repository.inner(),
+  )
+  .await?;
+
   let status = get_third_party_tileset_installation_status(
     &id,
     &variant,
+    data_profile.as_deref(),
     repository.inner(),
   )
   .await?;
@@ -13,13 +13,15 @@ pub enum GetThirdPartyTilesetInstallationStatusError {
 pub async fn get_third_party_tileset_installation_status(
   tileset_id: &str,
   variant: &GameVariant,
+  data_profile: Option<&str>,
   repository: &dyn InstalledTilesetsRepository,
 ) -> Result<
   TilesetInstallationStatus,
   GetThirdPartyTilesetInstallationStatusError,
 > {
-  let is_installed =
-    repository.is_tileset_installed(tileset_id, variant).await?;
+  let is_installed = repository
+    .is_tileset_installed(tileset_id, variant, data_profile)
+    .await?;
 
   Ok(if is_installed {
     TilesetInstallationStatus::Installed
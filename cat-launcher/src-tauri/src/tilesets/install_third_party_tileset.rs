@@ -56,6 +56,7 @@ pub enum InstallThirdPartyTilesetError {
 pub async fn install_third_party_tileset(
   tileset_id: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
   resource_dir: &Path,
   temp_dir: &Path,
@@ -90,8 +91,12 @@ pub async fn install_third_party_tileset(
     &tileset_details.installation.tileset,
   )?;
 
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let gfx_dir =
     get_or_create_directory(&user_game_data_dir, "gfx").await?;
 
@@ -101,7 +106,7 @@ pub async fn install_third_party_tileset(
   let _ = tokio::fs::remove_dir_all(&tileset_temp_dir).await;
 
   repository
-    .add_installed_tileset(tileset_id, game_variant)
+    .add_installed_tileset(tileset_id, game_variant, data_profile)
     .await?;
 
   Ok(())
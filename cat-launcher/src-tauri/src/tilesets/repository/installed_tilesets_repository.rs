@@ -28,22 +28,26 @@ pub trait InstalledTilesetsRepository: Send + Sync {
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError>;
 
   async fn delete_installed_tileset(
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError>;
 
   async fn delete_all_installed_tilesets(
     &self,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError>;
 
   async fn is_tileset_installed(
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<bool, InstalledTilesetsRepositoryError>;
 }
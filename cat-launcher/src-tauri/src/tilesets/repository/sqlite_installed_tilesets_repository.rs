@@ -25,16 +25,24 @@ impl InstalledTilesetsRepository
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError> {
     let pool = self.pool.clone();
     let tileset_id = tileset_id.to_string();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
     run_db(pool, move |conn| {
-      conn.execute(
-        "INSERT OR IGNORE INTO installed_tilesets (tileset_id, game_variant) VALUES (?1, ?2)",
-        [&tileset_id, &variant_name],
-      )?;
+      match data_profile {
+        None => conn.execute(
+          "INSERT OR IGNORE INTO installed_tilesets (tileset_id, game_variant) VALUES (?1, ?2)",
+          [&tileset_id, &variant_name],
+        ),
+        Some(profile_name) => conn.execute(
+          "INSERT OR IGNORE INTO data_profile_installed_tilesets (tileset_id, game_variant, profile_name) VALUES (?1, ?2, ?3)",
+          [&tileset_id, &variant_name, &profile_name],
+        ),
+      }?;
       Ok::<(), rusqlite::Error>(())
     })
     .await
@@ -45,16 +53,24 @@ impl InstalledTilesetsRepository
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError> {
     let pool = self.pool.clone();
     let tileset_id_clone = tileset_id.to_string();
     let variant_name_clone = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
     let rows_affected = run_db(pool, move |conn| {
-      let count = conn.execute(
-        "DELETE FROM installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2",
-        [&tileset_id_clone, &variant_name_clone],
-      )?;
+      let count = match data_profile {
+        None => conn.execute(
+          "DELETE FROM installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2",
+          [&tileset_id_clone, &variant_name_clone],
+        ),
+        Some(profile_name) => conn.execute(
+          "DELETE FROM data_profile_installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2 AND profile_name = ?3",
+          [&tileset_id_clone, &variant_name_clone, &profile_name],
+        ),
+      }?;
       Ok::<usize, rusqlite::Error>(count)
     })
     .await
@@ -73,15 +89,23 @@ impl InstalledTilesetsRepository
   async fn delete_all_installed_tilesets(
     &self,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<(), InstalledTilesetsRepositoryError> {
     let pool = self.pool.clone();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
     run_db(pool, move |conn| {
-      conn.execute(
-        "DELETE FROM installed_tilesets WHERE game_variant = ?1",
-        [&variant_name],
-      )?;
+      match data_profile {
+        None => conn.execute(
+          "DELETE FROM installed_tilesets WHERE game_variant = ?1",
+          [&variant_name],
+        ),
+        Some(profile_name) => conn.execute(
+          "DELETE FROM data_profile_installed_tilesets WHERE game_variant = ?1 AND profile_name = ?2",
+          [&variant_name, &profile_name],
+        ),
+      }?;
       Ok::<(), rusqlite::Error>(())
     })
     .await
@@ -92,16 +116,26 @@ impl InstalledTilesetsRepository
     &self,
     tileset_id: &str,
     game_variant: &GameVariant,
+    data_profile: Option<&str>,
   ) -> Result<bool, InstalledTilesetsRepositoryError> {
     let pool = self.pool.clone();
     let tileset_id = tileset_id.to_string();
     let variant_name = game_variant.to_string();
+    let data_profile = data_profile.map(str::to_string);
 
-    run_db(pool, move |conn| {
-      let mut stmt = conn.prepare(
-        "SELECT 1 FROM installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2",
-      )?;
-      stmt.exists([&tileset_id, &variant_name])
+    run_db(pool, move |conn| match data_profile {
+      None => {
+        let mut stmt = conn.prepare(
+          "SELECT 1 FROM installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2",
+        )?;
+        stmt.exists([&tileset_id, &variant_name])
+      }
+      Some(profile_name) => {
+        let mut stmt = conn.prepare(
+          "SELECT 1 FROM data_profile_installed_tilesets WHERE tileset_id = ?1 AND game_variant = ?2 AND profile_name = ?3",
+        )?;
+        stmt.exists([&tileset_id, &variant_name, &profile_name])
+      }
     })
     .await
     .map_err(InstalledTilesetsRepositoryError::IsInstalled)
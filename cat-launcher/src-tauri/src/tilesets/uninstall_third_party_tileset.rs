@@ -22,17 +22,22 @@ pub enum UninstallThirdPartyTilesetError {
 pub async fn uninstall_third_party_tileset(
   tileset_id: &str,
   game_variant: &GameVariant,
+  data_profile: Option<&str>,
   data_dir: &Path,
   repository: &impl InstalledTilesetsRepository,
 ) -> Result<(), UninstallThirdPartyTilesetError> {
   // Remove from repository
   repository
-    .delete_installed_tileset(tileset_id, game_variant)
+    .delete_installed_tileset(tileset_id, game_variant, data_profile)
     .await?;
 
   // Delete tileset directory
-  let user_game_data_dir =
-    get_or_create_user_game_data_dir(game_variant, data_dir).await?;
+  let user_game_data_dir = get_or_create_user_game_data_dir(
+    game_variant,
+    data_profile,
+    data_dir,
+  )
+  .await?;
   let tileset_dir = user_game_data_dir.join("gfx").join(tileset_id);
   tokio::fs::remove_dir_all(&tileset_dir).await?;
 
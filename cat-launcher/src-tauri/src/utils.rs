@@ -6,24 +6,36 @@ use r2d2_sqlite::SqliteConnectionManager;
 use tauri::{App, Emitter, Listener, Manager, WindowEvent};
 
 use crate::active_release::repository::sqlite_active_release_repository::SqliteActiveReleaseRepository;
-use crate::constants::PARALLEL_REQUESTS;
+use crate::backup_settings::repository::sqlite_backup_settings_repository::SqliteBackupSettingsRepository;
+use crate::backup_targets::mirror::{spawn_backup_mirror, BackupMirror};
+use crate::backup_targets::repository::sqlite_backup_targets_repository::SqliteBackupTargetsRepository;
+use crate::constants::{
+  DOWNLOAD_INITIAL_BACKOFF, DOWNLOAD_MAX_ATTEMPTS, DOWNLOAD_MAX_BACKOFF,
+};
+use crate::crash_reports::repository::sqlite_crash_reports_repository::SqliteCrashReportsRepository;
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
 use crate::fetch_releases::repository::sqlite_releases_repository::SqliteReleasesRepository;
 use crate::filesystem::paths::{get_db_path, get_schema_file_path};
 use crate::filesystem::paths::GetSchemaFilePathError;
 use crate::filesystem::utils::{copy_dir_all, CopyDirError};
 use crate::infra::autoupdate::update::run_updater;
-use crate::infra::download::Downloader;
+use crate::infra::download::{Downloader, RetryPolicy};
 use crate::infra::http_client::create_http_client;
 use crate::infra::repository::db_schema::initialize_schema;
 use crate::infra::repository::db_schema::InitializeSchemaError;
 use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::installed_releases::repository::sqlite_installed_releases_repository::SqliteInstalledReleasesRepository;
 use crate::launch_game::repository::sqlite_backup_repository::SqliteBackupRepository;
+use crate::launch_profiles::repository::sqlite_launch_profiles_repository::SqliteLaunchProfilesRepository;
 use crate::manual_backups::repository::sqlite_manual_backup_repository::SqliteManualBackupRepository;
 use crate::mods::lib::OnlineModRepositoryRegistry;
 use crate::mods::online::bright_nights::BrightNightsModRepository;
 use crate::mods::repository::sqlite_installed_mods_repository::SqliteInstalledModsRepository;
 use crate::mods::repository::sqlite_mods_repository::SqliteModsRepository;
 use crate::play_time::sqlite_play_time_repository::SqlitePlayTimeRepository;
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::save_sync::repository::sqlite_save_sync_repository::SqliteSaveSyncRepository;
+use crate::session_logs::repository::sqlite_session_logs_repository::SqliteSessionLogsRepository;
 use crate::settings::repository::settings_repository::SettingsRepository;
 use crate::settings::repository::settings_repository::GetSettingsError;
 use crate::settings::repository::sqlite_settings_repository::SqliteSettingsRepository;
@@ -33,6 +45,7 @@ use crate::tilesets::repository::sqlite_installed_tilesets_repository::SqliteIns
 use crate::users::repository::sqlite_users_repository::SqliteUsersRepository;
 use crate::users::service::get_or_create_user_id;
 use crate::variants::repository::sqlite_game_variant_order_repository::SqliteGameVariantOrderRepository;
+use crate::world_versions::repository::sqlite_world_versions_repository::SqliteWorldVersionsRepository;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ManageSettingsError {
@@ -99,9 +112,18 @@ pub fn manage_repositories(app: &App) -> Result<(), RepositoryError> {
 
   app.manage(SqliteReleasesRepository::new(pool.clone()));
   app.manage(SqliteBackupRepository::new(pool.clone()));
+  app.manage(SqliteBackupSettingsRepository::new(pool.clone()));
+  app.manage(SqliteLaunchProfilesRepository::new(pool.clone()));
+  app.manage(SqliteDataProfilesRepository::new(pool.clone()));
+  app.manage(SqliteCrashReportsRepository::new(pool.clone()));
+  app.manage(SqliteSessionLogsRepository::new(pool.clone()));
   app.manage(SqliteManualBackupRepository::new(pool.clone()));
+  app.manage(SqliteBackupTargetsRepository::new(pool.clone()));
+  app.manage(SqliteSaveSyncRepository::new(pool.clone()));
   app.manage(SqliteActiveReleaseRepository::new(pool.clone()));
+  app.manage(SqliteInstalledReleasesRepository::new(pool.clone()));
   app.manage(SqlitePlayTimeRepository::new(pool.clone()));
+  app.manage(SqliteWorldVersionsRepository::new(pool.clone()));
   app.manage(SqliteGameVariantOrderRepository::new(pool.clone()));
   app.manage(SqliteThemePreferenceRepository::new(pool.clone()));
   app.manage(SqliteSettingsRepository::new(pool.clone()));
@@ -178,11 +200,25 @@ async fn migrate_to_local_data_dir_impl(
 
 pub fn manage_downloader(app: &App) {
   let client: tauri::State<reqwest::Client> = app.state();
+  let retry_policy = RetryPolicy {
+    max_attempts: DOWNLOAD_MAX_ATTEMPTS,
+    initial_backoff: DOWNLOAD_INITIAL_BACKOFF,
+    max_backoff: DOWNLOAD_MAX_BACKOFF,
+  };
   let downloader =
-    Downloader::new(client.inner().clone(), PARALLEL_REQUESTS);
+    Downloader::new(client.inner().clone(), retry_policy);
   app.manage(downloader);
 }
 
+pub fn manage_backup_mirror(app: &App) {
+  app.manage(BackupMirror::default());
+  spawn_backup_mirror(app.handle().clone());
+}
+
+pub fn manage_running_games(app: &App) {
+  app.manage(RunningGamesRegistry::default());
+}
+
 pub fn manage_http_client(app: &App) {
   let client = create_http_client();
   app.manage(client);
@@ -0,0 +1,45 @@
+use strum::IntoStaticStr;
+use tauri::{command, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+use crate::variants::GameVariant;
+use crate::world_versions::repository::sqlite_world_versions_repository::SqliteWorldVersionsRepository;
+use crate::world_versions::repository::{
+  WorldVersion, WorldVersionsRepository, WorldVersionsRepositoryError,
+};
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum GetWorldVersionCommandError {
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to get world version: {0}")]
+  Repository(#[from] WorldVersionsRepositoryError),
+}
+
+/// Returns the release a world was last played with, so that it can be
+/// selected before launching the world.
+#[command]
+pub async fn get_world_version(
+  variant: GameVariant,
+  world: String,
+  repository: State<'_, SqliteWorldVersionsRepository>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Option<WorldVersion>, GetWorldVersionCommandError> {
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  let version = repository
+    .get_world_version(&variant, data_profile.as_deref(), &world)
+    .await?;
+
+  Ok(version)
+}
@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod world_versions;
+
+pub mod commands;
+pub mod repository;
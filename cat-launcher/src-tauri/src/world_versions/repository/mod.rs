@@ -0,0 +1,6 @@
+pub mod sqlite_world_versions_repository;
+pub mod world_versions_repository;
+
+pub use world_versions_repository::{
+  WorldVersion, WorldVersionsRepository, WorldVersionsRepositoryError,
+};
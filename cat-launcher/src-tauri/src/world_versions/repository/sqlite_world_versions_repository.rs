@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::OptionalExtension;
+
+use crate::infra::repository::db_helper::run_db;
+use crate::variants::GameVariant;
+use crate::world_versions::repository::{
+  WorldVersion, WorldVersionsRepository, WorldVersionsRepositoryError,
+};
+
+#[derive(Clone)]
+pub struct SqliteWorldVersionsRepository {
+  pool: r2d2::Pool<SqliteConnectionManager>,
+}
+
+impl SqliteWorldVersionsRepository {
+  pub fn new(pool: r2d2::Pool<SqliteConnectionManager>) -> Self {
+    Self { pool }
+  }
+}
+
+#[async_trait]
+impl WorldVersionsRepository for SqliteWorldVersionsRepository {
+  async fn get_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+  ) -> Result<Option<WorldVersion>, WorldVersionsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let world = world.to_string();
+
+    run_db(pool, move |conn| {
+      conn
+        .query_row(
+          "SELECT world, release_version, release_created_at, played_at FROM world_versions WHERE game_variant = ?1 AND data_profile = ?2 AND world = ?3",
+          [variant, data_profile, world],
+          |row| {
+            Ok(WorldVersion {
+              world: row.get(0)?,
+              release_version: row.get(1)?,
+              release_created_at: row.get(2)?,
+              played_at: row.get(3)?,
+            })
+          },
+        )
+        .optional()
+    })
+    .await
+    .map_err(WorldVersionsRepositoryError::Get)
+  }
+
+  async fn set_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    version: &WorldVersion,
+  ) -> Result<(), WorldVersionsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let version = version.clone();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "INSERT OR REPLACE INTO world_versions (game_variant, data_profile, world, release_version, release_created_at, played_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+          variant,
+          data_profile,
+          version.world,
+          version.release_version,
+          version.release_created_at,
+          version.played_at
+        ],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(WorldVersionsRepositoryError::Set)
+  }
+
+  async fn rename_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+    new_world: &str,
+  ) -> Result<(), WorldVersionsRepositoryError> {
+    let pool = self.pool.clone();
+    let variant = variant.to_string();
+    let data_profile = data_profile.unwrap_or_default().to_string();
+    let world = world.to_string();
+    let new_world = new_world.to_string();
+
+    run_db(pool, move |conn| {
+      conn.execute(
+        "UPDATE OR REPLACE world_versions SET world = ?4 WHERE game_variant = ?1 AND data_profile = ?2 AND world = ?3",
+        [variant, data_profile, world, new_world],
+      )?;
+      Ok::<(), rusqlite::Error>(())
+    })
+    .await
+    .map_err(WorldVersionsRepositoryError::Set)
+  }
+}
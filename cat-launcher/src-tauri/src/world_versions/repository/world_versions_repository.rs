@@ -0,0 +1,54 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::variants::GameVariant;
+
+/// The release a world was last played with.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorldVersion {
+  pub world: String,
+  pub release_version: String,
+  /// When the release was published, in seconds since the epoch.
+  pub release_created_at: i64,
+  pub played_at: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WorldVersionsRepositoryError {
+  #[error("failed to get world version: {0}")]
+  Get(#[source] Box<dyn Error + Send + Sync>),
+
+  #[error("failed to record world version: {0}")]
+  Set(#[source] Box<dyn Error + Send + Sync>),
+}
+
+#[async_trait]
+pub trait WorldVersionsRepository: Send + Sync {
+  async fn get_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+  ) -> Result<Option<WorldVersion>, WorldVersionsRepositoryError>;
+
+  async fn set_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    version: &WorldVersion,
+  ) -> Result<(), WorldVersionsRepositoryError>;
+
+  /// Moves the record of `world` to `new_world`, replacing any record
+  /// `new_world` already had.
+  async fn rename_world_version(
+    &self,
+    variant: &GameVariant,
+    data_profile: Option<&str>,
+    world: &str,
+    new_world: &str,
+  ) -> Result<(), WorldVersionsRepositoryError>;
+}
@@ -0,0 +1,145 @@
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+use tokio::fs;
+
+use crate::filesystem::paths::GetUserGameDataDirError;
+use crate::game_release::game_release::GameRelease;
+use crate::last_played_world::paths::get_last_world_path;
+use crate::variants::GameVariant;
+use crate::world_versions::repository::{
+  WorldVersion, WorldVersionsRepository, WorldVersionsRepositoryError,
+};
+use crate::worlds::worlds::{
+  get_save_dir, get_world_dir, WorldsError,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum WorldVersionError {
+  #[error(
+    "world {world} was last played with {last_version}, which is newer than {version}"
+  )]
+  Downgrade {
+    world: String,
+    last_version: String,
+    version: String,
+  },
+
+  #[error("failed to access world versions: {0}")]
+  Repository(#[from] WorldVersionsRepositoryError),
+
+  #[error("failed to get user game data directory: {0}")]
+  UserGameDataDir(#[from] GetUserGameDataDirError),
+
+  #[error("{0}")]
+  InvalidWorld(#[from] WorldsError),
+
+  #[error("failed to read world files: {0}")]
+  Io(#[from] io::Error),
+}
+
+/// Fails if `world` was last played with a release published after
+/// `release`, as older releases may not load newer saves correctly.
+pub async fn check_world_version(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  release: &GameRelease,
+  repository: &dyn WorldVersionsRepository,
+) -> Result<(), WorldVersionError> {
+  let Some(last_version) = repository
+    .get_world_version(variant, data_profile, world)
+    .await?
+  else {
+    return Ok(());
+  };
+
+  if last_version.release_version != release.version
+    && last_version.release_created_at
+      > release.created_at.timestamp()
+  {
+    return Err(WorldVersionError::Downgrade {
+      world: world.to_string(),
+      last_version: last_version.release_version,
+      version: release.version.clone(),
+    });
+  }
+
+  Ok(())
+}
+
+pub async fn record_world_version(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  release: &GameRelease,
+  played_at: u64,
+  repository: &dyn WorldVersionsRepository,
+) -> Result<(), WorldVersionError> {
+  repository
+    .set_world_version(
+      variant,
+      data_profile,
+      &WorldVersion {
+        world: world.to_string(),
+        release_version: release.version.clone(),
+        release_created_at: release.created_at.timestamp(),
+        played_at,
+      },
+    )
+    .await?;
+
+  Ok(())
+}
+
+/// Whether `world` was saved at or after `since`. lastworld.json outlives
+/// the session that wrote it, so it only shows that a world was played if
+/// it, the world directory or a file directly in it changed since then.
+pub async fn was_world_saved_since(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  data_dir: &Path,
+  since: SystemTime,
+) -> Result<bool, WorldVersionError> {
+  let is_newer = |path: &Path| {
+    let path = path.to_owned();
+    async move {
+      match fs::metadata(&path).await {
+        Ok(metadata) => Ok(metadata.modified()? >= since),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+      }
+    }
+  };
+
+  let last_world_path =
+    get_last_world_path(data_dir, variant, data_profile).await?;
+  if is_newer(&last_world_path).await? {
+    return Ok(true);
+  }
+
+  let world_dir = get_world_dir(
+    &get_save_dir(variant, data_profile, data_dir),
+    world,
+  )?;
+  if is_newer(&world_dir).await? {
+    return Ok(true);
+  }
+
+  let mut entries = match fs::read_dir(&world_dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(false)
+    }
+    Err(e) => return Err(e.into()),
+  };
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.metadata().await?.modified()? >= since {
+      return Ok(true);
+    }
+  }
+
+  Ok(false)
+}
@@ -0,0 +1,254 @@
+use strum::IntoStaticStr;
+use tauri::{command, AppHandle, Manager, State};
+
+use cat_macros::CommandErrorSerialize;
+
+use crate::data_profiles::data_profiles::{
+  get_active_data_profile, DataProfileError,
+};
+use crate::data_profiles::repository::sqlite_data_profiles_repository::SqliteDataProfilesRepository;
+use crate::infra::utils::{get_os_enum, OSNotSupportedError};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+use crate::world_versions::repository::sqlite_world_versions_repository::SqliteWorldVersionsRepository;
+use crate::worlds::management::{
+  delete_world as delete_world_impl,
+  duplicate_world as duplicate_world_impl,
+  list_deleted_worlds as list_deleted_worlds_impl,
+  purge_deleted_world as purge_deleted_world_impl,
+  rename_world as rename_world_impl,
+  restore_deleted_world as restore_deleted_world_impl, DeletedWorld,
+  ManageWorldError,
+};
+use crate::worlds::worlds::{
+  get_world_details as get_world_details_impl,
+  list_worlds as list_worlds_impl, World, WorldDetails, WorldsError,
+};
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum WorldsCommandError {
+  #[error("failed to get app local data directory: {0}")]
+  AppLocalDataDir(#[from] tauri::Error),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to read worlds: {0}")]
+  Worlds(#[from] WorldsError),
+}
+
+#[command]
+pub async fn list_worlds(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Vec<World>, WorldsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  let worlds =
+    list_worlds_impl(&variant, data_profile.as_deref(), &data_dir)
+      .await?;
+
+  Ok(worlds)
+}
+
+#[command]
+pub async fn get_world_details(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  world: String,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<WorldDetails, WorldsCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  let details = get_world_details_impl(
+    &variant,
+    data_profile.as_deref(),
+    &world,
+    &data_dir,
+  )
+  .await?;
+
+  Ok(details)
+}
+
+#[derive(
+  thiserror::Error, Debug, IntoStaticStr, CommandErrorSerialize,
+)]
+pub enum ManageWorldCommandError {
+  #[error("failed to get app local data directory: {0}")]
+  AppLocalDataDir(#[from] tauri::Error),
+
+  #[error("failed to get active data profile: {0}")]
+  DataProfile(#[from] DataProfileError),
+
+  #[error("failed to manage world: {0}")]
+  Manage(#[from] ManageWorldError),
+
+  #[error("unsupported OS: {0}")]
+  UnsupportedOS(#[from] OSNotSupportedError),
+}
+
+#[command]
+pub async fn duplicate_world(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  world: String,
+  new_name: String,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+  world_versions_repository: State<'_, SqliteWorldVersionsRepository>,
+) -> Result<(), ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let os = get_os_enum(std::env::consts::OS)?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  duplicate_world_impl(
+    &variant,
+    data_profile.as_deref(),
+    &world,
+    &new_name,
+    &data_dir,
+    &os,
+    &running_games,
+    &*world_versions_repository,
+  )
+  .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn rename_world(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  world: String,
+  new_name: String,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+  world_versions_repository: State<'_, SqliteWorldVersionsRepository>,
+) -> Result<(), ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  rename_world_impl(
+    &variant,
+    data_profile.as_deref(),
+    &world,
+    &new_name,
+    &data_dir,
+    &running_games,
+    &*world_versions_repository,
+  )
+  .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn delete_world(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  world: String,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  delete_world_impl(
+    &variant,
+    data_profile.as_deref(),
+    &world,
+    &data_dir,
+    &running_games,
+  )
+  .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn list_deleted_worlds(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<Vec<DeletedWorld>, ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  let deleted_worlds = list_deleted_worlds_impl(
+    &variant,
+    data_profile.as_deref(),
+    &data_dir,
+  )
+  .await?;
+
+  Ok(deleted_worlds)
+}
+
+#[command]
+pub async fn restore_deleted_world(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  id: String,
+  new_name: Option<String>,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+  running_games: State<'_, RunningGamesRegistry>,
+) -> Result<(), ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  restore_deleted_world_impl(
+    &variant,
+    data_profile.as_deref(),
+    &id,
+    new_name.as_deref(),
+    &data_dir,
+    &running_games,
+  )
+  .await?;
+
+  Ok(())
+}
+
+#[command]
+pub async fn purge_deleted_world(
+  app_handle: AppHandle,
+  variant: GameVariant,
+  id: String,
+  data_profiles_repository: State<'_, SqliteDataProfilesRepository>,
+) -> Result<(), ManageWorldCommandError> {
+  let data_dir = app_handle.path().app_local_data_dir()?;
+  let data_profile =
+    get_active_data_profile(&variant, &*data_profiles_repository)
+      .await?;
+
+  purge_deleted_world_impl(
+    &variant,
+    data_profile.as_deref(),
+    &id,
+    &data_dir,
+  )
+  .await?;
+
+  Ok(())
+}
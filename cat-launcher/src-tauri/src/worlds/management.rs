@@ -0,0 +1,375 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, SystemTimeError, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::fs;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::filesystem::paths::get_world_trash_dir;
+use crate::filesystem::utils::{
+  copy_dir_all, get_dir_size, CopyDirError, GetDirSizeError,
+};
+use crate::infra::utils::OS;
+use crate::last_played_world::last_played_world::{
+  update_last_played_world, UpdateLastPlayedWorldError,
+};
+use crate::running_games::running_games::RunningGamesRegistry;
+use crate::variants::GameVariant;
+use crate::world_versions::repository::{
+  WorldVersion, WorldVersionsRepository, WorldVersionsRepositoryError,
+};
+use crate::worlds::worlds::{
+  get_save_dir, get_world_dir, WorldsError,
+};
+
+/// A world in the trash, which can be restored until it is purged.
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct DeletedWorld {
+  pub id: String,
+  pub name: String,
+  pub deleted_at: u64,
+  pub size_bytes: u64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ManageWorldError {
+  #[error("worlds cannot be changed while {0} is running")]
+  GameRunning(GameVariant),
+
+  #[error("{0}")]
+  InvalidWorld(#[from] WorldsError),
+
+  #[error("world {0} does not exist")]
+  WorldNotFound(String),
+
+  #[error("a world named {0} already exists")]
+  WorldExists(String),
+
+  #[error("deleted world {0} does not exist")]
+  DeletedWorldNotFound(String),
+
+  #[error("failed to copy world: {0}")]
+  Copy(#[from] CopyDirError),
+
+  #[error("failed to get world size: {0}")]
+  Size(#[from] GetDirSizeError),
+
+  #[error("failed to update last played world: {0}")]
+  LastPlayedWorld(#[from] UpdateLastPlayedWorldError),
+
+  #[error("failed to update world version: {0}")]
+  WorldVersion(#[from] WorldVersionsRepositoryError),
+
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("failed to get current time: {0}")]
+  SystemTime(#[from] SystemTimeError),
+}
+
+/// The save directory of a variant and data profile, which must not be in
+/// use by a running game.
+struct SaveDir<'a> {
+  variant: &'a GameVariant,
+  data_profile: Option<&'a str>,
+  data_dir: &'a Path,
+  save_dir: PathBuf,
+}
+
+impl<'a> SaveDir<'a> {
+  fn open(
+    variant: &'a GameVariant,
+    data_profile: Option<&'a str>,
+    data_dir: &'a Path,
+    running_games: &RunningGamesRegistry,
+  ) -> Result<Self, ManageWorldError> {
+    if running_games.is_running(variant, data_profile) {
+      return Err(ManageWorldError::GameRunning(*variant));
+    }
+
+    Ok(Self {
+      variant,
+      data_profile,
+      data_dir,
+      save_dir: get_save_dir(variant, data_profile, data_dir),
+    })
+  }
+
+  async fn existing_world(
+    &self,
+    world: &str,
+  ) -> Result<PathBuf, ManageWorldError> {
+    let world_dir = get_world_dir(&self.save_dir, world)?;
+    if !fs::try_exists(&world_dir).await? {
+      return Err(ManageWorldError::WorldNotFound(world.to_string()));
+    }
+
+    Ok(world_dir)
+  }
+
+  async fn new_world(
+    &self,
+    world: &str,
+  ) -> Result<PathBuf, ManageWorldError> {
+    let world_dir = get_world_dir(&self.save_dir, world)?;
+    if fs::try_exists(&world_dir).await? {
+      return Err(ManageWorldError::WorldExists(world.to_string()));
+    }
+
+    Ok(world_dir)
+  }
+
+  fn trash_dir(&self) -> PathBuf {
+    get_world_trash_dir(
+      self.variant,
+      self.data_profile,
+      self.data_dir,
+    )
+  }
+
+  async fn update_last_played_world(
+    &self,
+    world: &str,
+    new_world: Option<&str>,
+  ) -> Result<(), ManageWorldError> {
+    update_last_played_world(
+      self.data_dir,
+      self.variant,
+      self.data_profile,
+      world,
+      new_world,
+    )
+    .await?;
+    Ok(())
+  }
+}
+
+/// Copies a world under a new name, leaving the original untouched. The
+/// copy keeps the release the world was last played with.
+#[allow(clippy::too_many_arguments)]
+pub async fn duplicate_world(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  new_name: &str,
+  data_dir: &Path,
+  os: &OS,
+  running_games: &RunningGamesRegistry,
+  world_versions_repository: &dyn WorldVersionsRepository,
+) -> Result<(), ManageWorldError> {
+  let save_dir =
+    SaveDir::open(variant, data_profile, data_dir, running_games)?;
+  let world_dir = save_dir.existing_world(world).await?;
+  let new_world_dir = save_dir.new_world(new_name).await?;
+
+  let result = async {
+    copy_dir_all(&world_dir, &new_world_dir, os).await?;
+
+    if let Some(version) = world_versions_repository
+      .get_world_version(variant, data_profile, world)
+      .await?
+    {
+      world_versions_repository
+        .set_world_version(
+          variant,
+          data_profile,
+          &WorldVersion {
+            world: new_name.to_string(),
+            ..version
+          },
+        )
+        .await?;
+    }
+    Ok::<(), ManageWorldError>(())
+  }
+  .await;
+
+  if let Err(e) = result {
+    // Do not leave a partial copy that looks like a world.
+    let _ = fs::remove_dir_all(&new_world_dir).await;
+    return Err(e);
+  }
+
+  Ok(())
+}
+
+pub async fn rename_world(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  new_name: &str,
+  data_dir: &Path,
+  running_games: &RunningGamesRegistry,
+  world_versions_repository: &dyn WorldVersionsRepository,
+) -> Result<(), ManageWorldError> {
+  let save_dir =
+    SaveDir::open(variant, data_profile, data_dir, running_games)?;
+  let world_dir = save_dir.existing_world(world).await?;
+  let new_world_dir = save_dir.new_world(new_name).await?;
+
+  fs::rename(&world_dir, &new_world_dir).await?;
+  // The world must keep its protection against downgrades, so it is only
+  // renamed if its record can be moved along with it.
+  if let Err(e) = world_versions_repository
+    .rename_world_version(variant, data_profile, world, new_name)
+    .await
+  {
+    let _ = fs::rename(&new_world_dir, &world_dir).await;
+    return Err(e.into());
+  }
+  save_dir
+    .update_last_played_world(world, Some(new_name))
+    .await
+}
+
+/// Moves a world to the trash.
+pub async fn delete_world(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  data_dir: &Path,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), ManageWorldError> {
+  let save_dir =
+    SaveDir::open(variant, data_profile, data_dir, running_games)?;
+  let world_dir = save_dir.existing_world(world).await?;
+
+  let deleted_at =
+    SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  // The world keeps its name inside the entry, so that worlds with the
+  // same name can be deleted more than once.
+  let entry_dir = save_dir.trash_dir().join(format!(
+    "{}_{}",
+    deleted_at,
+    Uuid::new_v4().simple()
+  ));
+  fs::create_dir_all(&entry_dir).await?;
+
+  // The trash is on the same file system as the save directory, so the
+  // world is moved with a rename.
+  if let Err(e) = fs::rename(&world_dir, entry_dir.join(world)).await
+  {
+    let _ = fs::remove_dir(&entry_dir).await;
+    return Err(e.into());
+  }
+
+  save_dir.update_last_played_world(world, None).await
+}
+
+// Returns the world directory of a trash entry and its name.
+async fn read_trash_entry(
+  entry_dir: &Path,
+) -> io::Result<Option<(PathBuf, String)>> {
+  let mut entries = fs::read_dir(entry_dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    if entry.file_type().await?.is_dir() {
+      let name = entry.file_name().to_string_lossy().into_owned();
+      return Ok(Some((entry.path(), name)));
+    }
+  }
+
+  Ok(None)
+}
+
+async fn get_trash_entry(
+  trash_dir: &Path,
+  id: &str,
+) -> Result<(PathBuf, PathBuf, String), ManageWorldError> {
+  let not_found =
+    || ManageWorldError::DeletedWorldNotFound(id.to_string());
+
+  let entry_dir =
+    get_world_dir(trash_dir, id).map_err(|_| not_found())?;
+  if !fs::try_exists(&entry_dir).await? {
+    return Err(not_found());
+  }
+
+  let (world_dir, name) =
+    read_trash_entry(&entry_dir).await?.ok_or_else(not_found)?;
+  Ok((entry_dir, world_dir, name))
+}
+
+/// Lists the worlds in the trash, most recently deleted first.
+pub async fn list_deleted_worlds(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> Result<Vec<DeletedWorld>, ManageWorldError> {
+  let trash_dir =
+    get_world_trash_dir(variant, data_profile, data_dir);
+  let mut entries = match fs::read_dir(&trash_dir).await {
+    Ok(entries) => entries,
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      return Ok(Vec::new());
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  let mut deleted_worlds = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    let id = entry.file_name().to_string_lossy().into_owned();
+    let Some(deleted_at) = id
+      .split_once('_')
+      .and_then(|(deleted_at, _)| deleted_at.parse().ok())
+    else {
+      continue;
+    };
+    let Some((world_dir, name)) =
+      read_trash_entry(&entry.path()).await?
+    else {
+      continue;
+    };
+
+    deleted_worlds.push(DeletedWorld {
+      id,
+      name,
+      deleted_at,
+      size_bytes: get_dir_size(&world_dir).await?,
+    });
+  }
+
+  deleted_worlds.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+  Ok(deleted_worlds)
+}
+
+/// Moves a world out of the trash, under its original name unless
+/// `new_name` is given.
+pub async fn restore_deleted_world(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  id: &str,
+  new_name: Option<&str>,
+  data_dir: &Path,
+  running_games: &RunningGamesRegistry,
+) -> Result<(), ManageWorldError> {
+  let save_dir =
+    SaveDir::open(variant, data_profile, data_dir, running_games)?;
+  let (entry_dir, world_dir, name) =
+    get_trash_entry(&save_dir.trash_dir(), id).await?;
+  let new_world_dir =
+    save_dir.new_world(new_name.unwrap_or(&name)).await?;
+
+  fs::create_dir_all(&save_dir.save_dir).await?;
+  fs::rename(&world_dir, &new_world_dir).await?;
+  fs::remove_dir_all(&entry_dir).await?;
+
+  Ok(())
+}
+
+/// Permanently deletes a world in the trash.
+pub async fn purge_deleted_world(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  id: &str,
+  data_dir: &Path,
+) -> Result<(), ManageWorldError> {
+  let trash_dir =
+    get_world_trash_dir(variant, data_profile, data_dir);
+  let (entry_dir, _, _) = get_trash_entry(&trash_dir, id).await?;
+
+  fs::remove_dir_all(&entry_dir).await?;
+  Ok(())
+}
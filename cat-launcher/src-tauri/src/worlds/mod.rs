@@ -0,0 +1,5 @@
+#[allow(clippy::module_inception)]
+pub mod worlds;
+
+pub mod commands;
+pub mod management;
@@ -0,0 +1,309 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinError;
+use ts_rs::TS;
+use walkdir::WalkDir;
+
+use crate::backups::contents::decode_character_name;
+use crate::filesystem::paths::get_user_game_data_dir;
+use crate::filesystem::utils::join_relative_path;
+use crate::last_played_world::last_played_world::{
+  get_last_played_world, GetLastPlayedWorldError,
+};
+use crate::variants::GameVariant;
+
+// Turns have been one second long since 0.E.
+const TURNS_PER_DAY: u64 = 24 * 60 * 60;
+
+// The game writes the calendar fields first, so only the start of a
+// character save is read instead of the whole game state.
+const CHARACTER_SAVE_HEADER_LEN: u64 = 16 * 1024;
+
+static SAVE_TURN_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r#""turn"\s*:\s*(\d+)"#).unwrap());
+
+static SAVE_CALENDAR_START_RE: LazyLock<Regex> =
+  LazyLock::new(|| {
+    Regex::new(r#""calendar_start"\s*:\s*(\d+)"#).unwrap()
+  });
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct World {
+  pub name: String,
+  pub size_bytes: u64,
+  /// When any file of the world was last written.
+  pub modified_at: u64,
+  /// The ids of the mods the world was created with, in load order.
+  pub mods: Vec<String>,
+  pub characters: Vec<WorldCharacter>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorldCharacter {
+  pub name: String,
+  /// The name all of the character's save files start with.
+  pub save_prefix: String,
+  /// The in-game day, counted from the start of the cataclysm. None if
+  /// the save could not be read.
+  pub day: Option<u64>,
+  /// When the character was last saved.
+  pub last_played: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorldOption {
+  pub name: String,
+  pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export)]
+pub struct WorldDetails {
+  pub world: World,
+  /// The options from `worldoptions.json`, such as city size and spawn
+  /// rates.
+  pub options: Vec<WorldOption>,
+  pub file_count: u64,
+  pub is_last_played: bool,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum WorldsError {
+  #[error("invalid world name: {0}")]
+  InvalidWorld(String),
+
+  #[error("world {0} does not exist")]
+  WorldNotFound(String),
+
+  #[error("failed to get last played world: {0}")]
+  LastPlayedWorld(#[from] GetLastPlayedWorldError),
+
+  #[error("failed to walk world directory: {0}")]
+  Walk(#[from] walkdir::Error),
+
+  #[error("file IO operation failed: {0}")]
+  Io(#[from] io::Error),
+
+  #[error("unexpected join error: {0}")]
+  Join(#[from] JoinError),
+}
+
+#[derive(Deserialize)]
+struct RawWorldOption {
+  name: String,
+  value: serde_json::Value,
+}
+
+pub fn get_save_dir(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> PathBuf {
+  get_user_game_data_dir(variant, data_profile, data_dir).join("save")
+}
+
+/// Returns the directory of a world in `save_dir`, or an error if the name
+/// is not a plain directory name.
+pub fn get_world_dir(
+  save_dir: &Path,
+  world: &str,
+) -> Result<PathBuf, WorldsError> {
+  join_relative_path(save_dir, world)
+    .filter(|path| path.parent() == Some(save_dir))
+    .ok_or_else(|| WorldsError::InvalidWorld(world.to_string()))
+}
+
+fn to_timestamp(time: SystemTime) -> u64 {
+  time
+    .duration_since(UNIX_EPOCH)
+    .map(|duration| duration.as_secs())
+    .unwrap_or_default()
+}
+
+// Missing or unreadable metadata files are treated as empty, as worlds
+// from older versions of the game do not have all of them.
+pub(crate) fn read_mods(world_dir: &Path) -> Vec<String> {
+  fs::read_to_string(world_dir.join("mods.json"))
+    .ok()
+    .and_then(|content| serde_json::from_str(&content).ok())
+    .unwrap_or_default()
+}
+
+fn read_options(world_dir: &Path) -> Vec<WorldOption> {
+  let options: Vec<RawWorldOption> =
+    fs::read_to_string(world_dir.join("worldoptions.json"))
+      .ok()
+      .and_then(|content| serde_json::from_str(&content).ok())
+      .unwrap_or_default();
+
+  options
+    .into_iter()
+    .map(|option| WorldOption {
+      name: option.name,
+      value: match option.value {
+        serde_json::Value::String(value) => value,
+        value => value.to_string(),
+      },
+    })
+    .collect()
+}
+
+fn find_save_field(header: &str, re: &Regex) -> Option<u64> {
+  re.captures(header)?[1].parse().ok()
+}
+
+// Character saves start with a `# version` line, followed by the JSON
+// of the game state. The turn is counted from the start of the calendar,
+// so the start of the cataclysm is subtracted. Saves from before it could
+// be changed do not record it.
+fn read_character_day(save_path: &Path) -> Option<u64> {
+  let mut header = Vec::new();
+  fs::File::open(save_path)
+    .ok()?
+    .take(CHARACTER_SAVE_HEADER_LEN)
+    .read_to_end(&mut header)
+    .ok()?;
+  let header = String::from_utf8_lossy(&header);
+
+  let turn = find_save_field(&header, &SAVE_TURN_RE)?;
+  let calendar_start =
+    find_save_field(&header, &SAVE_CALENDAR_START_RE).unwrap_or(0);
+  Some(turn.saturating_sub(calendar_start) / TURNS_PER_DAY + 1)
+}
+
+struct WorldScan {
+  world: World,
+  file_count: u64,
+}
+
+fn scan_world(
+  world_dir: &Path,
+  name: &str,
+) -> Result<WorldScan, WorldsError> {
+  let mut size_bytes = 0;
+  let mut file_count = 0;
+  let mut modified_at = 0;
+
+  for entry in WalkDir::new(world_dir) {
+    let entry = entry?;
+    if !entry.file_type().is_file() {
+      continue;
+    }
+
+    let metadata = entry.metadata()?;
+    size_bytes += metadata.len();
+    file_count += 1;
+    modified_at = modified_at.max(to_timestamp(metadata.modified()?));
+  }
+
+  let mut characters = Vec::new();
+  for entry in fs::read_dir(world_dir)? {
+    let entry = entry?;
+    let file_name = entry.file_name().to_string_lossy().into_owned();
+    let Some(save_prefix) = file_name.strip_suffix(".sav") else {
+      continue;
+    };
+    if !entry.file_type()?.is_file() {
+      continue;
+    }
+
+    characters.push(WorldCharacter {
+      name: decode_character_name(save_prefix),
+      save_prefix: save_prefix.to_string(),
+      day: read_character_day(&entry.path()),
+      last_played: to_timestamp(entry.metadata()?.modified()?),
+    });
+  }
+  characters.sort_by(|a, b| b.last_played.cmp(&a.last_played));
+
+  Ok(WorldScan {
+    world: World {
+      name: name.to_string(),
+      size_bytes,
+      modified_at,
+      mods: read_mods(world_dir),
+      characters,
+    },
+    file_count,
+  })
+}
+
+/// Lists the worlds in the user data directory of a variant, most recently
+/// modified first. Worlds that cannot be read are skipped.
+pub async fn list_worlds(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  data_dir: &Path,
+) -> Result<Vec<World>, WorldsError> {
+  let save_dir = get_save_dir(variant, data_profile, data_dir);
+
+  tokio::task::spawn_blocking(move || {
+    let entries = match fs::read_dir(&save_dir) {
+      Ok(entries) => entries,
+      Err(e) if e.kind() == io::ErrorKind::NotFound => {
+        return Ok(Vec::new());
+      }
+      Err(e) => return Err(e.into()),
+    };
+
+    let mut worlds = Vec::new();
+    for entry in entries {
+      let entry = entry?;
+      if !entry.file_type().is_ok_and(|file_type| file_type.is_dir())
+      {
+        continue;
+      }
+
+      let name = entry.file_name().to_string_lossy().into_owned();
+      match scan_world(&entry.path(), &name) {
+        Ok(scan) => worlds.push(scan.world),
+        Err(e) => {
+          eprintln!("Skipping unreadable world {}: {}", name, e)
+        }
+      }
+    }
+
+    worlds.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
+    Ok(worlds)
+  })
+  .await?
+}
+
+pub async fn get_world_details(
+  variant: &GameVariant,
+  data_profile: Option<&str>,
+  world: &str,
+  data_dir: &Path,
+) -> Result<WorldDetails, WorldsError> {
+  let save_dir = get_save_dir(variant, data_profile, data_dir);
+  let world_dir = get_world_dir(&save_dir, world)?;
+  if !tokio::fs::try_exists(&world_dir).await? {
+    return Err(WorldsError::WorldNotFound(world.to_string()));
+  }
+
+  let name = world.to_string();
+  let (scan, options) = tokio::task::spawn_blocking(move || {
+    let scan = scan_world(&world_dir, &name)?;
+    Ok::<_, WorldsError>((scan, read_options(&world_dir)))
+  })
+  .await??;
+
+  let last_played_world =
+    get_last_played_world(data_dir, variant, data_profile).await?;
+
+  Ok(WorldDetails {
+    is_last_played: last_played_world.as_deref() == Some(world),
+    world: scan.world,
+    options,
+    file_count: scan.file_count,
+  })
+}
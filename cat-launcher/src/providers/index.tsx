@@ -7,7 +7,6 @@ import { Provider } from "react-redux";
 
 import AutoUpdateNotifier from "@/components/AutoUpdateNotifier";
 import GameSessionMonitor from "@/components/GameSessionMonitor";
-import PlayTimeMonitor from "@/components/PlayTimeMonitor";
 import { Toaster } from "@/components/ui/sonner";
 import { TooltipProvider } from "@/components/ui/tooltip";
 import { store } from "@/store/store";
@@ -38,7 +37,6 @@ export default function Providers({ children }: ProvidersProps) {
                   <Toaster />
                   <AutoUpdateNotifier />
                   <GameSessionMonitor />
-                  <PlayTimeMonitor />
                 </QuitConfirmationProvider>
               </TooltipProvider>
             </Provider>